@@ -0,0 +1,95 @@
+#![no_main]
+
+//! Selects with a full selector string, the way the Explorer search box does
+use dbt_node_selector::{
+    AccessType, Edge, ModelNode, Node, NodeSelector, NodeType, SourceNode, TestNode,
+};
+use libfuzzer_sys::fuzz_target;
+
+fn node(unique_id: &str, node_type: NodeType, tags: &[&str]) -> Node {
+    let name = unique_id.rsplit('.').next().unwrap().to_string();
+    Node {
+        unique_id: unique_id.to_string(),
+        name: name.clone(),
+        node_type,
+        package_name: "jaffle_shop".to_string(),
+        path: format!("{}.sql", name),
+        original_file_path: format!("models/{}.sql", name),
+        depends_on: vec![],
+        config: vec![("materialized".to_string(), "view".to_string())],
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+    }
+}
+
+fn model(name: &str, version: Option<&str>) -> NodeType {
+    NodeType::Model(ModelNode {
+        fqn: vec!["jaffle_shop".to_string(), name.to_string()],
+        depends_on: vec![],
+        raw_code: String::new(),
+        access: AccessType::Protected,
+        version: version.map(|v| v.to_string()),
+        latest_version: version.map(|_| "1".to_string()),
+    })
+}
+
+fn node_selector() -> NodeSelector {
+    let nodes = vec![
+        node(
+            "source.jaffle_shop.raw.orders",
+            NodeType::Source(SourceNode {
+                fqn: vec![
+                    "jaffle_shop".to_string(),
+                    "raw".to_string(),
+                    "orders".to_string(),
+                ],
+                raw_code: String::new(),
+            }),
+            &[],
+        ),
+        node(
+            "model.jaffle_shop.stg_orders",
+            model("stg_orders", None),
+            &["staging"],
+        ),
+        node(
+            "model.jaffle_shop.orders.v1",
+            model("orders", Some("1")),
+            &["mart"],
+        ),
+        node(
+            "test.jaffle_shop.not_null_orders",
+            NodeType::Test(TestNode {
+                fqn: vec!["jaffle_shop".to_string(), "not_null_orders".to_string()],
+                raw_code: String::new(),
+                test_metadata_name: Some("not_null".to_string()),
+            }),
+            &[],
+        ),
+    ];
+    let edges = [
+        ("source.jaffle_shop.raw.orders", vec![]),
+        (
+            "model.jaffle_shop.stg_orders",
+            vec!["source.jaffle_shop.raw.orders"],
+        ),
+        (
+            "model.jaffle_shop.orders.v1",
+            vec!["model.jaffle_shop.stg_orders"],
+        ),
+        (
+            "test.jaffle_shop.not_null_orders",
+            vec!["model.jaffle_shop.orders.v1"],
+        ),
+    ]
+    .into_iter()
+    .map(|(unique_id, parents)| Edge {
+        unique_id: unique_id.to_string(),
+        parents: parents.into_iter().map(|p| p.to_string()).collect(),
+    })
+    .collect();
+    NodeSelector::from(nodes, edges, None).unwrap()
+}
+
+fuzz_target!(|selector: &str| {
+    let _ = node_selector()._select(selector.to_string());
+});
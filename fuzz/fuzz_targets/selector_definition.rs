@@ -0,0 +1,28 @@
+#![no_main]
+
+//! Parses the mapping form of a YAML selector definition, e.g.
+//!
+//! ```yaml
+//! method: tag
+//! value: nightly
+//! children: true
+//! parents_depth: 2
+//! ```
+//!
+//! Each `key: value` line becomes an entry of the mapping.
+use dbt_node_selector::{IndirectSelection, SelectionCriteria};
+use indexmap::IndexMap;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|definition: &str| {
+    let index_map: IndexMap<String, String> = definition
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let _ = SelectionCriteria::selection_criteria_from_indexmap(
+        definition,
+        &index_map,
+        Some(IndirectSelection::default()),
+    );
+});
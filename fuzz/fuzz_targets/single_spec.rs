@@ -0,0 +1,8 @@
+#![no_main]
+
+use dbt_node_selector::{IndirectSelection, SelectionCriteria};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|raw: &str| {
+    let _ = SelectionCriteria::from_single_spec(raw, &IndirectSelection::default());
+});
@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod args_tests {
+    use std::collections::HashMap;
+
+    use crate::assert_err;
+
+    use super::super::*;
+
+    fn criteria(group: &SelectionGroup) -> &SelectionCriteria {
+        match &group.spec {
+            SelectionSpec::SelectionCriteria(criteria) => criteria,
+            spec => panic!("Expected a selection criteria, got {:?}", spec),
+        }
+    }
+
+    fn operation(group: &SelectionGroup) -> &SetOperation {
+        match &group.spec {
+            SelectionSpec::SetOperation(operation) => operation,
+            spec => panic!("Expected a set operation, got {:?}", spec),
+        }
+    }
+
+    #[test]
+    fn from_args_reads_every_argument() {
+        let args = HashMap::from([
+            ("select".to_string(), "a b+".to_string()),
+            ("exclude".to_string(), "c".to_string()),
+            ("indirect_selection".to_string(), "cautious".to_string()),
+        ]);
+        let args = from_args(args).unwrap();
+
+        assert_eq!(args.selector, None);
+        assert_eq!(args.select, Some(vec!["a b+".to_string()]));
+        assert_eq!(args.exclude, Some(vec!["c".to_string()]));
+        assert_eq!(args.indirect_selection, IndirectSelection::Cautious);
+        assert_eq!(args.syntax, SelectorSyntax::Dbt);
+
+        let args = HashMap::from([("extended_syntax".to_string(), "true".to_string())]);
+        assert_eq!(from_args(args).unwrap().syntax, SelectorSyntax::Extended);
+
+        let args = HashMap::from([("indirect_selection".to_string(), "lazy".to_string())]);
+        assert_err!(
+            from_args(args),
+            Err(SelectionError::InvalidIndirectSelectionError(_))
+        );
+        let args = HashMap::from([("extended_syntax".to_string(), "yes".to_string())]);
+        assert_err!(from_args(args), Err(SelectionError::BoolInputError(_)));
+    }
+
+    #[test]
+    fn parse_difference_defaults_to_every_node() {
+        let group = parse_difference(
+            &None,
+            &None,
+            IndirectSelection::Buildable,
+            SelectorSyntax::Dbt,
+        )
+        .unwrap();
+
+        assert_eq!(operation(&group), &SetOperation::Difference);
+        let [included, excluded] = &group.components[..] else {
+            panic!("Expected two components")
+        };
+        assert!(!included.components.iter().any(|group| group.expect_exists));
+        assert_eq!(included.indirect_selection, IndirectSelection::Buildable);
+        assert_eq!(included.components.len(), DEFAULT_INCLUDES.len());
+        assert!(excluded.components.is_empty());
+        assert_eq!(excluded.indirect_selection, IndirectSelection::Eager);
+    }
+
+    #[test]
+    fn parse_union_only_reads_between_with_the_extended_syntax() {
+        let selector = vec!["a..b,tag:c d".to_string()];
+        let group = parse_union(&selector, true, IndirectSelection::Eager).unwrap();
+        assert_eq!(criteria(&group.components[0].components[0]).value, "a..b");
+
+        let group = parse_union_with_syntax(
+            &selector,
+            true,
+            IndirectSelection::Eager,
+            SelectorSyntax::Extended,
+        )
+        .unwrap();
+        let [intersection, d] = &group.components[..] else {
+            panic!("Expected two components")
+        };
+        let [between, c] = &intersection.components[..] else {
+            panic!("Expected two components")
+        };
+        assert_eq!(between.raw, "a..b");
+        assert_eq!(operation(between), &SetOperation::Intersection);
+        assert_eq!(criteria(c).value, "c");
+        assert_eq!(criteria(&d.components[0]).value, "d");
+
+        // Syntax errors point into the whole selector
+        let err = parse_union_with_syntax(
+            &vec!["x a..+b".to_string()],
+            true,
+            IndirectSelection::Eager,
+            SelectorSyntax::Extended,
+        );
+        let Err(SelectionError::InvalidSyntax(diagnostic)) = err else {
+            panic!("Expected a syntax error, got {:?}", err)
+        };
+        assert_eq!((diagnostic.start, diagnostic.end), (5, 7));
+    }
+
+    #[test]
+    fn parse_difference_excludes_from_the_selection() {
+        let select = Some(vec!["a+".to_string()]);
+        let exclude = Some(vec!["b".to_string()]);
+        let group = parse_difference(
+            &select,
+            &exclude,
+            IndirectSelection::Eager,
+            SelectorSyntax::Dbt,
+        )
+        .unwrap();
+
+        assert_eq!(group.raw, "a+ --exclude b");
+        let [included, excluded] = &group.components[..] else {
+            panic!("Expected two components")
+        };
+        assert!(included.components.iter().all(|group| group.expect_exists));
+        assert_eq!(included.raw, "a+");
+        assert_eq!(excluded.raw, "b");
+    }
+}
@@ -0,0 +1,208 @@
+#[cfg(test)]
+#[path = "definition_tests.rs"]
+mod definition_tests;
+
+/// core/dbt/graph/cli.py: the selectors of `selectors.yml`, as the manifest
+/// holds them
+use indexmap::IndexMap;
+use serde_json::{Map, Value};
+
+use crate::{dbt_node_selector::SelectionError, selector::spec::*};
+
+/// A named selector from `selectors.yml`
+#[derive(Clone, Debug)]
+pub struct SelectorConfig {
+    pub name: String,
+    /// Whether the selector applies when no `--select` or `--exclude` is given
+    pub default: bool,
+    pub definition: SelectionGroup,
+}
+
+fn invalid_definition(message: impl Into<String>, definition: &Value) -> SelectionError {
+    SelectionError::InvalidSelector(format!("{}: {}", message.into(), definition))
+}
+
+/// The list under `key`, which may also be a single definition
+fn get_list_dicts<'a>(definition: &'a Value, key: &str) -> Result<Vec<&'a Value>, SelectionError> {
+    match &definition[key] {
+        Value::Array(values) => Ok(values.iter().collect()),
+        Value::Null => Err(invalid_definition(format!("Missing '{}'", key), definition)),
+        value => Ok(vec![value]),
+    }
+}
+
+fn parse_exclusions(
+    definition: &Value,
+    indirect_selection: IndirectSelection,
+) -> Result<SelectionGroup, SelectionError> {
+    let exclusions = get_list_dicts(definition, "exclude")?
+        .into_iter()
+        .map(|exclusion| parse_from_definition(exclusion, false, indirect_selection))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(SelectionGroup::union(
+        definition.to_string(),
+        exclusions,
+        indirect_selection,
+        false,
+    ))
+}
+
+/// Splits the definitions of a union or intersection into the included
+/// definitions and the single `exclude` definition
+fn parse_include_exclude_subdefs(
+    definitions: Vec<&Value>,
+    indirect_selection: IndirectSelection,
+) -> Result<(Vec<SelectionGroup>, Option<SelectionGroup>), SelectionError> {
+    let mut include = vec![];
+    let mut diff_arg = None;
+    for definition in definitions {
+        match definition.get("exclude") {
+            Some(_) if diff_arg.is_some() => {
+                return Err(invalid_definition(
+                    "You cannot provide multiple exclude arguments to the same selector set operator",
+                    definition,
+                ));
+            }
+            Some(_) => diff_arg = Some(parse_exclusions(definition, indirect_selection)?),
+            None => include.push(parse_from_definition(
+                definition,
+                false,
+                indirect_selection,
+            )?),
+        }
+    }
+    Ok((include, diff_arg))
+}
+
+fn parse_set_definition(
+    definition: &Value,
+    key: &str,
+    indirect_selection: IndirectSelection,
+) -> Result<SelectionGroup, SelectionError> {
+    let raw = definition.to_string();
+    let (include, exclude) =
+        parse_include_exclude_subdefs(get_list_dicts(definition, key)?, indirect_selection)?;
+    let combined = match key {
+        "union" => SelectionGroup::union(raw.clone(), include, indirect_selection, false),
+        _ => SelectionGroup::intersection(raw.clone(), include, indirect_selection, false),
+    };
+    Ok(match exclude {
+        None => combined,
+        Some(exclude) => {
+            SelectionGroup::difference(raw, vec![combined, exclude], indirect_selection, false)
+        }
+    })
+}
+
+/// The definition's values as strings, as `selection_criteria_from_indexmap` expects
+fn to_index_map(definition: &Map<String, Value>) -> IndexMap<String, String> {
+    definition
+        .iter()
+        .filter_map(|(key, value)| match value {
+            Value::String(value) => Some((key.clone(), value.clone())),
+            Value::Bool(value) => Some((key.clone(), value.to_string())),
+            Value::Number(value) => Some((key.clone(), value.to_string())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// `{"method": "tag", "value": "nightly", ...}`, or the shorthand `{"tag": "nightly"}`
+fn parse_dict_definition(
+    definition: &Map<String, Value>,
+    indirect_selection: IndirectSelection,
+) -> Result<SelectionGroup, SelectionError> {
+    let value = Value::Object(definition.clone());
+    let raw = value.to_string();
+    let diff_arg = match definition.get("exclude") {
+        Some(_) => Some(parse_exclusions(&value, indirect_selection)?),
+        None => None,
+    };
+    let criteria = match (definition.len(), definition.iter().next()) {
+        (1, Some((method, value))) => {
+            let shorthand = Map::from_iter([
+                ("method".to_string(), Value::String(method.clone())),
+                ("value".to_string(), value.clone()),
+            ]);
+            to_index_map(&shorthand)
+        }
+        _ if definition.contains_key("method") && definition.contains_key("value") => {
+            to_index_map(definition)
+        }
+        _ => {
+            return Err(invalid_definition(
+                "Expected either 1 key or else 'method' and 'value' keys",
+                &value,
+            ))
+        }
+    };
+    let criteria = SelectionCriteria::selection_criteria_from_indexmap(
+        raw.clone(),
+        &criteria,
+        Some(indirect_selection),
+    )?;
+    let criteria = SelectionGroup::from_criteria(criteria);
+    Ok(match diff_arg {
+        None => criteria,
+        Some(diff_arg) => {
+            SelectionGroup::difference(raw, vec![criteria, diff_arg], indirect_selection, false)
+        }
+    })
+}
+
+/// Parses a `selectors.yml` definition: a selector string, a criteria
+/// mapping, or a `union` or `intersection` of definitions
+pub fn parse_from_definition(
+    definition: &Value,
+    rootlevel: bool,
+    indirect_selection: IndirectSelection,
+) -> Result<SelectionGroup, SelectionError> {
+    let is_set_operation =
+        definition.get("union").is_some() || definition.get("intersection").is_some();
+    if rootlevel && is_set_operation && definition.as_object().map_or(0, Map::len) > 1 {
+        return Err(invalid_definition(
+            "Only a single 'union' or 'intersection' key is allowed in a root level selector definition",
+            definition,
+        ));
+    }
+    match definition {
+        Value::String(spec) => Ok(SelectionGroup::from_criteria(
+            SelectionCriteria::from_single_spec(spec, &indirect_selection)?,
+        )),
+        _ if definition.get("union").is_some() => {
+            parse_set_definition(definition, "union", indirect_selection)
+        }
+        _ if definition.get("intersection").is_some() => {
+            parse_set_definition(definition, "intersection", indirect_selection)
+        }
+        Value::Object(definition) => parse_dict_definition(definition, indirect_selection),
+        _ => Err(invalid_definition(
+            "Expected to find union, intersection, str or dict",
+            definition,
+        )),
+    }
+}
+
+/// Parses the `selectors` of a manifest, each being
+/// `{"definition": ..., "default": true}`
+pub fn parse_from_selectors_definition(
+    selectors: &Map<String, Value>,
+    indirect_selection: IndirectSelection,
+) -> Result<IndexMap<String, SelectorConfig>, SelectionError> {
+    let mut result = IndexMap::new();
+    for (name, selector) in selectors {
+        let definition = parse_from_definition(&selector["definition"], true, indirect_selection)?;
+        let config = SelectorConfig {
+            name: name.clone(),
+            default: selector["default"].as_bool().unwrap_or_default(),
+            definition,
+        };
+        result.insert(name.clone(), config);
+    }
+    if result.values().filter(|selector| selector.default).count() > 1 {
+        return Err(SelectionError::InvalidSelector(
+            "Found multiple selectors with `default: true`".to_string(),
+        ));
+    }
+    Ok(result)
+}
@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod definition_tests {
+    use indexmap::IndexMap;
+    use serde_json::json;
+
+    use crate::{
+        args::{InputArgs, SelectorConfig},
+        assert_err,
+        dbt_node_selector::SelectorSyntax,
+        selector::MethodName,
+    };
+
+    use super::super::*;
+
+    fn criteria(group: &SelectionGroup) -> &SelectionCriteria {
+        match &group.spec {
+            SelectionSpec::SelectionCriteria(criteria) => criteria,
+            spec => panic!("Expected a selection criteria, got {:?}", spec),
+        }
+    }
+
+    fn operation(group: &SelectionGroup) -> &SetOperation {
+        match &group.spec {
+            SelectionSpec::SetOperation(operation) => operation,
+            spec => panic!("Expected a set operation, got {:?}", spec),
+        }
+    }
+
+    fn selectors(definitions: Value) -> IndexMap<String, SelectorConfig> {
+        let definitions = definitions.as_object().unwrap();
+        parse_from_selectors_definition(definitions, IndirectSelection::Eager).unwrap()
+    }
+
+    #[test]
+    fn parse_from_definition_reads_strings_and_dicts() {
+        let group =
+            parse_from_definition(&json!("tag:nightly+"), true, IndirectSelection::Eager).unwrap();
+        assert_eq!(criteria(&group).method, MethodName::Tag);
+        assert!(criteria(&group).children);
+
+        let group =
+            parse_from_definition(&json!({"tag": "nightly"}), true, IndirectSelection::Eager)
+                .unwrap();
+        assert_eq!(criteria(&group).method, MethodName::Tag);
+        assert_eq!(criteria(&group).value, "nightly");
+
+        let definition = json!({
+            "method": "config.materialized",
+            "value": "view",
+            "parents": true,
+            "parents_depth": 2,
+            "indirect_selection": "cautious",
+        });
+        let group = parse_from_definition(&definition, true, IndirectSelection::Eager).unwrap();
+        let criteria = criteria(&group);
+        assert_eq!(criteria.method, MethodName::Config);
+        assert_eq!(criteria.method_arguments, vec!["materialized"]);
+        assert_eq!(criteria.value, "view");
+        assert!(criteria.parents);
+        assert_eq!(criteria.parents_depth, Some(2));
+        assert_eq!(criteria.indirect_selection, IndirectSelection::Cautious);
+    }
+
+    #[test]
+    fn parse_from_definition_reads_set_operations() {
+        let definition = json!({
+            "union": [
+                "a",
+                {"intersection": ["b", {"tag": "c"}]},
+                {"exclude": ["d", {"method": "tag", "value": "e"}]},
+            ]
+        });
+        let group = parse_from_definition(&definition, true, IndirectSelection::Eager).unwrap();
+
+        assert_eq!(operation(&group), &SetOperation::Difference);
+        let [union, exclude] = &group.components[..] else {
+            panic!("Expected two components")
+        };
+        assert_eq!(operation(union), &SetOperation::Union);
+        assert_eq!(union.components.len(), 2);
+        assert_eq!(operation(&union.components[1]), &SetOperation::Intersection);
+        assert_eq!(operation(exclude), &SetOperation::Union);
+        assert_eq!(exclude.components.len(), 2);
+    }
+
+    #[test]
+    fn parse_from_definition_rejects_invalid_definitions() {
+        let invalid = [
+            json!({"union": ["a"], "intersection": ["b"]}),
+            json!({"union": [{"exclude": ["a"]}, {"exclude": ["b"]}]}),
+            json!({"method": "tag", "parents": true}),
+            json!({"tag": "a", "value": "b"}),
+            json!(["a"]),
+            json!(1),
+        ];
+        for definition in invalid {
+            assert_err!(
+                parse_from_definition(&definition, true, IndirectSelection::Eager),
+                Err(SelectionError::InvalidSelector(_) | SelectionError::MissingValueError(_))
+            );
+        }
+    }
+
+    #[test]
+    fn get_selection_group_picks_the_selector() {
+        let selectors = selectors(json!({
+            "nightly": {"definition": "tag:nightly", "default": true},
+            "hourly": {"definition": "tag:hourly"},
+        }));
+        let args = |selector: Option<&str>, select: Option<&str>| InputArgs {
+            selector: selector.map(|s| s.to_string()),
+            select: select.map(|s| vec![s.to_string()]),
+            exclude: None,
+            indirect_selection: IndirectSelection::Eager,
+            syntax: SelectorSyntax::Dbt,
+        };
+
+        let group = SelectionGroup::get_selection_group(&args(None, None), &selectors).unwrap();
+        assert_eq!(criteria(&group).value, "nightly");
+
+        let group =
+            SelectionGroup::get_selection_group(&args(Some("hourly"), None), &selectors).unwrap();
+        assert_eq!(criteria(&group).value, "hourly");
+
+        let group =
+            SelectionGroup::get_selection_group(&args(None, Some("a")), &selectors).unwrap();
+        assert_eq!(operation(&group), &SetOperation::Difference);
+
+        assert_err!(
+            SelectionGroup::get_selection_group(&args(Some("weekly"), None), &selectors),
+            Err(SelectionError::InvalidSelector(_))
+        );
+    }
+
+    #[test]
+    fn only_one_selector_may_be_the_default() {
+        let definitions = json!({
+            "nightly": {"definition": "tag:nightly", "default": true},
+            "hourly": {"definition": "tag:hourly", "default": true},
+        });
+        assert_err!(
+            parse_from_selectors_definition(
+                definitions.as_object().unwrap(),
+                IndirectSelection::Eager
+            ),
+            Err(SelectionError::InvalidSelector(_))
+        );
+    }
+}
@@ -1,3 +1,13 @@
+#[cfg(test)]
+#[path = "args_tests.rs"]
+mod args_tests;
+
+#[cfg(feature = "cli")]
+mod definition;
+#[cfg(feature = "cli")]
+pub use definition::*;
+
+/// core/dbt/graph/cli.py
 use std::collections::{HashMap, HashSet};
 
 #[derive(Clone, Debug)]
@@ -10,6 +20,12 @@ pub struct InputArgs {
 
     /// List of exclusion selectors
     pub exclude: Option<Vec<String>>,
+
+    /// How tests are selected when only some of their parents are
+    pub indirect_selection: IndirectSelection,
+
+    /// Whether `select` and `exclude` may use operators dbt-core doesn't have
+    pub syntax: SelectorSyntax,
 }
 
 pub struct ParsedArgs {
@@ -23,33 +39,62 @@ pub struct ParsedArgs {
     pub exclude: Option<HashSet<UniqueId>>,
 }
 
+/// Nodes selected when there is no `--select`
+const DEFAULT_INCLUDES: &[&str] = &[
+    "fqn:*",
+    "source:*",
+    "exposure:*",
+    "metric:*",
+    "semantic_model:*",
+    "saved_query:*",
+    "unit_test:*",
+];
+const DEFAULT_EXCLUDES: &[&str] = &[];
+
 pub use String as ArgName;
 
 use crate::{
-    dbt_node_selector::{SelectionError, UniqueId},
+    dbt_node_selector::{SelectionError, SelectionError::*, SelectorSyntax, UniqueId},
     selector::spec::*,
 };
 
-fn parse_union(
+pub fn parse_union(
+    components: &Vec<String>,
+    expect_exists: bool,
+    indirect_selection: IndirectSelection,
+) -> Result<SelectionGroup, SelectionError> {
+    parse_union_with_syntax(
+        components,
+        expect_exists,
+        indirect_selection,
+        SelectorSyntax::Dbt,
+    )
+}
+
+pub fn parse_union_with_syntax(
     components: &Vec<String>,
     expect_exists: bool,
     indirect_selection: IndirectSelection,
+    syntax: SelectorSyntax,
 ) -> Result<SelectionGroup, SelectionError> {
     let INTERSECTION_DELIMITER = ",";
-    // Turn ['a b', 'c'] -> ['a', 'b', 'c']
-    let raw_specs = components.iter().flat_map(|r| r.split(" "));
+    // Turn ['a b', 'c'] -> ['a', 'b', 'c'], keeping each spec's offset in the
+    // joined selector so syntax errors point at the right place
+    let mut raw_specs: Vec<(usize, &str)> = vec![];
+    let mut component_offset = 0;
+    for component in components {
+        raw_specs.extend(split_with_offsets(component, " ", component_offset));
+        component_offset += component.len() + 1;
+    }
 
     let mut union_components: Vec<SelectionGroup> = vec![];
 
     // ['a', 'b', 'c,d'] -> union('a', 'b', intersection('c', 'd'))
-    for raw_spec in raw_specs {
-        let parts = raw_spec.split(INTERSECTION_DELIMITER);
-        let a = parts.map(|part| {
-            let selection_criteria =
-                SelectionCriteria::from_single_spec(part.to_string(), &indirect_selection);
-            selection_criteria.and_then(|selection_criteria| {
-                Ok(SelectionGroup::from_criteria(selection_criteria))
-            })
+    for (spec_offset, raw_spec) in raw_specs {
+        let parts = split_with_offsets(raw_spec, INTERSECTION_DELIMITER, spec_offset);
+        let a = parts.into_iter().map(|(offset, part)| {
+            SelectionGroup::from_spec(part, &indirect_selection, syntax)
+                .map_err(|err| err.offset(offset))
         });
         let intersection_components: Result<Vec<SelectionGroup>, SelectionError> = a.collect();
 
@@ -70,21 +115,72 @@ fn parse_union(
     ))
 }
 
+/// Splits `raw` on `delimiter`, pairing each part with its byte offset from
+/// the start of the selector, given that `raw` itself starts at `offset`.
+fn split_with_offsets<'a>(raw: &'a str, delimiter: &str, offset: usize) -> Vec<(usize, &'a str)> {
+    let mut parts = vec![];
+    let mut part_offset = offset;
+    for part in raw.split(delimiter) {
+        parts.push((part_offset, part));
+        part_offset += part.len() + delimiter.len();
+    }
+    parts
+}
+
 fn parse_union_from_default(
     raw: &Option<Vec<String>>,
-    default: &Vec<String>,
+    default: &[&str],
     indirect_selection: IndirectSelection,
+    syntax: SelectorSyntax,
 ) -> Result<SelectionGroup, SelectionError> {
     match raw {
-        Some(raw) => parse_union(raw, true, indirect_selection),
-        None => parse_union(default, false, indirect_selection),
+        Some(raw) => parse_union_with_syntax(raw, true, indirect_selection, syntax),
+        None => {
+            let default = default.iter().map(|s| s.to_string()).collect();
+            parse_union(&default, false, indirect_selection)
+        }
     }
 }
 
-pub fn from_args(args: HashMap<ArgName, String>) -> InputArgs {
-    let selector = args.get("selector");
-    let selection_arg = args.get("select");
-    let exclusion_arg = args.get("exclude");
+/// `--select` minus `--exclude`, each defaulting to `DEFAULT_INCLUDES` and
+/// `DEFAULT_EXCLUDES`. Exclusions are always eager, so excluding a model
+/// also excludes its tests.
+pub fn parse_difference(
+    include: &Option<Vec<String>>,
+    exclude: &Option<Vec<String>>,
+    indirect_selection: IndirectSelection,
+    syntax: SelectorSyntax,
+) -> Result<SelectionGroup, SelectionError> {
+    let included = parse_union_from_default(include, DEFAULT_INCLUDES, indirect_selection, syntax)?;
+    let excluded =
+        parse_union_from_default(exclude, DEFAULT_EXCLUDES, IndirectSelection::Eager, syntax)?;
+    let raw = match &excluded.raw[..] {
+        "" => included.raw.clone(),
+        exclusions => format!("{} --exclude {}", included.raw, exclusions),
+    };
+    Ok(SelectionGroup::difference(
+        raw,
+        vec![included, excluded],
+        indirect_selection,
+        false,
+    ))
+}
 
-    todo!()
+/// Reads the `selector`, `select`, `exclude`, `indirect_selection` and
+/// `extended_syntax` arguments. Like the CLI flags, `select` and `exclude`
+/// hold space separated selectors.
+pub fn from_args(args: HashMap<ArgName, String>) -> Result<InputArgs, SelectionError> {
+    let indirect_selection = IndirectSelection::from_string_option(args.get("indirect_selection"))?;
+    let syntax = match args.get("extended_syntax").map(|value| value.as_str()) {
+        None | Some("false") => SelectorSyntax::Dbt,
+        Some("true") => SelectorSyntax::Extended,
+        Some(_) => return Err(BoolInputError("extended_syntax".to_string())),
+    };
+    Ok(InputArgs {
+        selector: args.get("selector").cloned(),
+        select: args.get("select").map(|select| vec![select.clone()]),
+        exclude: args.get("exclude").map(|exclude| vec![exclude.clone()]),
+        indirect_selection: indirect_selection.unwrap_or_default(),
+        syntax,
+    })
 }
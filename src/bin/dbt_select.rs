@@ -0,0 +1,15 @@
+/// Lists the nodes of a dbt manifest which match a selection, like `dbt ls`.
+/// See `dbt-select --help`.
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut stdin = std::io::stdin().lock();
+    match dbt_node_selector::cli::run(&args, &mut stdin, &mut std::io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,210 @@
+#[cfg(test)]
+mod cli_tests {
+    use crate::assert_err;
+
+    use super::super::*;
+
+    const FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/jaffle_shop/manifest.json"
+    );
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn run_lines(cli_args: &[&str]) -> Result<Vec<String>, String> {
+        let mut cli_args = args(cli_args);
+        cli_args.extend(args(&["--manifest", FIXTURE]));
+        let mut out = vec![];
+        run(&cli_args, &mut &b""[..], &mut out)?;
+        let out = String::from_utf8(out).unwrap();
+        Ok(out.lines().map(|line| line.to_string()).collect())
+    }
+
+    #[test]
+    fn parse_reads_multiple_values() {
+        let cli_args = CliArgs::parse(&args(&[
+            "-s",
+            "a",
+            "b+",
+            "--exclude=c",
+            "--select",
+            "d",
+            "--resource-type",
+            "model",
+            "seed",
+            "-o",
+            "json",
+            "--state",
+            "previous.json",
+            "--lint",
+        ]))
+        .unwrap();
+
+        assert_eq!(cli_args.manifest, "target/manifest.json");
+        assert_eq!(cli_args.input_args.select, Some(args(&["a", "b+", "d"])));
+        assert_eq!(cli_args.input_args.exclude, Some(args(&["c"])));
+        assert_eq!(
+            cli_args.resource_types,
+            vec![NodeTypeKey::Model, NodeTypeKey::Seed]
+        );
+        assert_eq!(cli_args.output, Output::Json);
+        assert_eq!(cli_args.state, Some("previous.json".to_string()));
+        assert!(cli_args.lint);
+    }
+
+    #[test]
+    fn parse_rejects_invalid_arguments() {
+        let invalid: [&[&str]; 6] = [
+            &["--select"],
+            &["--output", "yaml"],
+            &["--manifest", "a.json", "b.json"],
+            &["--resource-type", "dashboard"],
+            &["--indirect-selection", "lazy"],
+            &["customers"],
+        ];
+        for invalid in invalid {
+            assert!(CliArgs::parse(&args(invalid)).is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn lists_unique_ids_sorted() {
+        let lines = run_lines(&["-s", "stg_orders+1", "--exclude", "orders"]).unwrap();
+        assert_eq!(
+            lines,
+            [
+                "model.jaffle_shop.order_items",
+                "model.jaffle_shop.stg_orders",
+                "test.jaffle_shop.not_null_stg_orders_order_id.81cfe2fe64",
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_every_node_but_analyses_by_default() {
+        let lines = run_lines(&[]).unwrap();
+        assert_eq!(lines.len(), 29);
+        assert!(!lines.iter().any(|line| line.starts_with("analysis.")));
+        assert!(!lines.iter().any(|line| line.starts_with("group.")));
+
+        let lines = run_lines(&["--resource-type", "all"]).unwrap();
+        assert_eq!(lines.len(), 30);
+    }
+
+    #[test]
+    fn filters_by_resource_type() {
+        let lines = run_lines(&["-s", "+orders", "--resource-type", "source", "seed"]).unwrap();
+        assert_eq!(
+            lines,
+            [
+                "source.jaffle_shop.ecom.raw_items",
+                "source.jaffle_shop.ecom.raw_orders",
+                "source.jaffle_shop.ecom.raw_payments",
+            ]
+        );
+    }
+
+    #[test]
+    fn between_needs_the_extended_syntax() {
+        let select = [
+            "-s",
+            "source:ecom.raw_orders..exposure:weekly_dashboard",
+            "--resource-type",
+            "model",
+        ];
+        let lines = run_lines(&[&select[..], &["--extended-syntax"]].concat()).unwrap();
+        assert_eq!(
+            lines,
+            [
+                "model.jaffle_shop.customers",
+                "model.jaffle_shop.orders",
+                "model.jaffle_shop.stg_orders",
+            ]
+        );
+
+        assert!(run_lines(&select).is_err());
+    }
+
+    #[test]
+    fn outputs_names_paths_and_json() {
+        let select = ["-s", "stg_orders"];
+        let lines = run_lines(&[&select[..], &["-o", "name"]].concat()).unwrap();
+        // Sorted by unique ID rather than name
+        assert_eq!(lines, ["stg_orders", "not_null_stg_orders_order_id"]);
+
+        let lines = run_lines(&[&select[..], &["-o", "path", "--resource-type", "model"]].concat())
+            .unwrap();
+        assert_eq!(lines, ["models/staging/stg_orders.sql"]);
+
+        let lines = run_lines(&[&select[..], &["-o", "json", "--resource-type", "model"]].concat())
+            .unwrap();
+        let node: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(node["unique_id"], "model.jaffle_shop.stg_orders");
+        assert_eq!(node["resource_type"], "model");
+        assert_eq!(node["tags"], serde_json::json!(["staging"]));
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_err!(run_lines(&["--selector", "nightly"]), Err(_));
+        assert_err!(run_lines(&["-s", "nonexistent:a"]), Err(_));
+        assert!(run(
+            &args(&["--manifest", "missing.json"]),
+            &mut &b""[..],
+            &mut vec![]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn lints_selectors() {
+        let mut manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(FIXTURE).unwrap()).unwrap();
+        manifest["selectors"] = serde_json::json!({
+            "staging": {"definition": {"union": ["stg_orders", "stg_orders+"]}},
+            "marts": {"definition": "orders customers"},
+        });
+        let path = std::env::temp_dir().join("dbt_select_lint_manifest.json");
+        std::fs::write(&path, manifest.to_string()).unwrap();
+        let lint = |cli_args: &[&str]| {
+            let mut cli_args = args(cli_args);
+            cli_args.extend(args(&["--lint", "--manifest", path.to_str().unwrap()]));
+            let mut out = vec![];
+            let result = run(&cli_args, &mut &b""[..], &mut out);
+            (result, String::from_utf8(out).unwrap())
+        };
+
+        let (result, out) = lint(&["-s", "state:new"]);
+        assert_eq!(result, Err("Found 2 selector warnings".to_string()));
+        assert_eq!(
+            out.lines().collect::<Vec<_>>(),
+            [
+                "staging: redundant-member: 'fqn:stg_orders' selects nothing 'fqn:stg_orders+' \
+                 doesn't (try 'fqn:stg_orders+')",
+                "--select: requires-previous-state: 'state:new' compares against a previous \
+                 state, but none is loaded",
+            ]
+        );
+
+        let (result, out) = lint(&["-s", "state:new", "--state", FIXTURE]);
+        assert_eq!(result, Err("Found 1 selector warnings".to_string()));
+        assert!(out.starts_with("staging: "));
+
+        manifest["selectors"] = serde_json::json!({
+            "marts": {"definition": "orders customers"},
+        });
+        std::fs::write(&path, manifest.to_string()).unwrap();
+        let (result, out) = lint(&[]);
+        assert_eq!(result, Ok(()));
+        assert_eq!(out, "No warnings in 1 selectors\n");
+    }
+
+    #[test]
+    fn prints_help() {
+        let mut out = vec![];
+        run(&args(&["--help"]), &mut &b""[..], &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), USAGE);
+    }
+}
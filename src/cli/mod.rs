@@ -0,0 +1,372 @@
+#[cfg(test)]
+#[path = "cli_tests.rs"]
+mod cli_tests;
+
+pub mod repl;
+
+/// `dbt-select`: lists the nodes of a manifest which match a selection, like
+/// `dbt ls` does, without a Python dbt install.
+///
+/// core/dbt/task/list.py
+use std::{
+    collections::HashSet,
+    io::{BufRead, Write},
+    rc::Rc,
+};
+
+use indexmap::IndexMap;
+use serde_json::json;
+
+use self::repl::Repl;
+use crate::{
+    args::{parse_difference, parse_from_selectors_definition, InputArgs, SelectorConfig},
+    dbt_node_selector::SelectorSyntax,
+    graph::{
+        node::{NodeTypeKey, WrapperNode, WrapperNodeExt},
+        UniqueId,
+    },
+    manifest::{Manifest, RunArtifacts},
+    selector::{
+        node_selector::{NodeSelector, PreviousState},
+        spec::{IndirectSelection, SelectionGroup},
+    },
+};
+
+pub const USAGE: &str = "\
+Lists the nodes of a dbt manifest which match a selection, like `dbt ls`
+
+Usage: dbt-select [OPTIONS]
+
+Options:
+  --manifest <PATH>              manifest.json, or a compressed ID map JSON [default: target/manifest.json]
+  -s, --select <SELECTOR>...     Nodes to include
+  --exclude <SELECTOR>...        Nodes to exclude
+  --selector <NAME>              A named selector of the project's selectors.yml
+  --resource-type <TYPE>...      Only list these resource types, `default` or `all` [default: default]
+  --indirect-selection <MODE>    eager, cautious, buildable or empty [default: eager]
+  --extended-syntax              Allow operators dbt doesn't have: `a..b` selects the nodes between a and b
+  --state <PATH>                 The manifest.json of a previous run, for `state:`, `result:` and `source_status:`
+  -o, --output <FORMAT>          unique_id, name, path or json [default: unique_id]
+  --repl                         Keep the manifest loaded and read selectors from stdin, see `:help`
+  --lint                         Check the project's selectors and `--select` for likely mistakes
+  -h, --help                     Print this help
+";
+
+/// Resource types listed without `--resource-type`. Analyses are only listed
+/// with `--resource-type all`, and groups never are.
+const DEFAULT_RESOURCE_TYPES: &[NodeTypeKey] = &[
+    NodeTypeKey::Model,
+    NodeTypeKey::Snapshot,
+    NodeTypeKey::Seed,
+    NodeTypeKey::Test,
+    NodeTypeKey::Source,
+    NodeTypeKey::Exposure,
+    NodeTypeKey::Metric,
+    NodeTypeKey::SavedQuery,
+    NodeTypeKey::SemanticModel,
+    NodeTypeKey::UnitTest,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Output {
+    UniqueId,
+    Name,
+    Path,
+    Json,
+}
+
+impl Output {
+    pub fn from_string(raw: &str) -> Result<Self, String> {
+        match raw {
+            "unique_id" => Ok(Self::UniqueId),
+            "name" => Ok(Self::Name),
+            "path" => Ok(Self::Path),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "Invalid output '{}', expected one of: unique_id, name, path, json",
+                raw
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CliArgs {
+    pub manifest: String,
+    pub input_args: InputArgs,
+    pub resource_types: Vec<NodeTypeKey>,
+    pub output: Output,
+    pub state: Option<String>,
+    pub repl: bool,
+    pub lint: bool,
+    pub help: bool,
+}
+
+fn parse_resource_types(values: &[String]) -> Result<Vec<NodeTypeKey>, String> {
+    let mut resource_types = vec![];
+    for value in values {
+        match value.as_str() {
+            "default" => resource_types.extend(DEFAULT_RESOURCE_TYPES.iter().cloned()),
+            "all" => {
+                resource_types.extend(DEFAULT_RESOURCE_TYPES.iter().cloned());
+                resource_types.push(NodeTypeKey::Analysis);
+            }
+            key => resource_types.push(
+                NodeTypeKey::from_key(key)
+                    .map_err(|_| format!("Invalid resource type '{}'", key))?,
+            ),
+        }
+    }
+    Ok(resource_types)
+}
+
+impl CliArgs {
+    /// Parses the arguments following the program name. Options take one
+    /// value, except for `--select`, `--exclude` and `--resource-type` which
+    /// take every value up to the next option, as in dbt.
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut manifest = "target/manifest.json".to_string();
+        let mut selector = None;
+        let mut select: Option<Vec<String>> = None;
+        let mut exclude: Option<Vec<String>> = None;
+        let mut resource_types: Vec<String> = vec![];
+        let mut indirect_selection = IndirectSelection::default();
+        let mut syntax = SelectorSyntax::Dbt;
+        let mut output = Output::UniqueId;
+        let mut state = None;
+        let mut repl = false;
+        let mut lint = false;
+        let mut help = false;
+
+        let mut args = args.iter().peekable();
+        while let Some(arg) = args.next() {
+            // `--option=value` is the same as `--option value`
+            let (option, inline_value) = match arg.split_once('=') {
+                Some((option, value)) if option.starts_with("--") => (option, Some(value)),
+                _ => (arg.as_str(), None),
+            };
+            let mut values = vec![];
+            match inline_value {
+                Some(value) => values.push(value.to_string()),
+                None => {
+                    while let Some(value) = args.next_if(|value| !value.starts_with('-')) {
+                        values.push(value.clone());
+                    }
+                }
+            }
+            let single_value = || match values.as_slice() {
+                [value] => Ok(value.clone()),
+                _ => Err(format!("'{}' takes exactly one value", option)),
+            };
+            let multiple_values = || match values.is_empty() {
+                true => Err(format!("'{}' takes at least one value", option)),
+                false => Ok(values.clone()),
+            };
+
+            match option {
+                "-h" | "--help" => help = true,
+                "--repl" => repl = true,
+                "--lint" => lint = true,
+                "--extended-syntax" => syntax = SelectorSyntax::Extended,
+                "--manifest" => manifest = single_value()?,
+                "--selector" => selector = Some(single_value()?),
+                "--state" => state = Some(single_value()?),
+                "-s" | "--select" | "-m" | "--models" => select
+                    .get_or_insert_with(Vec::new)
+                    .extend(multiple_values()?),
+                "--exclude" => exclude
+                    .get_or_insert_with(Vec::new)
+                    .extend(multiple_values()?),
+                "--resource-type" => resource_types.extend(multiple_values()?),
+                "--indirect-selection" => {
+                    indirect_selection = IndirectSelection::from_string(single_value()?)
+                        .map_err(|err| err.to_string())?
+                }
+                "-o" | "--output" => output = Output::from_string(&single_value()?)?,
+                _ => return Err(format!("Unexpected argument '{}'", arg)),
+            }
+        }
+
+        if resource_types.is_empty() {
+            resource_types.push("default".to_string());
+        }
+        Ok(CliArgs {
+            manifest,
+            input_args: InputArgs {
+                selector,
+                select,
+                exclude,
+                indirect_selection,
+                syntax,
+            },
+            resource_types: parse_resource_types(&resource_types)?,
+            output,
+            state,
+            repl,
+            lint,
+            help,
+        })
+    }
+}
+
+/// Loads a manifest, along with the selectors defined in its project
+pub fn load(
+    path: &str,
+    indirect_selection: IndirectSelection,
+) -> Result<(NodeSelector, IndexMap<String, SelectorConfig>), String> {
+    let manifest = Manifest::load(path)?;
+    let node_selector = NodeSelector::from(manifest.nodes, manifest.edges, None)
+        .map_err(|err| format!("Invalid manifest: {:?}", err))?;
+    let selectors = parse_from_selectors_definition(&manifest.selectors, indirect_selection)
+        .map_err(|err| err.to_string())?;
+    Ok((node_selector, selectors))
+}
+
+/// The previous state in the `manifest.json` at `state`, with the run results
+/// and source freshness next to it, and the source freshness next to the
+/// current `manifest` to compare against
+pub fn load_previous_state(
+    state: &str,
+    manifest: &str,
+    indirect_selection: IndirectSelection,
+) -> Result<PreviousState, String> {
+    let (previous, _) = load(state, indirect_selection)?;
+    let artifacts = RunArtifacts::load_beside(state)?;
+    let current_artifacts = RunArtifacts::load_beside(manifest)?;
+    Ok(PreviousState {
+        graph: Some(previous.graph),
+        run_results: artifacts.run_results,
+        sources: artifacts.sources,
+        sources_current: current_artifacts.sources,
+    })
+}
+
+pub fn format_node(output: Output, unique_id: &UniqueId, node: &WrapperNode) -> String {
+    match output {
+        Output::UniqueId => unique_id.to_string(),
+        Output::Name => node.name().to_string(),
+        Output::Path => node.original_file_path().to_string(),
+        Output::Json => {
+            let mut tags: Vec<&String> = node.tags().iter().collect();
+            tags.sort();
+            json!({
+                "name": node.name(),
+                "resource_type": node.resource_type().key().key(),
+                "package_name": node.package_name(),
+                "original_file_path": node.original_file_path(),
+                "unique_id": unique_id,
+                "tags": tags,
+            })
+            .to_string()
+        }
+    }
+}
+
+/// Writes the selected nodes of the given resource types sorted by unique
+/// ID, and returns how many were written
+pub fn write_nodes(
+    node_selector: &NodeSelector,
+    selected: &HashSet<UniqueId>,
+    resource_types: &[NodeTypeKey],
+    output: Output,
+    out: &mut impl Write,
+) -> Result<usize, String> {
+    let mut nodes: Vec<_> = selected
+        .iter()
+        .filter_map(|unique_id| node_selector.graph.node_map.get_key_value(unique_id))
+        .filter(|(_, node)| resource_types.contains(&node.resource_type().key()))
+        .collect();
+    nodes.sort_by_key(|(unique_id, _)| *unique_id);
+
+    for (unique_id, node) in nodes.iter() {
+        writeln!(out, "{}", format_node(output, unique_id, node)).map_err(|err| err.to_string())?;
+    }
+    Ok(nodes.len())
+}
+
+/// Writes the warnings of every selector of the project, and of `--select`
+/// and `--exclude` when given, failing when there are any so CI can use it
+pub fn lint(
+    node_selector: &NodeSelector,
+    selectors: &IndexMap<String, SelectorConfig>,
+    input_args: &InputArgs,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let mut definitions: Vec<(&str, SelectionGroup)> = selectors
+        .values()
+        .map(|selector| (selector.name.as_str(), selector.definition.clone()))
+        .collect();
+    if input_args.select.is_some() || input_args.exclude.is_some() {
+        let selection_group = parse_difference(
+            &input_args.select,
+            &input_args.exclude,
+            input_args.indirect_selection,
+            input_args.syntax,
+        )
+        .map_err(|err| err.to_string())?;
+        definitions.push(("--select", selection_group));
+    }
+
+    let has_previous_state = node_selector.previous_state().is_some();
+    let mut count = 0;
+    for (name, definition) in definitions.iter() {
+        for warning in definition.analyze(has_previous_state) {
+            count += 1;
+            let suggestion = match warning.suggestion {
+                Some(suggestion) => format!(" (try '{}')", suggestion),
+                None => "".to_string(),
+            };
+            writeln!(
+                out,
+                "{}: {}: {}{}",
+                name,
+                warning.kind.key(),
+                warning.message,
+                suggestion
+            )
+            .map_err(|err| err.to_string())?;
+        }
+    }
+    match count {
+        0 => writeln!(out, "No warnings in {} selectors", definitions.len())
+            .map_err(|err| err.to_string()),
+        _ => Err(format!("Found {} selector warnings", count)),
+    }
+}
+
+/// Runs `dbt-select` with the arguments following the program name, writing
+/// the selected nodes to `out` sorted by unique ID. `input` is only read by
+/// `--repl`.
+pub fn run(args: &[String], input: &mut impl BufRead, out: &mut impl Write) -> Result<(), String> {
+    let args = CliArgs::parse(args)?;
+    if args.help {
+        return write!(out, "{}", USAGE).map_err(|err| err.to_string());
+    }
+
+    let (mut node_selector, selectors) = load(&args.manifest, args.input_args.indirect_selection)?;
+    if let Some(state) = &args.state {
+        let previous_state =
+            load_previous_state(state, &args.manifest, args.input_args.indirect_selection)?;
+        node_selector.set_previous_state(Some(Rc::new(previous_state)));
+    }
+    if args.lint {
+        return lint(&node_selector, &selectors, &args.input_args, out);
+    }
+    if args.repl {
+        return Repl::new(node_selector, selectors, &args).run(input, out);
+    }
+
+    let selection_group = SelectionGroup::get_selection_group(&args.input_args, &selectors)
+        .map_err(|err| err.to_string())?;
+    let selected = node_selector
+        .get_selected(&selection_group)
+        .map_err(|err| err.to_string())?;
+    write_nodes(
+        &node_selector,
+        &selected,
+        &args.resource_types,
+        args.output,
+        out,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,284 @@
+#[cfg(test)]
+#[path = "repl_tests.rs"]
+mod repl_tests;
+
+/// `dbt-select --repl`: keeps a manifest loaded in memory so selectors can
+/// be tried out one after another without parsing the manifest each time.
+use std::{
+    collections::HashSet,
+    io::{BufRead, Write},
+    rc::Rc,
+    time::Instant,
+};
+
+use indexmap::IndexMap;
+
+use crate::{
+    args::{parse_union_with_syntax, InputArgs, SelectorConfig},
+    dbt_node_selector::{
+        IndirectSelectionMode, SelectionReason, SelectionVia, SelectorSyntax, SetOperationKind,
+    },
+    graph::{
+        node::{NodeTypeKey, WrapperNodeExt},
+        UniqueId,
+    },
+    selector::{
+        node_selector::NodeSelector,
+        spec::{IndirectSelection, SelectionGroup},
+    },
+};
+
+use super::{load_previous_state, write_nodes, CliArgs, Output};
+
+pub const HELP: &str = "\
+Enter selectors as given to --select, optionally followed by `--exclude <SELECTOR>...`,
+or `--selector <NAME>`. Commands:
+  :explain <SELECTOR>...     Why each node is selected
+  :parents <ID> [DEPTH]      Ancestors of a node
+  :children <ID> [DEPTH]     Descendants of a node
+  :path <ID> <ID>            A shortest path from the first node to the second
+  :diff <SELECTOR> <SELECTOR>
+                             Nodes selected by only one of the selectors
+  :load-state <PATH>         Load a previous manifest for the state:, result: and source_status: methods
+  :help                      Print this help
+  :quit                      Exit
+";
+
+pub struct Repl {
+    node_selector: NodeSelector,
+    /// The path of the loaded manifest, whose source freshness `:load-state` reads
+    manifest: String,
+    selectors: IndexMap<String, SelectorConfig>,
+    indirect_selection: IndirectSelection,
+    syntax: SelectorSyntax,
+    resource_types: Vec<NodeTypeKey>,
+    output: Output,
+}
+
+fn parse_depth(depth: Option<&&str>) -> Result<Option<usize>, String> {
+    depth
+        .map(|depth| {
+            depth
+                .parse()
+                .map_err(|_| format!("Invalid depth '{}'", depth))
+        })
+        .transpose()
+}
+
+fn format_reason(reason: &SelectionReason) -> String {
+    let via = match &reason.via {
+        SelectionVia::Direct => "matched",
+        SelectionVia::Parents => "parent of a match",
+        SelectionVia::Children => "child of a match",
+        SelectionVia::ChildrensParents => "parent of a match's children",
+        SelectionVia::Indirect(IndirectSelectionMode::Eager) => "indirect (eager)",
+        SelectionVia::Indirect(IndirectSelectionMode::Cautious) => "indirect (cautious)",
+        SelectionVia::Indirect(IndirectSelectionMode::Buildable) => "indirect (buildable)",
+        SelectionVia::Indirect(IndirectSelectionMode::Empty) => "indirect (empty)",
+    };
+    let mut line = format!("{}: {}", reason.criteria, via);
+    for step in reason.set_operations.iter() {
+        let operation = match step.operation {
+            SetOperationKind::Intersection => "intersection",
+            SetOperationKind::Difference => "difference",
+            SetOperationKind::Union => "union",
+        };
+        line.push_str(&format!(", kept by the {} '{}'", operation, step.raw));
+    }
+    line
+}
+
+impl Repl {
+    pub fn new(
+        node_selector: NodeSelector,
+        selectors: IndexMap<String, SelectorConfig>,
+        args: &CliArgs,
+    ) -> Self {
+        Self {
+            node_selector,
+            manifest: args.manifest.clone(),
+            selectors,
+            indirect_selection: args.input_args.indirect_selection,
+            syntax: args.input_args.syntax,
+            resource_types: args.resource_types.clone(),
+            output: args.output,
+        }
+    }
+
+    /// Reads lines from `input` until it ends or `:quit`. Errors are written
+    /// to `out` and don't end the session.
+    pub fn run(&mut self, input: &mut impl BufRead, out: &mut impl Write) -> Result<(), String> {
+        let mut line = String::new();
+        loop {
+            write!(out, "> ").map_err(|err| err.to_string())?;
+            out.flush().map_err(|err| err.to_string())?;
+            line.clear();
+            if input.read_line(&mut line).map_err(|err| err.to_string())? == 0 {
+                return Ok(());
+            }
+            match self.eval(&line, out) {
+                Ok(true) => (),
+                Ok(false) => return Ok(()),
+                Err(err) => writeln!(out, "error: {}", err).map_err(|err| err.to_string())?,
+            }
+        }
+    }
+
+    /// Evaluates a single line, returning false when the session should end
+    pub fn eval(&mut self, line: &str, out: &mut impl Write) -> Result<bool, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let start = Instant::now();
+        let count = match words.as_slice() {
+            [] => return Ok(true),
+            [":quit" | ":q" | ":exit"] => return Ok(false),
+            [":help" | ":h"] => {
+                write!(out, "{}", HELP).map_err(|err| err.to_string())?;
+                return Ok(true);
+            }
+            [":explain", selectors @ ..] if !selectors.is_empty() => {
+                self.explain(selectors, out)?
+            }
+            [command @ (":parents" | ":children"), unique_id, depth @ ..] if depth.len() <= 1 => {
+                let selected = HashSet::from([unique_id.to_string()]);
+                let depth = parse_depth(depth.first())?;
+                let graph = &self.node_selector.graph;
+                let nodes = match *command {
+                    ":parents" => graph.select_parents(&selected, &depth),
+                    _ => graph.select_children(&selected, &depth),
+                }
+                .map_err(|err| err.to_string())?;
+                self.write_nodes(&nodes, out)?
+            }
+            [":path", from, to] => {
+                let path = self
+                    .node_selector
+                    .graph
+                    .shortest_path(&from.to_string(), &to.to_string())
+                    .map_err(|err| err.to_string())?;
+                match path.is_empty() {
+                    true => writeln!(out, "No path from {} to {}", from, to),
+                    false => writeln!(out, "{}", path.join(" -> ")),
+                }
+                .map_err(|err| err.to_string())?;
+                path.len()
+            }
+            [":diff", left, right] => self.diff(left, right, out)?,
+            [":load-state", path] => {
+                let previous_state =
+                    load_previous_state(path, &self.manifest, self.indirect_selection)?;
+                let count = previous_state
+                    .graph
+                    .as_ref()
+                    .map_or(0, |graph| graph.node_map.len());
+                self.node_selector
+                    .set_previous_state(Some(Rc::new(previous_state)));
+                count
+            }
+            [command, ..] if command.starts_with(':') => {
+                return Err(format!("Invalid command '{}', see :help", line.trim()))
+            }
+            _ => {
+                let selected = self.select(&words)?;
+                self.write_nodes(&selected, out)?
+            }
+        };
+        writeln!(out, "({} nodes in {:.2?})", count, start.elapsed())
+            .map_err(|err| err.to_string())?;
+        Ok(true)
+    }
+
+    /// Selects nodes like `dbt-select` would with the given words as
+    /// arguments, where the leading `--select` is implied
+    fn select(&self, words: &[&str]) -> Result<HashSet<UniqueId>, String> {
+        let mut input_args = InputArgs {
+            selector: None,
+            select: None,
+            exclude: None,
+            indirect_selection: self.indirect_selection,
+            syntax: self.syntax,
+        };
+        let mut values = &mut input_args.select;
+        let mut words = words.iter();
+        while let Some(word) = words.next() {
+            match *word {
+                "--select" | "-s" => values = &mut input_args.select,
+                "--exclude" => values = &mut input_args.exclude,
+                "--selector" => {
+                    let name = words.next().ok_or("'--selector' takes exactly one value")?;
+                    input_args.selector = Some(name.to_string());
+                }
+                word => values.get_or_insert_with(Vec::new).push(word.to_string()),
+            }
+        }
+
+        let selection_group = SelectionGroup::get_selection_group(&input_args, &self.selectors)
+            .map_err(|err| err.to_string())?;
+        self.node_selector
+            .get_selected(&selection_group)
+            .map_err(|err| err.to_string())
+    }
+
+    fn write_nodes(
+        &self,
+        selected: &HashSet<UniqueId>,
+        out: &mut impl Write,
+    ) -> Result<usize, String> {
+        write_nodes(
+            &self.node_selector,
+            selected,
+            &self.resource_types,
+            self.output,
+            out,
+        )
+    }
+
+    fn has_resource_type(&self, unique_id: &UniqueId) -> bool {
+        self.node_selector
+            .graph
+            .node_map
+            .get(unique_id)
+            .is_some_and(|node| self.resource_types.contains(&node.resource_type().key()))
+    }
+
+    fn explain(&self, selectors: &[&str], out: &mut impl Write) -> Result<usize, String> {
+        let selectors = selectors
+            .iter()
+            .map(|selector| selector.to_string())
+            .collect();
+        let selection_group =
+            parse_union_with_syntax(&selectors, true, self.indirect_selection, self.syntax)
+                .map_err(|err| err.to_string())?;
+        let explanations: Vec<_> = self
+            .node_selector
+            .explain_selected(&selection_group)
+            .map_err(|err| err.to_string())?
+            .into_iter()
+            .filter(|explanation| self.has_resource_type(&explanation.unique_id))
+            .collect();
+        for explanation in explanations.iter() {
+            writeln!(out, "{}", explanation.unique_id).map_err(|err| err.to_string())?;
+            for reason in explanation.reasons.iter() {
+                writeln!(out, "    {}", format_reason(reason)).map_err(|err| err.to_string())?;
+            }
+        }
+        Ok(explanations.len())
+    }
+
+    /// Writes the nodes only selected by `left` prefixed with `-`, and those
+    /// only selected by `right` prefixed with `+`
+    fn diff(&self, left: &str, right: &str, out: &mut impl Write) -> Result<usize, String> {
+        let left = self.select(&[left])?;
+        let right = self.select(&[right])?;
+        let mut lines: Vec<(&UniqueId, char)> = left
+            .difference(&right)
+            .map(|unique_id| (unique_id, '-'))
+            .chain(right.difference(&left).map(|unique_id| (unique_id, '+')))
+            .filter(|(unique_id, _)| self.has_resource_type(unique_id))
+            .collect();
+        lines.sort();
+        for (unique_id, sign) in lines.iter() {
+            writeln!(out, "{} {}", sign, unique_id).map_err(|err| err.to_string())?;
+        }
+        Ok(lines.len())
+    }
+}
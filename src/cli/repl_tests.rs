@@ -0,0 +1,207 @@
+#[cfg(test)]
+mod repl_tests {
+    use crate::cli::{load, CliArgs};
+
+    use super::super::*;
+
+    const FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/jaffle_shop/manifest.json"
+    );
+
+    fn new_repl(cli_args: &[&str]) -> Repl {
+        let cli_args: Vec<String> = cli_args.iter().map(|arg| arg.to_string()).collect();
+        let cli_args = CliArgs::parse(&cli_args).unwrap();
+        let (node_selector, selectors) =
+            load(FIXTURE, cli_args.input_args.indirect_selection).unwrap();
+        Repl::new(node_selector, selectors, &cli_args)
+    }
+
+    /// Evaluates a line, returning the lines written without the timing
+    fn eval(repl: &mut Repl, line: &str) -> Result<Vec<String>, String> {
+        let mut out = vec![];
+        assert!(repl.eval(line, &mut out)?);
+        let out = String::from_utf8(out).unwrap();
+        let mut lines: Vec<String> = out.lines().map(|line| line.to_string()).collect();
+        let timing = lines.pop().unwrap();
+        assert!(
+            timing.starts_with('(') && timing.ends_with(')'),
+            "{}",
+            timing
+        );
+        Ok(lines)
+    }
+
+    #[test]
+    fn evaluates_selectors_with_timing() {
+        let mut repl = new_repl(&["--resource-type", "model"]);
+        let mut out = vec![];
+        repl.eval("stg_orders+1 --exclude orders", &mut out)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[..2],
+            [
+                "model.jaffle_shop.order_items",
+                "model.jaffle_shop.stg_orders"
+            ]
+        );
+        assert!(lines[2].starts_with("(2 nodes in "), "{}", lines[2]);
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[test]
+    fn reports_errors() {
+        let mut repl = new_repl(&[]);
+        assert!(eval(&mut repl, "--selector nightly")
+            .unwrap_err()
+            .starts_with("Could not find selector named 'nightly'"));
+        assert!(eval(&mut repl, "nonexistent:a").is_err());
+        assert!(eval(&mut repl, ":diff a").is_err());
+        assert!(eval(&mut repl, ":load-state missing.json").is_err());
+    }
+
+    #[test]
+    fn lists_parents_and_children() {
+        let mut repl = new_repl(&["-o", "name"]);
+        assert_eq!(
+            eval(&mut repl, ":parents model.jaffle_shop.stg_orders").unwrap(),
+            ["raw_orders"]
+        );
+        assert_eq!(
+            eval(&mut repl, ":children model.jaffle_shop.customers 1").unwrap(),
+            [
+                "weekly_dashboard",
+                "campaign_orders",
+                "dim_customers",
+                "dim_customers",
+                "dim_customers",
+                "relationships_customers_customer_id__customer_id__ref_stg_customers_",
+            ]
+        );
+        assert!(eval(&mut repl, ":children model.jaffle_shop.customers two").is_err());
+        assert!(eval(&mut repl, ":parents model.jaffle_shop.missing").is_err());
+    }
+
+    #[test]
+    fn prints_paths() {
+        let mut repl = new_repl(&[]);
+        assert_eq!(
+            eval(
+                &mut repl,
+                ":path source.jaffle_shop.ecom.raw_orders exposure.jaffle_shop.weekly_dashboard"
+            )
+            .unwrap(),
+            ["source.jaffle_shop.ecom.raw_orders -> model.jaffle_shop.stg_orders -> model.jaffle_shop.orders -> exposure.jaffle_shop.weekly_dashboard"]
+        );
+        assert_eq!(
+            eval(
+                &mut repl,
+                ":path model.jaffle_shop.orders model.jaffle_shop.stg_orders"
+            )
+            .unwrap(),
+            ["No path from model.jaffle_shop.orders to model.jaffle_shop.stg_orders"]
+        );
+    }
+
+    #[test]
+    fn diffs_selections() {
+        let mut repl = new_repl(&["--resource-type", "model"]);
+        assert_eq!(
+            eval(&mut repl, ":diff stg_payments+1 stg_orders+1").unwrap(),
+            [
+                "+ model.jaffle_shop.order_items",
+                "+ model.jaffle_shop.stg_orders",
+                "- model.jaffle_shop.stg_payments",
+            ]
+        );
+        assert_eq!(
+            eval(&mut repl, ":diff stg_orders stg_orders").unwrap(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn explains_set_operations() {
+        let mut repl = new_repl(&["--resource-type", "model"]);
+        let kept = ", kept by the intersection 'stg_payments+1,resource_type:model'";
+        assert_eq!(
+            eval(&mut repl, ":explain stg_payments+1,resource_type:model").unwrap(),
+            [
+                "model.jaffle_shop.orders".to_string(),
+                format!("    stg_payments+1: child of a match{}", kept),
+                format!("    resource_type:model: matched{}", kept),
+                "model.jaffle_shop.stg_payments".to_string(),
+                format!("    stg_payments+1: matched{}", kept),
+                format!("    resource_type:model: matched{}", kept),
+            ]
+        );
+    }
+
+    #[test]
+    fn explains_selections() {
+        let mut repl = new_repl(&[]);
+        assert_eq!(
+            eval(&mut repl, ":explain +stg_orders").unwrap(),
+            [
+                "model.jaffle_shop.stg_orders",
+                "    +stg_orders: matched",
+                "source.jaffle_shop.ecom.raw_orders",
+                "    +stg_orders: parent of a match",
+                "test.jaffle_shop.not_null_stg_orders_order_id.81cfe2fe64",
+                "    +stg_orders: indirect (eager)",
+                "test.jaffle_shop.source_not_null_ecom_raw_orders_id.0f8d47e1d2",
+                "    +stg_orders: indirect (eager)",
+            ]
+        );
+    }
+
+    #[test]
+    fn loads_previous_state() {
+        let mut repl = new_repl(&[]);
+        assert!(eval(&mut repl, "state:new")
+            .unwrap_err()
+            .contains("No previous state"));
+        assert_eq!(
+            eval(&mut repl, &format!(":load-state {}", FIXTURE)).unwrap(),
+            Vec::<String>::new()
+        );
+        assert!(eval(&mut repl, "state:new")
+            .unwrap_err()
+            .contains("does not match any nodes"));
+
+        // A previous state where only the staging models existed
+        let previous = std::env::temp_dir().join("dbt_select_repl_previous_state.json");
+        std::fs::write(
+            &previous,
+            r#"{"ids": ["model.jaffle_shop.stg_orders", "model.jaffle_shop.stg_payments"], "children": [[], []]}"#,
+        )
+        .unwrap();
+        let mut repl = new_repl(&["--resource-type", "model"]);
+        assert_eq!(
+            eval(&mut repl, &format!(":load-state {}", previous.display())).unwrap(),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            eval(&mut repl, "state:new,stg_orders+1").unwrap(),
+            ["model.jaffle_shop.order_items", "model.jaffle_shop.orders"]
+        );
+    }
+
+    #[test]
+    fn runs_until_quit() {
+        let mut repl = new_repl(&["--resource-type", "seed"]);
+        let mut input = &b"\n:unknown\nresource_type:seed\n:quit\nresource_type:seed\n"[..];
+        let mut out = vec![];
+        repl.run(&mut input, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "> > error: Invalid command ':unknown', see :help");
+        assert_eq!(lines[1], "> seed.jaffle_shop.country_codes");
+        assert!(lines[2].starts_with("(1 nodes in "), "{}", lines[2]);
+        // Nothing is evaluated after `:quit`
+        assert_eq!(lines[3], "> ");
+    }
+}
@@ -0,0 +1,92 @@
+#[cfg(test)]
+#[path = "access_tests.rs"]
+mod access_tests;
+
+/// Model governance: which nodes may reference a model, given its access level.
+/// https://docs.getdbt.com/docs/collaborate/govern/model-access
+use crate::{
+    dbt_node_selector::{AccessViolation, AccessViolationKind},
+    selector::AccessType,
+};
+
+use super::{
+    node::{NodeTypeKey, WrapperNodeExt},
+    parsed_graph::ParsedGraph,
+    UniqueId,
+};
+
+impl ParsedGraph {
+    /// Why referencing `model_id` from `unique_id` is forbidden, if it is.
+    /// Private models may only be referenced from their own group, so a
+    /// private model without a group can't be referenced at all. Like dbt,
+    /// tests are exempt, as they check the model rather than build on it.
+    fn access_violation(
+        &self,
+        unique_id: &UniqueId,
+        model_id: &UniqueId,
+    ) -> Option<AccessViolation> {
+        let node = self.node_map.get(unique_id)?;
+        if matches!(
+            node.resource_type().key(),
+            NodeTypeKey::Test | NodeTypeKey::UnitTest
+        ) {
+            return None;
+        }
+        let model = self.node_map.get(model_id)?;
+        let access = AccessType::from(model.resource_type().access()?);
+        let (kind, message) = match access {
+            AccessType::Public => return None,
+            AccessType::Private => {
+                let model_group = self.group_of(model_id);
+                if model_group.is_some() && model_group == self.group_of(unique_id) {
+                    return None;
+                }
+                let message = match model_group {
+                    Some(group) => format!(
+                        "'{}' references '{}', which is private to the '{}' group",
+                        unique_id, model_id, group
+                    ),
+                    None => format!(
+                        "'{}' references '{}', which is private but not in any group",
+                        unique_id, model_id
+                    ),
+                };
+                (AccessViolationKind::PrivateOutsideGroup, message)
+            }
+            AccessType::Protected => {
+                if node.package_name() == model.package_name() {
+                    return None;
+                }
+                let message = format!(
+                    "'{}' references '{}', which is protected to the '{}' package",
+                    unique_id,
+                    model_id,
+                    model.package_name()
+                );
+                (AccessViolationKind::ProtectedOutsidePackage, message)
+            }
+        };
+        Some(AccessViolation {
+            unique_id: unique_id.clone(),
+            model_id: model_id.clone(),
+            kind,
+            message,
+        })
+    }
+
+    /// Every reference to a model which its access level forbids, ordered by
+    /// the referencing node and then the model
+    pub fn access_violations(&self) -> Vec<AccessViolation> {
+        let mut violations: Vec<AccessViolation> = self
+            .parents_map
+            .iter()
+            .flat_map(|(unique_id, parents)| {
+                parents
+                    .iter()
+                    .filter_map(move |model_id| self.access_violation(unique_id, model_id))
+            })
+            .collect();
+        violations.sort_by(|a, b| (&a.unique_id, &a.model_id).cmp(&(&b.unique_id, &b.model_id)));
+        violations
+    }
+}
@@ -0,0 +1,179 @@
+#[cfg(test)]
+mod access_tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        dbt_node_selector::{AccessType, AccessViolationKind, ModelNode, NodeType, SelectionError},
+        selector::MethodName,
+        util::test::{get_unit_test_node, to_config, vec_to_set, GraphBuilder},
+    };
+
+    use super::super::*;
+
+    fn get_model(name: &str, access: AccessType) -> NodeType {
+        NodeType::Model(ModelNode {
+            fqn: vec!["pkg".to_string(), name.to_string()],
+            depends_on: vec![],
+            raw_code: "".to_string(),
+            access,
+            version: None,
+            latest_version: None,
+        })
+    }
+
+    /// (unique_id, access, package, group, parents)
+    type ModelSpec<'a> = (&'a str, AccessType, &'a str, Option<&'a str>, Vec<&'a str>);
+
+    fn get_graph(nodes: Vec<ModelSpec>) -> ParsedGraph {
+        nodes
+            .into_iter()
+            .fold(
+                GraphBuilder::new(),
+                |builder, (unique_id, access, package_name, group, parents)| {
+                    builder.node_with(unique_id, &parents, |node| {
+                        node.package_name = package_name.to_string();
+                        node.node_type = get_model(unique_id, access);
+                        if let Some(group) = group {
+                            node.config = to_config(&[("group", group)]);
+                        }
+                    })
+                },
+            )
+            .build()
+    }
+
+    fn get_default_graph() -> ParsedGraph {
+        get_graph(vec![
+            (
+                "private_finance",
+                AccessType::Private,
+                "core",
+                Some("finance"),
+                vec![],
+            ),
+            (
+                "protected_core",
+                AccessType::Protected,
+                "core",
+                None,
+                vec![],
+            ),
+            ("public_core", AccessType::Public, "core", None, vec![]),
+            (
+                "finance_report",
+                AccessType::Protected,
+                "core",
+                Some("finance"),
+                vec!["private_finance", "protected_core"],
+            ),
+            (
+                "marketing_report",
+                AccessType::Protected,
+                "core",
+                Some("marketing"),
+                vec!["private_finance", "public_core"],
+            ),
+            (
+                "other_package",
+                AccessType::Protected,
+                "downstream",
+                Some("finance"),
+                vec!["private_finance", "protected_core", "public_core"],
+            ),
+        ])
+    }
+
+    fn get_violations(graph: &ParsedGraph) -> Vec<(&str, &str, AccessViolationKind)> {
+        graph
+            .access_violations()
+            .iter()
+            .map(|violation| {
+                let unique_id = graph
+                    .node_map
+                    .get_key_value(&violation.unique_id)
+                    .unwrap()
+                    .0;
+                let model_id = graph.node_map.get_key_value(&violation.model_id).unwrap().0;
+                (unique_id.as_str(), model_id.as_str(), violation.kind)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn access_violations_are_found() {
+        let graph = get_default_graph();
+
+        assert_eq!(
+            get_violations(&graph),
+            vec![
+                (
+                    "marketing_report",
+                    "private_finance",
+                    AccessViolationKind::PrivateOutsideGroup
+                ),
+                (
+                    "other_package",
+                    "protected_core",
+                    AccessViolationKind::ProtectedOutsidePackage
+                ),
+            ]
+        );
+        assert_eq!(
+            graph.access_violations()[0].message,
+            "'marketing_report' references 'private_finance', which is private to the 'finance' group"
+        );
+    }
+
+    #[test]
+    fn private_models_without_a_group_cannot_be_referenced() {
+        let graph = get_graph(vec![
+            ("private", AccessType::Private, "core", None, vec![]),
+            ("child", AccessType::Public, "core", None, vec!["private"]),
+        ]);
+
+        assert_eq!(
+            get_violations(&graph),
+            vec![("child", "private", AccessViolationKind::PrivateOutsideGroup)]
+        );
+    }
+
+    #[test]
+    fn tests_may_reference_any_model() {
+        let graph = GraphBuilder::new()
+            .node_with("private", &[], |node| {
+                node.node_type = get_model("private", AccessType::Private);
+                node.config = to_config(&[("group", "finance")]);
+            })
+            .node("test_relationships_private", &["private"])
+            .node_with("unit_test_private", &["private"], |node| {
+                node.node_type = get_unit_test_node("unit_test_private");
+            })
+            .node_with("child", &["private"], |node| {
+                node.node_type = get_model("child", AccessType::Public);
+            })
+            .build();
+
+        assert_eq!(
+            get_violations(&graph),
+            vec![("child", "private", AccessViolationKind::PrivateOutsideGroup)]
+        );
+    }
+
+    #[test]
+    fn access_selects_models_by_access() {
+        let graph = std::rc::Rc::new(get_default_graph());
+        let included = graph.node_map.keys().cloned().collect();
+
+        let result = MethodName::Access.search(&None, graph.clone(), &included, &[], "public");
+        assert_eq!(
+            result.unwrap().into_iter().collect::<HashSet<_>>(),
+            vec_to_set(vec!["public_core"])
+        );
+
+        let result = MethodName::Access.search(&None, graph.clone(), &included, &[], "private");
+        assert_eq!(result.unwrap(), vec!["private_finance"]);
+
+        let result = MethodName::Access.search(&None, graph, &included, &[], "secret");
+        assert!(matches!(result, Err(SelectionError::InvalidSelector(_))));
+    }
+}
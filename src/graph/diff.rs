@@ -0,0 +1,205 @@
+#[cfg(test)]
+#[path = "diff_tests.rs"]
+mod diff_tests;
+
+/// Structured diffs between two graphs, i.e. the current graph and the one of
+/// a `PreviousState`, for showing what changed between two runs.
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::dbt_node_selector::{GraphDiff, GraphEdge, ModificationReason, ModifiedNode, NodeType};
+
+use super::{
+    node::{WrapperNode, WrapperNodeExt},
+    parsed_graph::ParsedGraph,
+    UniqueId,
+};
+
+/// Configs deciding which relation a node is built as
+const RELATION_CONFIGS: &[&str] = &["database", "schema", "alias"];
+
+impl NodeType {
+    /// The code or text a node is defined by, for those which have one
+    fn body(&self) -> Option<&str> {
+        match self {
+            NodeType::Model(node) => Some(&node.raw_code),
+            NodeType::Analysis(node) => Some(&node.raw_code),
+            NodeType::Test(node) => Some(&node.raw_code),
+            NodeType::Snapshot(node) => Some(&node.raw_code),
+            NodeType::Operation(node) => Some(&node.raw_code),
+            NodeType::Seed(node) => Some(&node.raw_code),
+            NodeType::Rpc(node) => Some(&node.raw_code),
+            NodeType::SqlOperation(node) => Some(&node.raw_code),
+            NodeType::Source(node) => Some(&node.raw_code),
+            NodeType::Exposure(node) => Some(&node.raw_code),
+            NodeType::Macro(node) => Some(&node.macro_sql),
+            NodeType::Doc(node) => Some(&node.block_contents),
+            NodeType::Metric(_)
+            | NodeType::Group(_)
+            | NodeType::SemanticModel(_)
+            | NodeType::SavedQuery(_)
+            | NodeType::UnitTest(_) => None,
+        }
+    }
+}
+
+fn same_configs(node: &WrapperNode, previous: &WrapperNode, is_key: impl Fn(&str) -> bool) -> bool {
+    let keys: HashSet<&String> = node
+        .config()
+        .keys()
+        .chain(previous.config().keys())
+        .filter(|key| is_key(key))
+        .collect();
+    keys.into_iter()
+        .all(|key| node.config().get(key) == previous.config().get(key))
+}
+
+fn same_contract(node: &WrapperNode, previous: &WrapperNode) -> bool {
+    let same_model_interface = match (node.resource_type(), previous.resource_type()) {
+        (NodeType::Model(node), NodeType::Model(previous)) => {
+            node.access == previous.access && node.version == previous.version
+        }
+        _ => true,
+    };
+    same_model_interface && same_configs(node, previous, |key| key.starts_with("contract."))
+}
+
+fn edges(graph: &ParsedGraph) -> BTreeSet<(&UniqueId, &UniqueId)> {
+    graph
+        .parents_map
+        .iter()
+        .flat_map(|(child, parents)| parents.iter().map(move |parent| (parent, child)))
+        .collect()
+}
+
+fn graph_edges<'a>(
+    edges: impl Iterator<Item = &'a (&'a UniqueId, &'a UniqueId)>,
+) -> Vec<GraphEdge> {
+    edges
+        .map(|(parent, child)| GraphEdge {
+            parent: parent.to_string(),
+            child: child.to_string(),
+        })
+        .collect()
+}
+
+impl ParsedGraph {
+    /// Macros whose SQL differs from `previous`, including those which only
+    /// one of the graphs has
+    fn modified_macros<'a>(&'a self, previous: &'a ParsedGraph) -> HashSet<&'a UniqueId> {
+        let body = |graph: &'a ParsedGraph, unique_id: &UniqueId| {
+            graph
+                .node_map
+                .get(unique_id)
+                .and_then(|node| node.resource_type().body())
+        };
+        self.macros
+            .iter()
+            .chain(previous.macros.iter())
+            .filter(|unique_id| body(self, unique_id) != body(previous, unique_id))
+            .collect()
+    }
+
+    /// Whether `node` calls one of `modified_macros`, following the macros it
+    /// depends on through the macros they depend on
+    fn calls_modified_macros(
+        &self,
+        node: &WrapperNode,
+        modified_macros: &HashSet<&UniqueId>,
+        calls: &mut HashMap<UniqueId, bool>,
+    ) -> bool {
+        node.depends_on().iter().any(|unique_id| {
+            if modified_macros.contains(unique_id) {
+                return true;
+            }
+            let Some(called) = self.node_map.get(unique_id) else {
+                return false;
+            };
+            if !self.macros.contains(unique_id) {
+                return false;
+            }
+            if let Some(known) = calls.get(unique_id) {
+                return *known;
+            }
+            // Cycles between macros can't reach anything new
+            calls.insert(unique_id.to_string(), false);
+            let result = self.calls_modified_macros(called, modified_macros, calls);
+            calls.insert(unique_id.to_string(), result);
+            result
+        })
+    }
+
+    fn modification_reasons(
+        node: &WrapperNode,
+        previous: &WrapperNode,
+        calls_modified_macros: bool,
+    ) -> Vec<ModificationReason> {
+        [
+            (
+                ModificationReason::Body,
+                node.resource_type().body() != previous.resource_type().body(),
+            ),
+            (
+                ModificationReason::Config,
+                node.config() != previous.config() || node.tags() != previous.tags(),
+            ),
+            (
+                ModificationReason::Relation,
+                !same_configs(node, previous, |key| RELATION_CONFIGS.contains(&key)),
+            ),
+            (ModificationReason::Contract, !same_contract(node, previous)),
+            (ModificationReason::Macros, calls_modified_macros),
+        ]
+        .into_iter()
+        .filter_map(|(reason, modified)| modified.then_some(reason))
+        .collect()
+    }
+
+    /// The nodes and edges added, removed or modified since `previous`. Nodes
+    /// in both graphs are only modified when one of the reasons applies, and
+    /// changes to what a node depends on show up as edges.
+    pub fn diff(&self, previous: &ParsedGraph) -> GraphDiff {
+        let sorted = |ids: Vec<&UniqueId>| {
+            let mut ids: Vec<String> = ids.into_iter().cloned().collect();
+            ids.sort();
+            ids
+        };
+        let added = self
+            .node_map
+            .keys()
+            .filter(|unique_id| !previous.node_map.contains_key(*unique_id))
+            .collect();
+        let removed = previous
+            .node_map
+            .keys()
+            .filter(|unique_id| !self.node_map.contains_key(*unique_id))
+            .collect();
+
+        let modified_macros = self.modified_macros(previous);
+        let mut calls = HashMap::new();
+        let mut modified: Vec<ModifiedNode> = self
+            .node_map
+            .iter()
+            .filter_map(|(unique_id, node)| {
+                let previous_node = previous.node_map.get(unique_id)?;
+                let calls_modified_macros =
+                    self.calls_modified_macros(node, &modified_macros, &mut calls);
+                let reasons =
+                    Self::modification_reasons(node, previous_node, calls_modified_macros);
+                (!reasons.is_empty()).then(|| ModifiedNode {
+                    unique_id: unique_id.to_string(),
+                    reasons,
+                })
+            })
+            .collect();
+        modified.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+
+        let (edges, previous_edges) = (edges(self), edges(previous));
+        GraphDiff {
+            added: sorted(added),
+            removed: sorted(removed),
+            modified,
+            added_edges: graph_edges(edges.difference(&previous_edges)),
+            removed_edges: graph_edges(previous_edges.difference(&edges)),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+#[cfg(test)]
+mod diff_tests {
+    use std::rc::Rc;
+
+    use crate::{
+        assert_err,
+        dbt_node_selector::{AccessType, GraphDiff, ModificationReason, Node, SelectionError},
+        selector::node_selector::{NodeSelector, PreviousState},
+        util::test::{get_node, to_config, to_strings, GraphBuilder},
+    };
+
+    use super::super::*;
+
+    use ModificationReason::*;
+
+    fn node(unique_id: &str, depends_on: &[&str], config: &[(&str, &str)]) -> Node {
+        Node {
+            depends_on: to_strings(depends_on),
+            config: to_config(config),
+            ..get_node(unique_id)
+        }
+    }
+
+    fn with_body(mut node: Node, body: &str) -> Node {
+        match &mut node.node_type {
+            NodeType::Model(model) => model.raw_code = body.to_string(),
+            NodeType::Macro(macro_node) => macro_node.macro_sql = body.to_string(),
+            _ => panic!("{} has no body to change", node.unique_id),
+        }
+        node
+    }
+
+    /// Graphs whose edges are what the nodes depend on
+    fn builder(nodes: Vec<Node>) -> GraphBuilder {
+        nodes
+            .into_iter()
+            .fold(GraphBuilder::new(), |builder, node| {
+                let parents = node.depends_on.clone();
+                builder.add(
+                    node,
+                    &parents.iter().map(String::as_str).collect::<Vec<_>>(),
+                )
+            })
+    }
+
+    fn graph(nodes: Vec<Node>) -> ParsedGraph {
+        builder(nodes).build()
+    }
+
+    fn modified(diff: &GraphDiff) -> Vec<(&str, &[ModificationReason])> {
+        diff.modified
+            .iter()
+            .map(|node| (node.unique_id.as_str(), node.reasons.as_slice()))
+            .collect()
+    }
+
+    fn edges(edges: &[GraphEdge]) -> Vec<(&str, &str)> {
+        edges
+            .iter()
+            .map(|edge| (edge.parent.as_str(), edge.child.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn finds_added_and_removed_nodes_and_edges() {
+        let previous = graph(vec![
+            node("source_a", &[], &[]),
+            node("model_a", &["source_a"], &[]),
+            node("model_b", &["model_a"], &[]),
+        ]);
+        let current = graph(vec![
+            node("source_a", &[], &[]),
+            node("model_a", &["source_a"], &[]),
+            node("model_c", &["source_a", "model_a"], &[]),
+        ]);
+
+        let diff = current.diff(&previous);
+        assert_eq!(diff.added, ["model_c"]);
+        assert_eq!(diff.removed, ["model_b"]);
+        assert!(diff.modified.is_empty());
+        assert_eq!(
+            edges(&diff.added_edges),
+            [("model_a", "model_c"), ("source_a", "model_c")]
+        );
+        assert_eq!(edges(&diff.removed_edges), [("model_a", "model_b")]);
+
+        let diff = previous.diff(&previous);
+        assert!(diff.added.is_empty() && diff.removed.is_empty() && diff.modified.is_empty());
+        assert!(diff.added_edges.is_empty() && diff.removed_edges.is_empty());
+    }
+
+    #[test]
+    fn changed_dependencies_are_edges() {
+        let previous = graph(vec![
+            node("model_a", &[], &[]),
+            node("model_b", &["model_a"], &[]),
+        ]);
+        let current = graph(vec![node("model_a", &[], &[]), node("model_b", &[], &[])]);
+
+        let diff = current.diff(&previous);
+        assert!(diff.modified.is_empty());
+        assert_eq!(edges(&diff.removed_edges), [("model_a", "model_b")]);
+    }
+
+    #[test]
+    fn categorizes_modified_nodes() {
+        let config = [
+            ("materialized", "view"),
+            ("schema", "staging"),
+            ("contract.enforced", "false"),
+        ];
+        let previous = graph(vec![
+            node("model_body", &[], &config),
+            node("model_materialized", &[], &config),
+            node("model_schema", &[], &config),
+            node("model_contract", &[], &config),
+            node("model_access", &[], &config),
+            node("model_unchanged", &[], &config),
+        ]);
+        let mut access = node("model_access", &[], &config);
+        if let NodeType::Model(model) = &mut access.node_type {
+            model.access = AccessType::Public;
+        }
+        let current = graph(vec![
+            with_body(node("model_body", &[], &config), "select 1"),
+            node(
+                "model_materialized",
+                &[],
+                &[config[1], config[2], ("materialized", "table")],
+            ),
+            node(
+                "model_schema",
+                &[],
+                &[config[0], config[2], ("schema", "marts")],
+            ),
+            node(
+                "model_contract",
+                &[],
+                &[config[0], config[1], ("contract.enforced", "true")],
+            ),
+            access,
+            node("model_unchanged", &[], &config),
+        ]);
+
+        let diff = current.diff(&previous);
+        assert_eq!(
+            modified(&diff),
+            [
+                ("model_access", &[Contract][..]),
+                ("model_body", &[Body]),
+                ("model_contract", &[Config, Contract]),
+                ("model_materialized", &[Config]),
+                ("model_schema", &[Config, Relation]),
+            ]
+        );
+    }
+
+    #[test]
+    fn follows_modified_macros() {
+        let previous = graph(vec![
+            node("macro_a", &["macro_b"], &[]),
+            node("macro_b", &[], &[]),
+            node("macro_removed", &[], &[]),
+            node("model_a", &["macro_a"], &[]),
+            node("model_b", &["model_a"], &[]),
+            node("model_c", &["macro_removed"], &[]),
+        ]);
+        let current = graph(vec![
+            node("macro_a", &["macro_b"], &[]),
+            with_body(node("macro_b", &[], &[]), "{% macro b() %}{% endmacro %}"),
+            node("model_a", &["macro_a"], &[]),
+            node("model_b", &["model_a"], &[]),
+            node("model_c", &["macro_removed"], &[]),
+        ]);
+
+        let diff = current.diff(&previous);
+        assert_eq!(diff.removed, ["macro_removed"]);
+        // model_b only depends on model_a, which state:modified+ would cover
+        assert_eq!(
+            modified(&diff),
+            [
+                ("macro_a", &[Macros][..]),
+                ("macro_b", &[Body]),
+                ("model_a", &[Macros]),
+                ("model_c", &[Macros]),
+            ]
+        );
+    }
+
+    #[test]
+    fn state_modified_selects_from_the_diff() {
+        let previous = graph(vec![
+            node("macro_a", &[], &[]),
+            node("model_body", &[], &[]),
+            node("model_config", &[], &[("materialized", "view")]),
+            node("model_macro", &["macro_a"], &[]),
+            node("model_same", &[], &[]),
+        ]);
+        let node_selector = builder(vec![
+            with_body(node("macro_a", &[], &[]), "{% macro a() %}{% endmacro %}"),
+            with_body(node("model_body", &[], &[]), "select 1"),
+            node("model_config", &[], &[("materialized", "table")]),
+            node("model_macro", &["macro_a"], &[]),
+            node("model_same", &[], &[]),
+            node("model_new", &[], &[]),
+        ])
+        .build_selector(Some(previous));
+        let select = |selector: &str| {
+            let mut selected = node_selector._select(selector.to_string())?;
+            selected.sort();
+            Ok::<_, SelectionError>(selected)
+        };
+
+        assert_eq!(
+            select("state:modified").unwrap(),
+            [
+                "macro_a",
+                "model_body",
+                "model_config",
+                "model_macro",
+                "model_new"
+            ]
+        );
+        assert_eq!(
+            select("state:modified.body").unwrap(),
+            ["macro_a", "model_body"]
+        );
+        assert_eq!(select("state:modified.configs").unwrap(), ["model_config"]);
+        assert_eq!(select("state:modified.macros").unwrap(), ["model_macro"]);
+        assert!(select("state:modified.relation").unwrap().is_empty());
+        assert!(select("state:modified.contract").unwrap().is_empty());
+        assert_err!(
+            select("state:modified.persisted_descriptions"),
+            Err(SelectionError::UnsupportedMethod(_))
+        );
+    }
+
+    #[test]
+    fn node_selectors_diff_against_their_previous_state() {
+        let mut node_selector = NodeSelector::from(vec![], vec![], None).unwrap();
+        assert_err!(
+            node_selector._diff(),
+            Err(SelectionError::RequiresPreviousState(_))
+        );
+
+        let previous = graph(vec![node("model_a", &[], &[])]);
+        node_selector
+            .set_previous_state(Some(Rc::new(PreviousState::from_graph(Rc::new(previous)))));
+        assert_eq!(node_selector._diff().unwrap().removed, ["model_a"]);
+
+        node_selector.set_previous_state(Some(Rc::new(PreviousState::default())));
+        assert_err!(
+            node_selector._diff(),
+            Err(SelectionError::RequiresPreviousState(_))
+        );
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+#[path = "group_tests.rs"]
+mod group_tests;
+
+/// Groups: named collections of nodes with an owner.
+/// https://docs.getdbt.com/docs/build/groups
+use crate::dbt_node_selector::{
+    GroupMembership, GroupNode, NodeType, SelectionError, SelectionError::*,
+};
+
+use super::{node::WrapperNodeExt, parsed_graph::ParsedGraph, UniqueId};
+
+impl ParsedGraph {
+    /// The group configured on a node, which isn't the same as the group node
+    pub(crate) fn group_of(&self, unique_id: &UniqueId) -> Option<&String> {
+        self.node_map.get(unique_id)?.config().get("group")
+    }
+
+    /// The group node named `name`
+    pub fn group_node(&self, name: &str) -> Option<(&UniqueId, &GroupNode)> {
+        self.node_map
+            .iter()
+            .find_map(|(unique_id, node)| match node.resource_type() {
+                NodeType::Group(group) if node.name() == name => Some((unique_id, group)),
+                _ => None,
+            })
+    }
+
+    /// The owner and members of the group named `name`
+    pub fn group_members(&self, name: &str) -> Result<GroupMembership, SelectionError> {
+        let Some((group_id, group)) = self.group_node(name) else {
+            return Err(NodeNotInGraph(name.to_string()));
+        };
+        let mut members: Vec<UniqueId> = self
+            .node_map
+            .keys()
+            .filter(|unique_id| self.group_of(unique_id).is_some_and(|group| group == name))
+            .cloned()
+            .collect();
+        members.sort();
+        Ok(GroupMembership {
+            group: group_id.clone(),
+            owner: group.owner.clone(),
+            members,
+        })
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod group_tests {
+    use std::{collections::HashSet, rc::Rc};
+
+    use crate::{
+        assert_err,
+        dbt_node_selector::{GroupOwner, NodeType, SelectionError},
+        selector::MethodName,
+        util::test::{to_config, vec_to_set, GraphBuilder},
+    };
+
+    use super::super::*;
+
+    fn get_group(name: &str, email: &str) -> NodeType {
+        NodeType::Group(GroupNode {
+            fqn: vec!["pkg".to_string(), name.to_string()],
+            owner: GroupOwner {
+                name: Some(format!("{} team", name)),
+                email: Some(email.to_string()),
+            },
+        })
+    }
+
+    fn get_graph() -> Rc<ParsedGraph> {
+        let nodes = vec![
+            ("group.pkg.finance", "finance", None),
+            ("group.pkg.finance_ops", "finance_ops", None),
+            ("group.pkg.marketing", "marketing", None),
+            ("model_revenue", "revenue", Some("finance")),
+            ("model_invoices", "invoices", Some("finance")),
+            ("model_payroll", "payroll", Some("finance_ops")),
+            ("model_campaigns", "campaigns", Some("marketing")),
+            ("model_orders", "orders", None),
+        ];
+        let graph = nodes
+            .into_iter()
+            .fold(GraphBuilder::new(), |builder, (unique_id, name, group)| {
+                builder.node_with(unique_id, &[], |node| {
+                    node.name = name.to_string();
+                    node.package_name = "pkg".to_string();
+                    if let Some(name) = unique_id.strip_prefix("group.pkg.") {
+                        node.node_type = get_group(name, &format!("{}@example.com", name));
+                    }
+                    if let Some(group) = group {
+                        node.config = to_config(&[("group", group)]);
+                    }
+                })
+            })
+            .build();
+        Rc::new(graph)
+    }
+
+    fn select_group(selector: &str) -> HashSet<String> {
+        let graph = get_graph();
+        let included = graph.node_map.keys().cloned().collect();
+        let result = MethodName::Group.search(&None, graph, &included, &[], selector);
+        result.unwrap().into_iter().collect()
+    }
+
+    #[test]
+    fn group_selects_members_and_the_group_node() {
+        assert_eq!(
+            select_group("finance"),
+            vec_to_set(vec!["group.pkg.finance", "model_revenue", "model_invoices"])
+        );
+    }
+
+    #[test]
+    fn group_matches_wildcards() {
+        assert_eq!(
+            select_group("finance*"),
+            vec_to_set(vec![
+                "group.pkg.finance",
+                "group.pkg.finance_ops",
+                "model_revenue",
+                "model_invoices",
+                "model_payroll",
+            ])
+        );
+        assert_eq!(select_group("sales*"), HashSet::new());
+    }
+
+    #[test]
+    fn group_members_lists_the_owner_and_members() {
+        let graph = get_graph();
+
+        let membership = graph.group_members("finance").unwrap();
+        assert_eq!(membership.group, "group.pkg.finance");
+        assert_eq!(membership.owner.name.as_deref(), Some("finance team"));
+        assert_eq!(
+            membership.owner.email.as_deref(),
+            Some("finance@example.com")
+        );
+        assert_eq!(membership.members, vec!["model_invoices", "model_revenue"]);
+
+        assert_err!(
+            graph.group_members("finance*"),
+            Err(SelectionError::NodeNotInGraph(_))
+        );
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+#[path = "impact_tests.rs"]
+mod impact_tests;
+
+/// Impact analysis: which exposures, metrics and groups a change reaches
+/// downstream, how far away they are and through which nodes.
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::dbt_node_selector::{
+    GroupImpact, ImpactReport, ImpactedConsumer, SelectionError, SelectionError::*,
+};
+
+use super::{
+    parsed_graph::{NodeSet, ParsedGraph},
+    UniqueId,
+};
+
+impl ParsedGraph {
+    /// Breadth-first search from every changed node at once, returning the
+    /// node each downstream node was first reached from. Changed nodes have
+    /// no predecessor.
+    fn impact_predecessors<'a>(
+        &'a self,
+        changed: &'a HashSet<UniqueId>,
+    ) -> HashMap<&'a UniqueId, Option<&'a UniqueId>> {
+        let mut frontier: Vec<&UniqueId> = changed.iter().collect();
+        frontier.sort();
+        let mut predecessors: HashMap<&UniqueId, Option<&UniqueId>> =
+            frontier.iter().map(|node_id| (*node_id, None)).collect();
+        while !frontier.is_empty() {
+            let mut next = vec![];
+            for node_id in frontier {
+                let mut children: Vec<&UniqueId> = self
+                    .children_map
+                    .get(node_id)
+                    .map(|children| children.iter().collect())
+                    .unwrap_or_default();
+                children.sort();
+                for child in children {
+                    if !predecessors.contains_key(child) {
+                        predecessors.insert(child, Some(node_id));
+                        next.push(child);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        predecessors
+    }
+
+    fn impacted_consumer(
+        predecessors: &HashMap<&UniqueId, Option<&UniqueId>>,
+        unique_id: &UniqueId,
+    ) -> ImpactedConsumer {
+        let mut path = vec![unique_id.to_string()];
+        let mut node_id = unique_id;
+        while let Some(Some(predecessor)) = predecessors.get(node_id) {
+            path.push(predecessor.to_string());
+            node_id = predecessor;
+        }
+        path.reverse();
+        ImpactedConsumer {
+            unique_id: unique_id.to_string(),
+            changed_id: path[0].clone(),
+            distance: (path.len() - 1) as u32,
+            path,
+        }
+    }
+
+    /// The exposures, metrics and group members downstream of the changed
+    /// nodes, or changed themselves. Each consumer is reported against its
+    /// closest changed node.
+    pub fn impact(&self, changed: &HashSet<UniqueId>) -> Result<ImpactReport, SelectionError> {
+        if let Some(node_id) = changed.iter().find(|id| !self.node_map.contains_key(*id)) {
+            return Err(NodeNotInGraph(node_id.to_string()));
+        }
+        let predecessors = self.impact_predecessors(changed);
+        let mut impacted: Vec<&UniqueId> = predecessors.keys().cloned().collect();
+        impacted.sort();
+
+        let consumers = |consumers: &NodeSet| -> Vec<ImpactedConsumer> {
+            impacted
+                .iter()
+                .filter(|unique_id| consumers.contains(**unique_id))
+                .map(|unique_id| Self::impacted_consumer(&predecessors, unique_id))
+                .collect()
+        };
+
+        let mut groups: BTreeMap<&UniqueId, GroupImpact> = BTreeMap::new();
+        for unique_id in impacted.iter() {
+            let Some((group_id, group)) = self
+                .group_of(unique_id)
+                .and_then(|name| self.group_node(name))
+            else {
+                continue;
+            };
+            groups
+                .entry(group_id)
+                .or_insert_with(|| GroupImpact {
+                    group: group_id.clone(),
+                    owner: group.owner.clone(),
+                    members: vec![],
+                })
+                .members
+                .push(Self::impacted_consumer(&predecessors, unique_id));
+        }
+
+        let mut changed: Vec<UniqueId> = changed.iter().cloned().collect();
+        changed.sort();
+        Ok(ImpactReport {
+            changed,
+            exposures: consumers(&self.exposures),
+            metrics: consumers(&self.metrics),
+            groups: groups.into_values().collect(),
+        })
+    }
+}
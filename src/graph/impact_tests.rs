@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod impact_tests {
+    use std::{collections::HashSet, rc::Rc};
+
+    use crate::{
+        assert_err,
+        dbt_node_selector::{GroupNode, GroupOwner, NodeType, SelectionError},
+        util::test::{to_config, vec_to_set, GraphBuilder},
+    };
+
+    use super::super::*;
+
+    /// source_raw -> model_stg -> model_orders -> exposure_dashboard, and
+    /// model_orders -> metric_revenue. model_stg -> model_customers ->
+    /// exposure_dashboard, where model_customers is in the finance group and
+    /// model_campaigns, which nothing depends on, is in the marketing group.
+    fn get_builder() -> GraphBuilder {
+        let nodes = vec![
+            ("group.pkg.finance", vec![], None),
+            ("group.pkg.marketing", vec![], None),
+            ("source_raw", vec![], None),
+            ("model_stg", vec!["source_raw"], None),
+            ("model_orders", vec!["model_stg"], None),
+            ("model_customers", vec!["model_stg"], Some("finance")),
+            ("model_campaigns", vec![], Some("marketing")),
+            (
+                "exposure_dashboard",
+                vec!["model_orders", "model_customers"],
+                None,
+            ),
+            ("metric_revenue", vec!["model_orders"], None),
+        ];
+        nodes.into_iter().fold(
+            GraphBuilder::new(),
+            |builder, (unique_id, parents, group)| {
+                builder.node_with(unique_id, &parents, |node| {
+                    node.name = unique_id.rsplit('.').next().unwrap().to_string();
+                    node.package_name = "pkg".to_string();
+                    if let Some(name) = unique_id.strip_prefix("group.pkg.") {
+                        node.node_type = NodeType::Group(GroupNode {
+                            fqn: vec!["pkg".to_string(), name.to_string()],
+                            owner: GroupOwner {
+                                name: Some(format!("{} team", name)),
+                                email: None,
+                            },
+                        });
+                    }
+                    if let Some(group) = group {
+                        node.config = to_config(&[("group", group)]);
+                    }
+                })
+            },
+        )
+    }
+
+    fn get_graph() -> ParsedGraph {
+        get_builder().build()
+    }
+
+    fn ids(ids: &[&str]) -> Vec<UniqueId> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn reports_consumers_with_distance_and_path() {
+        let report = get_graph().impact(&vec_to_set(vec!["model_stg"])).unwrap();
+
+        assert_eq!(report.changed, ids(&["model_stg"]));
+        let [exposure] = &report.exposures[..] else {
+            panic!("Expected one exposure, got {:?}", report.exposures)
+        };
+        assert_eq!(exposure.unique_id, "exposure_dashboard");
+        assert_eq!(exposure.changed_id, "model_stg");
+        assert_eq!(exposure.distance, 2);
+        // Both paths are as short, the first child in order wins
+        assert_eq!(
+            exposure.path,
+            ids(&["model_stg", "model_customers", "exposure_dashboard"])
+        );
+
+        let [metric] = &report.metrics[..] else {
+            panic!("Expected one metric, got {:?}", report.metrics)
+        };
+        assert_eq!(metric.unique_id, "metric_revenue");
+        assert_eq!(
+            metric.path,
+            ids(&["model_stg", "model_orders", "metric_revenue"])
+        );
+
+        let [group] = &report.groups[..] else {
+            panic!("Expected one group, got {:?}", report.groups)
+        };
+        assert_eq!(group.group, "group.pkg.finance");
+        assert_eq!(group.owner.name, Some("finance team".to_string()));
+        let members: Vec<(&str, u32)> = group
+            .members
+            .iter()
+            .map(|member| (member.unique_id.as_str(), member.distance))
+            .collect();
+        assert_eq!(members, [("model_customers", 1)]);
+    }
+
+    #[test]
+    fn reports_against_the_closest_change() {
+        let changed = vec_to_set(vec!["source_raw", "model_orders"]);
+        let report = get_graph().impact(&changed).unwrap();
+
+        assert_eq!(report.changed, ids(&["model_orders", "source_raw"]));
+        let exposure = &report.exposures[0];
+        assert_eq!(exposure.changed_id, "model_orders");
+        assert_eq!(exposure.distance, 1);
+        assert_eq!(exposure.path, ids(&["model_orders", "exposure_dashboard"]));
+        assert_eq!(report.metrics[0].distance, 1);
+        assert_eq!(report.groups[0].members[0].distance, 2);
+    }
+
+    #[test]
+    fn changed_consumers_are_impacted_themselves() {
+        let changed = vec_to_set(vec!["model_campaigns", "metric_revenue"]);
+        let report = get_graph().impact(&changed).unwrap();
+
+        assert!(report.exposures.is_empty());
+        assert_eq!(report.metrics[0].distance, 0);
+        assert_eq!(report.metrics[0].path, ids(&["metric_revenue"]));
+        let groups: Vec<&str> = report
+            .groups
+            .iter()
+            .map(|group| group.group.as_str())
+            .collect();
+        assert_eq!(groups, ["group.pkg.marketing"]);
+        assert_eq!(report.groups[0].members[0].changed_id, "model_campaigns");
+    }
+
+    #[test]
+    fn nothing_changed_impacts_nothing() {
+        let report = get_graph().impact(&HashSet::new()).unwrap();
+
+        assert!(report.changed.is_empty());
+        assert!(report.exposures.is_empty());
+        assert!(report.metrics.is_empty());
+        assert!(report.groups.is_empty());
+    }
+
+    #[test]
+    fn state_modified_selections_are_impacted() {
+        let mut previous = get_graph();
+        let model_stg = previous.node_map.get_mut("model_stg").unwrap();
+        if let NodeType::Model(model) = &mut Rc::make_mut(model_stg).resource_type {
+            model.raw_code = "select 1".to_string();
+        }
+        let node_selector = get_builder().build_selector(Some(previous));
+
+        let report = node_selector
+            ._impact_of_selection("state:modified".to_string())
+            .unwrap();
+        assert_eq!(report.changed, ids(&["model_stg"]));
+        assert_eq!(report.exposures[0].changed_id, "model_stg");
+        assert_eq!(report.metrics[0].distance, 2);
+    }
+
+    #[test]
+    fn missing_changed_nodes_are_errors() {
+        assert_err!(
+            get_graph().impact(&vec_to_set(vec!["model_stg", "model_deleted"])),
+            Err(SelectionError::NodeNotInGraph(_))
+        );
+    }
+}
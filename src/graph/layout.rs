@@ -0,0 +1,430 @@
+#[cfg(test)]
+#[path = "layout_tests.rs"]
+mod layout_tests;
+
+/// A Sugiyama-style layered layout for a subset of the graph.
+///
+/// The layout runs in four phases:
+/// 1. rank assignment (longest path from the subgraph's roots)
+/// 2. dummy node insertion, so every edge only spans one rank
+/// 3. crossing minimisation (barycenter sweeps, keeping the best ordering)
+/// 4. coordinate assignment (least-squares straightening under a minimum
+///    separation constraint)
+///
+/// Ranks grow along the `y` axis, from parents to children. Hosts that want a
+/// left-to-right DAG can simply swap `x` and `y`.
+use std::collections::{HashMap, HashSet};
+
+use crate::dbt_node_selector::{
+    EdgePath, GraphLayout, LayoutOptions, NodePosition, Point, SelectionError,
+};
+use crate::SelectionError::*;
+
+use super::{parsed_graph::ParsedGraph, UniqueId};
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            rank_separation: 100.0,
+            node_separation: 50.0,
+            crossing_sweeps: 8,
+        }
+    }
+}
+
+/// A vertex in the layered graph, either a real node or a dummy node which
+/// carries a long edge through an intermediate rank.
+struct LayerVertex {
+    unique_id: Option<UniqueId>,
+    rank: usize,
+    parents: Vec<usize>,
+    children: Vec<usize>,
+}
+
+struct LayeredGraph {
+    vertices: Vec<LayerVertex>,
+    /// Vertex indices per rank, in their current left-to-right order
+    layers: Vec<Vec<usize>>,
+    /// (source vertex, target vertex, dummy vertices in between)
+    edges: Vec<(usize, usize, Vec<usize>)>,
+}
+
+impl LayeredGraph {
+    fn position_index(&self) -> Vec<usize> {
+        let mut positions = vec![0; self.vertices.len()];
+        for layer in &self.layers {
+            for (position, vertex) in layer.iter().enumerate() {
+                positions[*vertex] = position;
+            }
+        }
+        positions
+    }
+
+    /// Number of edge crossings between `layer` and `layer + 1`
+    fn count_crossings_below(&self, layer: usize, positions: &[usize]) -> usize {
+        let mut pairs: Vec<(usize, usize)> = self.layers[layer]
+            .iter()
+            .flat_map(|upper| {
+                self.vertices[*upper]
+                    .children
+                    .iter()
+                    .map(move |lower| (positions[*upper], positions[*lower]))
+            })
+            .collect();
+        pairs.sort_unstable();
+
+        // Count inversions of the lower positions with a Fenwick tree
+        let size = self.layers[layer + 1].len() + 1;
+        let mut tree = vec![0usize; size + 1];
+        let mut crossings = 0;
+        for (inserted, (_, lower)) in pairs.iter().enumerate() {
+            let mut index = lower + 1;
+            let mut not_greater = 0;
+            while index > 0 {
+                not_greater += tree[index];
+                index -= index & index.wrapping_neg();
+            }
+            crossings += inserted - not_greater;
+
+            let mut index = lower + 1;
+            while index <= size {
+                tree[index] += 1;
+                index += index & index.wrapping_neg();
+            }
+        }
+        crossings
+    }
+
+    fn count_crossings(&self) -> usize {
+        let positions = self.position_index();
+        (0..self.layers.len().saturating_sub(1))
+            .map(|layer| self.count_crossings_below(layer, &positions))
+            .sum()
+    }
+
+    /// Reorders `layer` by the mean position of each vertex's neighbors in the
+    /// adjacent, already fixed layer. Vertices without neighbors keep their slot.
+    fn order_by_barycenter(&mut self, layer: usize, use_parents: bool) {
+        let positions = self.position_index();
+        let mut keyed: Vec<(f64, usize)> = self.layers[layer]
+            .iter()
+            .enumerate()
+            .map(|(position, vertex)| {
+                let vertex_data = &self.vertices[*vertex];
+                let neighbors = if use_parents {
+                    &vertex_data.parents
+                } else {
+                    &vertex_data.children
+                };
+                let barycenter = match neighbors.len() {
+                    0 => position as f64,
+                    len => neighbors.iter().map(|n| positions[*n] as f64).sum::<f64>() / len as f64,
+                };
+                (barycenter, *vertex)
+            })
+            .collect();
+        // Stable sort keeps ties in their current order, which avoids oscillation
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.layers[layer] = keyed.into_iter().map(|(_, vertex)| vertex).collect();
+    }
+
+    fn minimize_crossings(&mut self, sweeps: u32) {
+        let mut best_layers = self.layers.clone();
+        let mut best_crossings = self.count_crossings();
+
+        for _ in 0..sweeps {
+            if best_crossings == 0 {
+                break;
+            }
+            for layer in 1..self.layers.len() {
+                self.order_by_barycenter(layer, true);
+            }
+            for layer in (0..self.layers.len().saturating_sub(1)).rev() {
+                self.order_by_barycenter(layer, false);
+            }
+            let crossings = self.count_crossings();
+            if crossings < best_crossings {
+                best_crossings = crossings;
+                best_layers = self.layers.clone();
+            }
+        }
+        self.layers = best_layers;
+    }
+
+    /// Places each vertex of `layer` as close as possible to `desired` (in the
+    /// least-squares sense) while keeping the order and a minimum separation.
+    ///
+    /// With `y_i = desired_i - i * separation`, this is an isotonic regression on
+    /// `y`, solved with pool-adjacent-violators.
+    fn place_layer(desired: &[f64], separation: f64) -> Vec<f64> {
+        // (sum, count) blocks of pooled values
+        let mut blocks: Vec<(f64, usize)> = vec![];
+        for (i, value) in desired.iter().enumerate() {
+            blocks.push((value - i as f64 * separation, 1));
+            while blocks.len() > 1 {
+                let (last_sum, last_count) = blocks[blocks.len() - 1];
+                let (prev_sum, prev_count) = blocks[blocks.len() - 2];
+                if prev_sum / prev_count as f64 <= last_sum / last_count as f64 {
+                    break;
+                }
+                blocks.pop();
+                let merged = blocks.len() - 1;
+                blocks[merged] = (prev_sum + last_sum, prev_count + last_count);
+            }
+        }
+        blocks
+            .into_iter()
+            .flat_map(|(sum, count)| std::iter::repeat_n(sum / count as f64, count))
+            .enumerate()
+            .map(|(i, fitted)| fitted + i as f64 * separation)
+            .collect()
+    }
+
+    fn assign_coordinates(&self, options: &LayoutOptions) -> Vec<f64> {
+        let separation = options.node_separation;
+        let mut x = vec![0.0; self.vertices.len()];
+        for layer in &self.layers {
+            for (position, vertex) in layer.iter().enumerate() {
+                x[*vertex] = position as f64 * separation;
+            }
+        }
+
+        let iterations = options.crossing_sweeps.max(1);
+        for _ in 0..iterations {
+            for use_parents in [true, false] {
+                for layer in &self.layers {
+                    let desired: Vec<f64> = layer
+                        .iter()
+                        .map(|vertex| {
+                            let vertex_data = &self.vertices[*vertex];
+                            let neighbors = if use_parents {
+                                &vertex_data.parents
+                            } else {
+                                &vertex_data.children
+                            };
+                            match neighbors.len() {
+                                0 => x[*vertex],
+                                len => neighbors.iter().map(|n| x[*n]).sum::<f64>() / len as f64,
+                            }
+                        })
+                        .collect();
+                    let placed = Self::place_layer(&desired, separation);
+                    for (vertex, value) in layer.iter().zip(placed) {
+                        x[*vertex] = value;
+                    }
+                }
+            }
+        }
+
+        let min_x = x.iter().cloned().fold(f64::INFINITY, f64::min);
+        if min_x.is_finite() {
+            x.iter_mut().for_each(|value| *value -= min_x);
+        }
+        x
+    }
+}
+
+impl ParsedGraph {
+    /// Longest-path rank assignment over the subgraph induced by `node_ids`.
+    /// Any cycle is broken by ranking its remaining nodes after everything else.
+    fn assign_ranks(
+        &self,
+        node_ids: &[UniqueId],
+        included: &HashSet<UniqueId>,
+    ) -> HashMap<UniqueId, usize> {
+        let parents_in_subset = |id: &UniqueId| -> Vec<UniqueId> {
+            let mut parents: Vec<UniqueId> = self
+                .parents_map
+                .get(id)
+                .map(|parents| {
+                    parents
+                        .iter()
+                        .filter(|p| included.contains(*p))
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default();
+            parents.sort();
+            parents
+        };
+
+        let mut remaining_parents: HashMap<&UniqueId, usize> = node_ids
+            .iter()
+            .map(|id| (id, parents_in_subset(id).len()))
+            .collect();
+        let mut ranks: HashMap<UniqueId, usize> = HashMap::new();
+        let mut queue: Vec<&UniqueId> = node_ids
+            .iter()
+            .filter(|id| remaining_parents[id] == 0)
+            .collect();
+
+        while ranks.len() < node_ids.len() {
+            if queue.is_empty() {
+                // Only reachable with a cycle, pick the first unranked node
+                let Some(next) = node_ids.iter().find(|id| !ranks.contains_key(*id)) else {
+                    break;
+                };
+                queue.push(next);
+            }
+            let mut next_queue = vec![];
+            for id in queue {
+                if ranks.contains_key(id) {
+                    continue;
+                }
+                let rank = parents_in_subset(id)
+                    .iter()
+                    .filter_map(|parent| ranks.get(parent).map(|rank| rank + 1))
+                    .max()
+                    .unwrap_or(0);
+                ranks.insert(id.clone(), rank);
+
+                let mut children: Vec<&UniqueId> = self
+                    .children_map
+                    .get(id)
+                    .map(|children| children.iter().filter(|c| included.contains(*c)).collect())
+                    .unwrap_or_default();
+                children.sort();
+                for child in children {
+                    let Some(count) = remaining_parents.get_mut(child) else {
+                        continue;
+                    };
+                    *count = count.saturating_sub(1);
+                    if *count == 0 {
+                        next_queue.push(child);
+                    }
+                }
+            }
+            queue = next_queue;
+        }
+        ranks
+    }
+
+    fn build_layered_graph(&self, node_ids: &[UniqueId]) -> LayeredGraph {
+        let included: HashSet<UniqueId> = node_ids.iter().cloned().collect();
+        let ranks = self.assign_ranks(node_ids, &included);
+
+        let mut vertices: Vec<LayerVertex> = node_ids
+            .iter()
+            .map(|id| LayerVertex {
+                unique_id: Some(id.clone()),
+                rank: ranks[id],
+                parents: vec![],
+                children: vec![],
+            })
+            .collect();
+        let index: HashMap<&UniqueId, usize> =
+            node_ids.iter().enumerate().map(|(i, id)| (id, i)).collect();
+
+        let mut edges = vec![];
+        for (source, source_id) in node_ids.iter().enumerate() {
+            let Some(children) = self.children_map.get(source_id) else {
+                continue;
+            };
+            let mut children: Vec<&UniqueId> =
+                children.iter().filter(|c| included.contains(*c)).collect();
+            children.sort();
+            for target_id in children {
+                let target = index[target_id];
+                let (source_rank, target_rank) = (vertices[source].rank, vertices[target].rank);
+                // Edges that point "up" only exist in cyclic input, and are drawn straight
+                let mut previous = source;
+                let mut dummies = vec![];
+                for rank in (source_rank + 1)..target_rank {
+                    let dummy = vertices.len();
+                    vertices.push(LayerVertex {
+                        unique_id: None,
+                        rank,
+                        parents: vec![previous],
+                        children: vec![],
+                    });
+                    vertices[previous].children.push(dummy);
+                    dummies.push(dummy);
+                    previous = dummy;
+                }
+                if target_rank > source_rank {
+                    vertices[previous].children.push(target);
+                    vertices[target].parents.push(previous);
+                }
+                edges.push((source, target, dummies));
+            }
+        }
+
+        let num_layers = vertices.iter().map(|v| v.rank + 1).max().unwrap_or(0);
+        let mut layers: Vec<Vec<usize>> = vec![vec![]; num_layers];
+        for (i, vertex) in vertices.iter().enumerate() {
+            layers[vertex.rank].push(i);
+        }
+
+        LayeredGraph {
+            vertices,
+            layers,
+            edges,
+        }
+    }
+
+    /// Computes x/y coordinates for every node in `node_ids` and waypoints for
+    /// every edge between them, so that hosts only have to draw the result.
+    pub fn layout(
+        &self,
+        node_ids: &HashSet<UniqueId>,
+        options: &LayoutOptions,
+    ) -> Result<GraphLayout, SelectionError> {
+        if let Some(missing) = node_ids.iter().find(|id| !self.node_map.contains_key(*id)) {
+            return Err(NodeNotInGraph(missing.to_string()));
+        }
+        // Sorting keeps the output deterministic across HashSet iteration orders
+        let mut sorted_ids: Vec<UniqueId> = node_ids.iter().cloned().collect();
+        sorted_ids.sort();
+
+        let mut layered = self.build_layered_graph(&sorted_ids);
+        layered.minimize_crossings(options.crossing_sweeps);
+        let x = layered.assign_coordinates(options);
+        let y = |vertex: usize| layered.vertices[vertex].rank as f64 * options.rank_separation;
+        let point = |vertex: usize| Point {
+            x: x[vertex],
+            y: y(vertex),
+        };
+
+        let positions = layered.position_index();
+        let nodes: Vec<NodePosition> = layered
+            .vertices
+            .iter()
+            .enumerate()
+            .filter_map(|(i, vertex)| {
+                let unique_id = vertex.unique_id.clone()?;
+                Some(NodePosition {
+                    unique_id,
+                    rank: vertex.rank as u32,
+                    order: positions[i] as u32,
+                    x: x[i],
+                    y: y(i),
+                })
+            })
+            .collect();
+
+        let edges: Vec<EdgePath> = layered
+            .edges
+            .iter()
+            .map(|(source, target, dummies)| {
+                let mut points = vec![point(*source)];
+                points.extend(dummies.iter().map(|dummy| point(*dummy)));
+                points.push(point(*target));
+                EdgePath {
+                    source: sorted_ids[*source].clone(),
+                    target: sorted_ids[*target].clone(),
+                    points,
+                }
+            })
+            .collect();
+
+        let width = x.iter().cloned().fold(0.0, f64::max);
+        let height = layered.layers.len().saturating_sub(1) as f64 * options.rank_separation;
+
+        Ok(GraphLayout {
+            nodes,
+            edges,
+            width,
+            height,
+        })
+    }
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod layout_tests {
+    use crate::{assert_err, assert_ok, util::test::*};
+
+    use super::super::*;
+
+    fn get_graph(edges: Vec<(&str, Vec<&str>)>) -> ParsedGraph {
+        edges
+            .into_iter()
+            .fold(GraphBuilder::new(), |builder, (unique_id, parents)| {
+                builder.node(unique_id, &parents)
+            })
+            .build()
+    }
+
+    fn get_position<'a>(layout: &'a GraphLayout, unique_id: &str) -> &'a NodePosition {
+        layout
+            .nodes
+            .iter()
+            .find(|node| node.unique_id == unique_id)
+            .expect("Node missing from layout")
+    }
+
+    #[test]
+    fn layout_assigns_longest_path_ranks() {
+        let graph = get_graph(vec![
+            ("a", vec![]),
+            ("b", vec!["a"]),
+            ("c", vec!["b"]),
+            ("d", vec!["a", "c"]),
+        ]);
+
+        let layout = assert_ok!(graph.layout(
+            &vec_to_set(vec!["a", "b", "c", "d"]),
+            &LayoutOptions::default()
+        ));
+
+        assert_eq!(get_position(&layout, "a").rank, 0);
+        assert_eq!(get_position(&layout, "b").rank, 1);
+        assert_eq!(get_position(&layout, "c").rank, 2);
+        assert_eq!(get_position(&layout, "d").rank, 3);
+        assert_eq!(get_position(&layout, "d").y, 300.0);
+        assert_eq!(layout.height, 300.0);
+    }
+
+    #[test]
+    fn layout_routes_long_edges_through_every_rank() {
+        let graph = get_graph(vec![
+            ("a", vec![]),
+            ("b", vec!["a"]),
+            ("c", vec!["b"]),
+            ("d", vec!["a", "c"]),
+        ]);
+
+        let layout = assert_ok!(graph.layout(
+            &vec_to_set(vec!["a", "b", "c", "d"]),
+            &LayoutOptions::default()
+        ));
+
+        let long_edge = layout
+            .edges
+            .iter()
+            .find(|edge| edge.source == "a" && edge.target == "d")
+            .expect("Edge missing from layout");
+        let ys: Vec<f64> = long_edge.points.iter().map(|point| point.y).collect();
+        assert_eq!(ys, vec![0.0, 100.0, 200.0, 300.0]);
+        assert_eq!(layout.edges.len(), 4);
+    }
+
+    #[test]
+    fn layout_removes_avoidable_crossings() {
+        // Sorted by id, "c" and "d" start out crossed
+        let graph = get_graph(vec![
+            ("a", vec![]),
+            ("b", vec![]),
+            ("c", vec!["b"]),
+            ("d", vec!["a"]),
+        ]);
+
+        let layout = assert_ok!(graph.layout(
+            &vec_to_set(vec!["a", "b", "c", "d"]),
+            &LayoutOptions::default()
+        ));
+
+        let a = get_position(&layout, "a");
+        let b = get_position(&layout, "b");
+        let c = get_position(&layout, "c");
+        let d = get_position(&layout, "d");
+        assert_eq!(a.x < b.x, d.x < c.x);
+    }
+
+    #[test]
+    fn layout_keeps_nodes_separated() {
+        let graph = get_graph(vec![
+            ("a", vec![]),
+            ("b", vec!["a"]),
+            ("c", vec!["a"]),
+            ("d", vec!["a"]),
+            ("e", vec!["a"]),
+        ]);
+        let options = LayoutOptions::default();
+
+        let layout = assert_ok!(graph.layout(&vec_to_set(vec!["a", "b", "c", "d", "e"]), &options));
+
+        let mut xs: Vec<f64> = ["b", "c", "d", "e"]
+            .iter()
+            .map(|id| get_position(&layout, id).x)
+            .collect();
+        xs.sort_by(f64::total_cmp);
+        assert!(xs
+            .windows(2)
+            .all(|w| w[1] - w[0] >= options.node_separation - 1e-9));
+        // The parent is centered over its children
+        assert!((get_position(&layout, "a").x - (xs[0] + xs[3]) / 2.0).abs() < 1e-9);
+        assert_eq!(layout.width, 150.0);
+    }
+
+    #[test]
+    fn layout_ignores_edges_outside_the_subset() {
+        let graph = get_graph(vec![("a", vec![]), ("b", vec!["a"]), ("c", vec!["b"])]);
+
+        let layout =
+            assert_ok!(graph.layout(&vec_to_set(vec!["a", "c"]), &LayoutOptions::default()));
+
+        assert!(layout.edges.is_empty());
+        assert_eq!(get_position(&layout, "c").rank, 0);
+    }
+
+    #[test]
+    fn layout_missing_node() {
+        let graph = get_graph(vec![("a", vec![])]);
+
+        let result = graph.layout(&vec_to_set(vec!["a", "z"]), &LayoutOptions::default());
+
+        assert_err!(result, Err(SelectionError::NodeNotInGraph(_)));
+    }
+}
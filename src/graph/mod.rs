@@ -1,5 +1,13 @@
+pub mod access;
+pub mod diff;
+pub mod group;
+pub mod impact;
+pub mod layout;
 pub mod node;
 pub mod parsed_graph;
+pub mod patch;
+pub mod paths;
+pub mod search_index;
 pub mod types;
 
 pub use String as UniqueId;
@@ -43,6 +43,9 @@ pub enum NodeTypeKey {
     Exposure,
     Metric,
     Group,
+    SemanticModel,
+    SavedQuery,
+    UnitTest,
 }
 
 impl NodeTypeKey {
@@ -62,6 +65,9 @@ impl NodeTypeKey {
             NodeTypeKey::Exposure => "exposure",
             NodeTypeKey::Metric => "metric",
             NodeTypeKey::Group => "group",
+            NodeTypeKey::SemanticModel => "semantic_model",
+            NodeTypeKey::SavedQuery => "saved_query",
+            NodeTypeKey::UnitTest => "unit_test",
         }
     }
 
@@ -82,6 +88,9 @@ impl NodeTypeKey {
             "exposure" => Ok(Self::Exposure),
             "metric" => Ok(Self::Metric),
             "group" => Ok(Self::Group),
+            "semantic_model" => Ok(Self::SemanticModel),
+            "saved_query" => Ok(Self::SavedQuery),
+            "unit_test" => Ok(Self::UnitTest),
             _ => Err(NoMatchingResourceType(key_str)),
         }
     }
@@ -102,6 +111,9 @@ impl NodeTypeKey {
             NodeType::Exposure(_) => Self::Exposure,
             NodeType::Metric(_) => Self::Metric,
             NodeType::Group(_) => Self::Group,
+            NodeType::SemanticModel(_) => Self::SemanticModel,
+            NodeType::SavedQuery(_) => Self::SavedQuery,
+            NodeType::UnitTest(_) => Self::UnitTest,
         }
     }
 }
@@ -131,8 +143,75 @@ impl NodeType {
             NodeType::Exposure(data) => Some(data.fqn.clone()),
             NodeType::Metric(data) => Some(data.fqn.clone()),
             NodeType::Group(data) => Some(data.fqn.clone()),
+            NodeType::SemanticModel(data) => Some(data.fqn.clone()),
+            NodeType::SavedQuery(data) => Some(data.fqn.clone()),
+            NodeType::UnitTest(data) => Some(data.fqn.clone()),
         }
     }
+
+    /// Only models can be versioned
+    pub fn version(&self) -> Option<&str> {
+        match self {
+            NodeType::Model(data) => data.version.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn latest_version(&self) -> Option<&str> {
+        match self {
+            NodeType::Model(data) => data.latest_version.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The name of the model a unit test tests
+    pub fn unit_test_model(&self) -> Option<&str> {
+        match self {
+            NodeType::UnitTest(data) => Some(&data.model),
+            _ => None,
+        }
+    }
+
+    /// The generic test a test runs, which singular tests have none of
+    pub fn test_metadata_name(&self) -> Option<&str> {
+        match self {
+            NodeType::Test(data) => data.test_metadata_name.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Only models have an access level
+    pub fn access(&self) -> Option<&AccessType> {
+        match self {
+            NodeType::Model(data) => Some(&data.access),
+            _ => None,
+        }
+    }
+
+    /// Whether the node is in the `nodes` or `sources` of a manifest, which
+    /// are the nodes dbt searches for `config:`
+    pub fn is_configurable(&self) -> bool {
+        matches!(
+            self,
+            NodeType::Model(_)
+                | NodeType::Analysis(_)
+                | NodeType::Test(_)
+                | NodeType::Snapshot(_)
+                | NodeType::Operation(_)
+                | NodeType::Seed(_)
+                | NodeType::Rpc(_)
+                | NodeType::SqlOperation(_)
+                | NodeType::Source(_)
+        )
+    }
+
+    pub fn is_versioned(&self) -> bool {
+        self.version().is_some()
+    }
+
+    pub fn is_latest_version(&self) -> bool {
+        self.version().is_some() && self.version() == self.latest_version()
+    }
 }
 
 use crate::dbt_node_selector::*;
@@ -165,11 +244,9 @@ pub trait WrapperNodeExt {
     fn original_file_path(&self) -> &String;
     fn config(&self) -> &HashMap<String, String>;
     fn tags(&self) -> &HashSet<String>;
-    fn has_tag(&self, tag: &String) -> bool;
     fn resource_type(&self) -> &NodeType;
 
     fn fqn(&self) -> Option<Vec<String>>;
-    fn same_content(&self, other: &Self) -> bool;
 }
 
 /// These getters are just for ergomics, though we do
@@ -199,9 +276,6 @@ impl WrapperNodeExt for WrapperNode {
     fn tags(&self) -> &HashSet<UniqueId> {
         &self.common.tags
     }
-    fn has_tag(&self, tag: &String) -> bool {
-        self.common.tags.contains(tag)
-    }
     fn resource_type(&self) -> &NodeType {
         &self.resource_type
     }
@@ -209,10 +283,6 @@ impl WrapperNodeExt for WrapperNode {
     fn fqn(&self) -> Option<Vec<UniqueId>> {
         self.resource_type.fqn()
     }
-
-    fn same_content(&self, other: &Self) -> bool {
-        self.resource_type.same_content(&other.resource_type)
-    }
 }
 
 use indexmap::IndexMap;
@@ -284,8 +354,9 @@ impl WrapperNode {
     pub fn depends_on_macros(&self, graph: &ParsedGraph) -> HashSet<UniqueId> {
         self.common
             .depends_on
-            .intersection(&graph.macros)
-            .map(|s| s.to_string())
+            .iter()
+            .filter(|unique_id| graph.macros.contains(*unique_id))
+            .cloned()
             .collect()
     }
 }
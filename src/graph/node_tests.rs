@@ -34,6 +34,9 @@ mod node_tests {
         assert!(NodeTypeKey::Exposure.key() == "exposure");
         assert!(NodeTypeKey::Metric.key() == "metric");
         assert!(NodeTypeKey::Group.key() == "group");
+        assert!(NodeTypeKey::SemanticModel.key() == "semantic_model");
+        assert!(NodeTypeKey::SavedQuery.key() == "saved_query");
+        assert!(NodeTypeKey::UnitTest.key() == "unit_test");
     }
 
     #[test]
@@ -52,6 +55,9 @@ mod node_tests {
         assert!(NodeTypeKey::from_key("exposure").unwrap() == NodeTypeKey::Exposure);
         assert!(NodeTypeKey::from_key("metric").unwrap() == NodeTypeKey::Metric);
         assert!(NodeTypeKey::from_key("group").unwrap() == NodeTypeKey::Group);
+        assert!(NodeTypeKey::from_key("semantic_model").unwrap() == NodeTypeKey::SemanticModel);
+        assert!(NodeTypeKey::from_key("saved_query").unwrap() == NodeTypeKey::SavedQuery);
+        assert!(NodeTypeKey::from_key("unit_test").unwrap() == NodeTypeKey::UnitTest);
     }
 
     #[test]
@@ -92,6 +98,12 @@ mod node_tests {
         assert!(NodeTypeKey::from_node_type(&get_exposure_node("l")) == NodeTypeKey::Exposure);
         assert!(NodeTypeKey::from_node_type(&get_metric_node("m")) == NodeTypeKey::Metric);
         assert!(NodeTypeKey::from_node_type(&get_group_node("n")) == NodeTypeKey::Group);
+        assert!(
+            NodeTypeKey::from_node_type(&get_semantic_model_node("o"))
+                == NodeTypeKey::SemanticModel
+        );
+        assert!(NodeTypeKey::from_node_type(&get_saved_query_node("p")) == NodeTypeKey::SavedQuery);
+        assert!(NodeTypeKey::from_node_type(&get_unit_test_node("q")) == NodeTypeKey::UnitTest);
     }
 
     fn get_wrapper_model(resource_type: NodeType) -> WrapperNode {
@@ -2,10 +2,15 @@
 #[path = "parsed_graph_tests.rs"]
 mod parsed_graph_tests;
 
+#[cfg(test)]
+#[path = "parsed_graph_property_tests.rs"]
+mod parsed_graph_property_tests;
+
 /// https://github.com/dbt-labs/dbt-core/blob/4186f99b742b47e0e95aca4f604cc09e5c67a449/core/dbt/graph/graph.py
 use std::collections::HashMap;
 
 use std::collections::HashSet;
+use std::rc::Rc;
 
 use crate::dbt_node_selector::SelectionError;
 use crate::dbt_node_selector::SelectionError::*;
@@ -16,27 +21,36 @@ use super::node::NodeTypeKey;
 use super::node::WrapperNode;
 use super::node::WrapperNodeExt;
 
+/// The maps and sets of a graph are persistent, so a clone shares them and a
+/// patched clone only copies what the patch changes
+pub type NodeMap = im_rc::HashMap<UniqueId, Rc<WrapperNode>>;
+pub type EdgeMap = im_rc::HashMap<UniqueId, NodeSet>;
+pub type NodeSet = im_rc::HashSet<UniqueId>;
+
 #[derive(Clone, Debug)]
 pub struct ParsedGraph {
-    pub node_map: HashMap<UniqueId, WrapperNode>,
-    pub children_map: HashMap<UniqueId, HashSet<UniqueId>>,
+    pub node_map: NodeMap,
+    pub children_map: EdgeMap,
     /// A map of nodes to its set of parents
-    pub parents_map: HashMap<UniqueId, HashSet<UniqueId>>,
-    pub sources: HashSet<UniqueId>,
-    pub exposures: HashSet<UniqueId>,
-    pub metrics: HashSet<UniqueId>,
-    pub macros: HashSet<UniqueId>,
+    pub parents_map: EdgeMap,
+    pub sources: NodeSet,
+    pub exposures: NodeSet,
+    pub metrics: NodeSet,
+    pub macros: NodeSet,
 }
 
 /// Currently, we do not cycle detection and assume all inputs are Directed Acyclic Graphs (DAGs)
 impl ParsedGraph {
     /// Retrieves the nodes for a subset of IDs
-    pub fn get_nodes(&self, subset_ids: &HashSet<UniqueId>) -> HashMap<UniqueId, WrapperNode> {
+    pub fn get_nodes<'a>(
+        &self,
+        subset_ids: impl IntoIterator<Item = &'a UniqueId>,
+    ) -> HashMap<UniqueId, WrapperNode> {
         subset_ids
-            .iter()
+            .into_iter()
             .filter_map(|id| {
                 let Some(node) = self.node_map.get(id) else { return None };
-                let Some(target_node) = Some(node.clone()) else { return None };
+                let Some(target_node) = Some(WrapperNode::clone(node)) else { return None };
                 Some((id.to_string(), target_node))
             })
             .collect()
@@ -58,10 +72,8 @@ impl ParsedGraph {
         self.get_nodes(&self.macros)
     }
 
-    fn reverse_edges(
-        edge_map: &HashMap<UniqueId, HashSet<UniqueId>>,
-    ) -> HashMap<UniqueId, HashSet<UniqueId>> {
-        let mut target_map: HashMap<UniqueId, HashSet<UniqueId>> = HashMap::new();
+    fn reverse_edges(edge_map: &EdgeMap) -> EdgeMap {
+        let mut target_map = EdgeMap::new();
 
         for (source_id, target_ids) in edge_map.iter() {
             for target_id in target_ids {
@@ -71,9 +83,7 @@ impl ParsedGraph {
                         targets.insert(source_id.clone());
                     }
                     None => {
-                        let mut targets = HashSet::new();
-                        targets.insert(source_id.clone());
-                        target_map.insert(target_id.clone(), targets);
+                        target_map.insert(target_id.clone(), NodeSet::unit(source_id.clone()));
                     }
                 }
             }
@@ -94,10 +104,7 @@ impl ParsedGraph {
         self.get_node_if(node_id, is_match).is_some()
     }
 
-    pub fn filter_by_resource_type(
-        included: &HashMap<UniqueId, WrapperNode>,
-        resource_type: NodeTypeKey,
-    ) -> HashSet<UniqueId> {
+    pub fn filter_by_resource_type(included: &NodeMap, resource_type: NodeTypeKey) -> NodeSet {
         included
             .iter()
             .filter_map(
@@ -109,23 +116,35 @@ impl ParsedGraph {
             .collect()
     }
 
+    /// Keeps only the edges between included nodes
+    fn filter_edges(edge_map: &EdgeMap, included: &HashSet<UniqueId>) -> EdgeMap {
+        edge_map
+            .iter()
+            .filter(|(id, _)| included.contains(*id))
+            .map(|(id, targets)| {
+                let targets = targets
+                    .iter()
+                    .filter(|target| included.contains(*target))
+                    .cloned()
+                    .collect();
+                (id.clone(), targets)
+            })
+            .collect()
+    }
+
     // Returns a subset of the Graph, does not modify original Graph.
     pub fn filter(&self, included: &HashSet<UniqueId>) -> Self {
         let mut node_map = self.node_map.clone();
         node_map.retain(|id, _node| included.contains(id));
-        let node_set: HashSet<&UniqueId> = HashSet::from_iter(node_map.keys());
+        let included: HashSet<UniqueId> = node_map.keys().cloned().collect();
         ParsedGraph::from(
             node_map,
-            self.children_map.clone(),
-            self.children_map.clone(),
+            Self::filter_edges(&self.children_map, &included),
+            Self::filter_edges(&self.parents_map, &included),
         )
     }
 
-    fn from(
-        node_map: HashMap<UniqueId, WrapperNode>,
-        children_map: HashMap<UniqueId, HashSet<UniqueId>>,
-        parents_map: HashMap<UniqueId, HashSet<UniqueId>>,
-    ) -> Self {
+    fn from(node_map: NodeMap, children_map: EdgeMap, parents_map: EdgeMap) -> Self {
         ParsedGraph {
             sources: Self::filter_by_resource_type(&node_map, NodeTypeKey::Source),
             exposures: Self::filter_by_resource_type(&node_map, NodeTypeKey::Exposure),
@@ -137,90 +156,84 @@ impl ParsedGraph {
         }
     }
 
+    fn to_node_map(node_map: HashMap<UniqueId, WrapperNode>) -> NodeMap {
+        node_map
+            .into_iter()
+            .map(|(unique_id, node)| (unique_id, Rc::new(node)))
+            .collect()
+    }
+
+    fn to_edge_map(edge_map: HashMap<UniqueId, HashSet<UniqueId>>) -> EdgeMap {
+        edge_map
+            .into_iter()
+            .map(|(unique_id, targets)| (unique_id, NodeSet::from(targets)))
+            .collect()
+    }
+
     pub fn from_children(
         node_map: HashMap<UniqueId, WrapperNode>,
         children_map: HashMap<UniqueId, HashSet<UniqueId>>,
     ) -> Self {
+        let children_map = Self::to_edge_map(children_map);
         let parents_map = Self::reverse_edges(&children_map);
-        ParsedGraph::from(node_map, children_map, parents_map)
+        ParsedGraph::from(Self::to_node_map(node_map), children_map, parents_map)
     }
 
     pub fn from_parents(
         node_map: HashMap<UniqueId, WrapperNode>,
         parents_map: HashMap<UniqueId, HashSet<UniqueId>>,
     ) -> Self {
+        let parents_map = Self::to_edge_map(parents_map);
         let children_map = Self::reverse_edges(&parents_map);
-        ParsedGraph::from(node_map, children_map, parents_map)
+        ParsedGraph::from(Self::to_node_map(node_map), children_map, parents_map)
     }
 
+    /// Breadth-first search from every selected node at once, so each node is
+    /// reached through its shortest path from the selection and `max_depth`
+    /// bounds that path. Like dbt-core, which joins the descendants of each
+    /// selected node, a selected node is part of the output when another
+    /// selected node reaches it. Each node therefore keeps the first two
+    /// distinct selected nodes which reach it, so a selected node can't count
+    /// as reaching itself through a cycle.
     fn bfs_edges(
         &self,
         selected: &HashSet<UniqueId>,
-        output: &mut HashSet<UniqueId>,
-        node_id: &UniqueId,
         max_depth: &Option<usize>,
         reverse: bool,
-    ) {
-        match max_depth {
-            Some(0) => (),
-            None | Some(_) => {
-                let immutable_output = output.clone();
-                let edges = if reverse {
-                    &self.parents_map
-                } else {
-                    &self.children_map
-                };
-                let empty_set = HashSet::new();
-                let vanguard = edges.get(node_id).unwrap_or(&empty_set);
-                let to_traverse = vanguard
-                    .iter()
-                    .filter(|id| !selected.contains(*id) && !immutable_output.contains(*id));
-                for next_id in to_traverse {
-                    output.insert(next_id.to_string());
-                    self.bfs_edges(
-                        selected,
-                        output,
-                        next_id,
-                        &max_depth.and_then(|d| Some(d - 1)),
-                        reverse,
-                    );
-                }
-            }
-        }
-    }
-
-    /// Returns all nodes reachable from `node` in `graph`
-    fn descendants(
-        &self,
-        selected: &HashSet<UniqueId>,
-        output: &mut HashSet<UniqueId>,
-        node_id: &UniqueId,
-        max_depth: &Option<usize>,
     ) -> Result<HashSet<UniqueId>, SelectionError> {
-        match self.node_map.contains_key(node_id) {
-            false => Err(NoMatchingResourceType(node_id.to_string())),
-            true => {
-                self.bfs_edges(selected, output, node_id, max_depth, false);
-                Ok(output.clone())
-            }
+        if let Some(node_id) = selected.iter().find(|id| !self.node_map.contains_key(*id)) {
+            return Err(NodeNotInGraph(node_id.to_string()));
         }
-    }
+        let edges = if reverse {
+            &self.parents_map
+        } else {
+            &self.children_map
+        };
 
-    /// Returns all nodes having a path to `node` in `graph`
-    fn ancestors(
-        &self,
-        selected: &HashSet<UniqueId>,
-        output: &mut HashSet<UniqueId>,
-        node_id: &UniqueId,
-        max_depth: &Option<usize>,
-    ) -> Result<HashSet<UniqueId>, SelectionError> {
-        match self.node_map.contains_key(node_id) {
-            false => Err(NodeNotInGraph(node_id.to_string())),
-            true => {
-                self.bfs_edges(selected, output, node_id, max_depth, true);
-                Ok(output.clone())
+        let mut reached_from: HashMap<&UniqueId, Vec<&UniqueId>> =
+            selected.iter().map(|id| (id, vec![id])).collect();
+        let mut frontier: Vec<(&UniqueId, &UniqueId)> =
+            selected.iter().map(|id| (id, id)).collect();
+        let mut depth = 0;
+        while !frontier.is_empty() && max_depth.is_none_or(|max_depth| depth < max_depth) {
+            let mut next = vec![];
+            for (node_id, origin) in frontier {
+                for next_id in edges.get(node_id).into_iter().flatten() {
+                    let origins = reached_from.entry(next_id).or_default();
+                    if origins.len() < 2 && !origins.contains(&origin) {
+                        origins.push(origin);
+                        next.push((next_id, origin));
+                    }
+                }
             }
+            frontier = next;
+            depth += 1;
         }
+        Ok(reached_from
+            .into_iter()
+            .filter(|(node_id, origins)| origins.iter().any(|origin| origin != node_id))
+            .map(|(node_id, _)| node_id.clone())
+            .collect())
     }
 
     /// Returns set of all descendants up to a max-depth
@@ -229,11 +242,7 @@ impl ParsedGraph {
         selected: &HashSet<UniqueId>,
         max_depth: &Option<usize>,
     ) -> Result<HashSet<UniqueId>, SelectionError> {
-        let mut descendants: HashSet<UniqueId> = HashSet::new();
-        for node_id in selected.iter() {
-            self.descendants(selected, &mut descendants, node_id, max_depth)?;
-        }
-        Ok(descendants)
+        self.bfs_edges(selected, max_depth, false)
     }
 
     /// Returns set of all ancestors up to a max-depth
@@ -242,11 +251,7 @@ impl ParsedGraph {
         selected: &HashSet<UniqueId>,
         max_depth: &Option<usize>,
     ) -> Result<HashSet<UniqueId>, SelectionError> {
-        let mut ancestors: HashSet<UniqueId> = HashSet::new();
-        for node_id in selected.iter() {
-            self.ancestors(selected, &mut ancestors, node_id, max_depth)?;
-        }
-        Ok(ancestors)
+        self.bfs_edges(selected, max_depth, true)
     }
 
     /// Adds parents to the selected set
@@ -267,7 +272,7 @@ impl ParsedGraph {
         selected: &HashSet<UniqueId>,
     ) -> Result<HashSet<UniqueId>, SelectionError> {
         let mut ancestors_for = self.select_children(selected, &None)?;
-        ancestors_for.extend(ancestors_for.clone().into_iter());
-        self.and_select_parents(&mut ancestors_for, &None)
+        ancestors_for.extend(selected.iter().cloned());
+        self.and_select_parents(&ancestors_for, &None)
     }
 }
@@ -0,0 +1,256 @@
+/// Invariants of the graph traversals, checked against many random DAGs.
+/// A failing case reports its seed, so it can be replayed with `Rng::new(seed)`.
+#[cfg(test)]
+mod parsed_graph_property_tests {
+    use std::collections::VecDeque;
+
+    use crate::{selector::spec::SetOperation, util::test::*};
+
+    use super::super::*;
+
+    const SEEDS: u64 = 200;
+    const MAX_DEPTH: usize = 6;
+
+    /// A random graph and a random selection from it, for every seed
+    fn cases() -> impl Iterator<Item = (u64, ParsedGraph, HashSet<UniqueId>)> {
+        (0..SEEDS).map(|seed| {
+            let mut rng = Rng::new(seed);
+            let num_nodes = 1 + rng.below(40);
+            let max_parents = 1 + rng.below(4);
+            let graph = random_dag(&mut rng, num_nodes, max_parents);
+            let selected = rng.subset(graph.node_map.keys(), 6);
+            (seed, graph, selected)
+        })
+    }
+
+    /// Shortest distance of every node reachable from `node_id`, computed
+    /// independently of `bfs_edges`
+    fn distances(edges: &EdgeMap, node_id: &UniqueId) -> HashMap<UniqueId, usize> {
+        let mut distances = HashMap::from([(node_id.clone(), 0)]);
+        let mut queue = VecDeque::from([node_id.clone()]);
+        while let Some(node_id) = queue.pop_front() {
+            let distance = distances[&node_id];
+            for next_id in edges.get(&node_id).into_iter().flatten() {
+                if !distances.contains_key(next_id) {
+                    distances.insert(next_id.clone(), distance + 1);
+                    queue.push_back(next_id.clone());
+                }
+            }
+        }
+        distances
+    }
+
+    /// The nodes within `max_depth` of any selected node, searching from each
+    /// selected node on its own like dbt-core's `select_children`
+    fn reachable(
+        edges: &EdgeMap,
+        selected: &HashSet<UniqueId>,
+        max_depth: &Option<usize>,
+    ) -> HashSet<UniqueId> {
+        selected
+            .iter()
+            .flat_map(|node_id| distances(edges, node_id))
+            .filter(|(_, distance)| *distance > 0)
+            .filter(|(_, distance)| max_depth.is_none_or(|max_depth| *distance <= max_depth))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    #[test]
+    fn children_grow_with_depth() {
+        for (seed, graph, selected) in cases() {
+            let all = graph.select_children(&selected, &None).unwrap();
+            let mut previous = HashSet::new();
+            for depth in 0..MAX_DEPTH {
+                let children = graph.select_children(&selected, &Some(depth)).unwrap();
+                assert!(
+                    previous.is_subset(&children),
+                    "seed {}, depth {}",
+                    seed,
+                    depth
+                );
+                assert!(children.is_subset(&all), "seed {}, depth {}", seed, depth);
+                previous = children;
+            }
+        }
+    }
+
+    #[test]
+    fn traversals_match_a_search_from_each_selected_node() {
+        for (seed, graph, selected) in cases() {
+            for depth in (0..MAX_DEPTH).map(Some).chain([None]) {
+                assert_eq!(
+                    graph.select_children(&selected, &depth).unwrap(),
+                    reachable(&graph.children_map, &selected, &depth),
+                    "seed {}, depth {:?}",
+                    seed,
+                    depth
+                );
+                assert_eq!(
+                    graph.select_parents(&selected, &depth).unwrap(),
+                    reachable(&graph.parents_map, &selected, &depth),
+                    "seed {}, depth {:?}",
+                    seed,
+                    depth
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parents_are_children_of_the_reversed_graph() {
+        for (seed, graph, selected) in cases() {
+            let reversed = ParsedGraph::from(
+                graph.node_map.clone(),
+                graph.parents_map.clone(),
+                graph.children_map.clone(),
+            );
+            for depth in (0..MAX_DEPTH).map(Some).chain([None]) {
+                assert_eq!(
+                    graph.select_parents(&selected, &depth).unwrap(),
+                    reversed.select_children(&selected, &depth).unwrap(),
+                    "seed {}, depth {:?}",
+                    seed,
+                    depth
+                );
+                assert_eq!(
+                    graph.select_children(&selected, &depth).unwrap(),
+                    reversed.select_parents(&selected, &depth).unwrap(),
+                    "seed {}, depth {:?}",
+                    seed,
+                    depth
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parents_and_children_are_inverse() {
+        for (seed, graph, _) in cases() {
+            for node_id in graph.node_map.keys() {
+                let node = HashSet::from([node_id.clone()]);
+                for child_id in graph.select_children(&node, &None).unwrap() {
+                    let parents = graph
+                        .select_parents(&HashSet::from([child_id.clone()]), &None)
+                        .unwrap();
+                    assert!(parents.contains(node_id), "seed {}, {}", seed, child_id);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn childrens_parents_are_parents_of_children() {
+        for (seed, graph, selected) in cases() {
+            let mut children = graph.select_children(&selected, &None).unwrap();
+            children.extend(selected.iter().cloned());
+            let mut expected = graph.select_parents(&children, &None).unwrap();
+            expected.extend(children);
+
+            let childrens_parents = graph.select_childrens_parents(&selected).unwrap();
+            assert_eq!(childrens_parents, expected, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn filter_keeps_edges_within_the_included_nodes() {
+        for (seed, graph, selected) in cases() {
+            let included = graph.select_children(&selected, &None).unwrap();
+            let filtered = graph.filter(&included);
+
+            for edges in [&filtered.children_map, &filtered.parents_map] {
+                assert!(
+                    edges.iter().all(|(id, ids)| included.contains(id)
+                        && ids.iter().all(|id| included.contains(id))),
+                    "seed {}",
+                    seed
+                );
+            }
+            let start: HashSet<UniqueId> = included.iter().take(3).cloned().collect();
+            let children = filtered.select_children(&start, &None).unwrap();
+            let unfiltered = graph.select_children(&start, &None).unwrap();
+            assert!(children.is_subset(&unfiltered), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn set_operations_obey_set_algebra() {
+        use SetOperation::*;
+
+        for seed in 0..SEEDS {
+            let mut rng = Rng::new(seed);
+            let num_nodes = 1 + rng.below(30);
+            let graph = random_dag(&mut rng, num_nodes, 2);
+            let a = rng.subset(graph.node_map.keys(), 2);
+            let b = rng.subset(graph.node_map.keys(), 2);
+            let c = rng.subset(graph.node_map.keys(), 2);
+            let empty = HashSet::new();
+            let combine = |operation: SetOperation, sets: &[&HashSet<UniqueId>]| {
+                operation.combine_selections(&sets.iter().map(|set| (*set).clone()).collect())
+            };
+
+            // Commutativity
+            assert_eq!(
+                combine(Union, &[&a, &b]),
+                combine(Union, &[&b, &a]),
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                combine(Intersection, &[&a, &b]),
+                combine(Intersection, &[&b, &a]),
+                "seed {}",
+                seed
+            );
+            // Associativity
+            let ab = combine(Union, &[&a, &b]);
+            let bc = combine(Union, &[&b, &c]);
+            assert_eq!(
+                combine(Union, &[&ab, &c]),
+                combine(Union, &[&a, &bc]),
+                "seed {}",
+                seed
+            );
+            let ab = combine(Intersection, &[&a, &b]);
+            let bc = combine(Intersection, &[&b, &c]);
+            assert_eq!(
+                combine(Intersection, &[&ab, &c]),
+                combine(Intersection, &[&a, &bc]),
+                "seed {}",
+                seed
+            );
+            // Idempotence and identities
+            assert_eq!(combine(Union, &[&a, &a]), a, "seed {}", seed);
+            assert_eq!(combine(Intersection, &[&a, &a]), a, "seed {}", seed);
+            assert_eq!(combine(Union, &[&a, &empty]), a, "seed {}", seed);
+            assert_eq!(combine(Intersection, &[&a, &empty]), empty, "seed {}", seed);
+            assert_eq!(combine(Difference, &[&a, &a]), empty, "seed {}", seed);
+            assert_eq!(combine(Difference, &[&a, &empty]), a, "seed {}", seed);
+            // Distributivity
+            let b_or_c = combine(Union, &[&b, &c]);
+            let a_and_b = combine(Intersection, &[&a, &b]);
+            let a_and_c = combine(Intersection, &[&a, &c]);
+            assert_eq!(
+                combine(Intersection, &[&a, &b_or_c]),
+                combine(Union, &[&a_and_b, &a_and_c]),
+                "seed {}",
+                seed
+            );
+            // Subtracting several sets subtracts their union (De Morgan)
+            let a_minus_b = combine(Difference, &[&a, &b]);
+            let a_minus_c = combine(Difference, &[&a, &c]);
+            assert_eq!(
+                combine(Difference, &[&a, &b, &c]),
+                combine(Intersection, &[&a_minus_b, &a_minus_c]),
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                combine(Difference, &[&a, &b, &c]),
+                combine(Difference, &[&a, &b_or_c]),
+                "seed {}",
+                seed
+            );
+        }
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod parsed_graph_tests {
-    use crate::{assert_ok, dbt_node_selector::NodeType, util::test::*};
+    use crate::{assert_err, assert_ok, dbt_node_selector::NodeType, util::test::*};
 
     use super::super::*;
 
@@ -206,8 +206,8 @@ mod parsed_graph_tests {
         let children = graph.children_map.get("he").expect("Got no children");
         let parents = graph.parents_map.get("he").expect("Got no parents");
 
-        assert_eq!(children, &vec_to_set(vec!["her", "hel"]));
-        assert_eq!(parents, &vec_to_set(vec!["h", "source_2", "origin"]));
+        assert_eq!(children, &vec_to_node_set(vec!["her", "hel"]));
+        assert_eq!(parents, &vec_to_node_set(vec!["h", "source_2", "origin"]));
     }
 
     #[test]
@@ -220,8 +220,8 @@ mod parsed_graph_tests {
         let children = graph.children_map.get("he").expect("Got no children");
         let parents = graph.parents_map.get("he").expect("Got no parents");
 
-        assert_eq!(parents, &vec_to_set(vec!["her", "hel"]));
-        assert_eq!(children, &vec_to_set(vec!["h", "source_2", "origin"]));
+        assert_eq!(parents, &vec_to_node_set(vec!["her", "hel"]));
+        assert_eq!(children, &vec_to_node_set(vec!["h", "source_2", "origin"]));
     }
 
     #[test]
@@ -477,6 +477,21 @@ mod parsed_graph_tests {
         assert_eq!(expected, children);
     }
 
+    #[test]
+    fn select_childrens_parents_of_a_leaf() {
+        // A leaf has no children, so it selects itself and its own parents
+        let graph = GraphBuilder::new()
+            .node("a", &[])
+            .node("b", &["a"])
+            .node("c", &["b"])
+            .node("d", &["a"])
+            .build();
+
+        let selected = assert_ok!(graph.select_childrens_parents(&vec_to_set(vec!["c"])));
+
+        assert_eq!(selected, vec_to_set(vec!["a", "b", "c"]));
+    }
+
     #[test]
     fn get_node_if_true() {
         let (node_map, parents_map) = get_test_data();
@@ -693,4 +708,88 @@ mod parsed_graph_tests {
             ])
         );
     }
+
+    #[test]
+    fn filter_keeps_only_edges_between_included_nodes() {
+        let (node_map, parents_map) = get_test_data();
+        let graph = ParsedGraph::from_parents(node_map, parents_map);
+
+        let filtered = graph.filter(&vec_to_set(vec!["a", "b", "origin", "missing"]));
+
+        assert_eq!(
+            filtered.node_map.keys().cloned().collect::<HashSet<_>>(),
+            vec_to_set(vec!["a", "b", "origin"])
+        );
+        assert_eq!(filtered.parents_map["b"], vec_to_node_set(vec!["a"]));
+        assert_eq!(filtered.children_map["a"], vec_to_node_set(vec!["b"]));
+        assert!(filtered
+            .children_map
+            .values()
+            .chain(filtered.parents_map.values())
+            .flatten()
+            .all(|id| filtered.node_map.contains_key(id)));
+    }
+
+    #[test]
+    fn select_children_depth_uses_shortest_path() {
+        // "a" reaches "c" both directly and through "b". The depth limit is
+        // measured along the shortest path, however the traversal reaches "c" first.
+        let mut node_map = HashMap::new();
+        let mut parents_map = HashMap::new();
+        new_node(&mut node_map, &mut parents_map, "a", vec![]);
+        new_node(&mut node_map, &mut parents_map, "b", vec!["a"]);
+        new_node(&mut node_map, &mut parents_map, "c", vec!["a", "b"]);
+        new_node(&mut node_map, &mut parents_map, "d", vec!["c"]);
+        let graph = ParsedGraph::from_parents(node_map, parents_map);
+
+        let children = assert_ok!(graph.select_children(&vec_to_set(vec!["a"]), &Some(2)));
+        assert_eq!(children, vec_to_set(vec!["b", "c", "d"]));
+
+        let parents = assert_ok!(graph.select_parents(&vec_to_set(vec!["d"]), &Some(2)));
+        assert_eq!(parents, vec_to_set(vec!["a", "b", "c"]));
+    }
+
+    #[test]
+    fn select_children_includes_selected_nodes_reached_from_others() {
+        let mut node_map = HashMap::new();
+        let mut parents_map = HashMap::new();
+        new_node(&mut node_map, &mut parents_map, "a", vec![]);
+        new_node(&mut node_map, &mut parents_map, "b", vec!["a"]);
+        new_node(&mut node_map, &mut parents_map, "c", vec!["b"]);
+        let graph = ParsedGraph::from_parents(node_map, parents_map);
+
+        let children = assert_ok!(graph.select_children(&vec_to_set(vec!["a", "b"]), &None));
+        assert_eq!(children, vec_to_set(vec!["b", "c"]));
+
+        let parents = assert_ok!(graph.select_parents(&vec_to_set(vec!["b", "c"]), &Some(1)));
+        assert_eq!(parents, vec_to_set(vec!["a", "b"]));
+    }
+
+    #[test]
+    fn select_children_of_a_cycle() {
+        // A selected node only counts as a child when another selected node
+        // reaches it, not when it reaches itself.
+        let mut node_map = HashMap::new();
+        let mut parents_map = HashMap::new();
+        new_node(&mut node_map, &mut parents_map, "a", vec!["b"]);
+        new_node(&mut node_map, &mut parents_map, "b", vec!["a"]);
+        let graph = ParsedGraph::from_parents(node_map, parents_map);
+
+        let children = assert_ok!(graph.select_children(&vec_to_set(vec!["a"]), &None));
+        assert_eq!(children, vec_to_set(vec!["b"]));
+
+        let children = assert_ok!(graph.select_children(&vec_to_set(vec!["a", "b"]), &None));
+        assert_eq!(children, vec_to_set(vec!["a", "b"]));
+    }
+
+    #[test]
+    fn select_children_missing_node() {
+        let (node_map, parents_map) = get_test_data();
+        let graph = ParsedGraph::from_parents(node_map, parents_map);
+
+        assert_err!(
+            graph.select_children(&vec_to_set(vec!["missing"]), &None),
+            Err(SelectionError::NodeNotInGraph(_))
+        );
+    }
 }
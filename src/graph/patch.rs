@@ -0,0 +1,110 @@
+#[cfg(test)]
+#[path = "patch_tests.rs"]
+mod patch_tests;
+
+/// In-place graph updates, so hosts which change a few nodes at a time (i.e.
+/// on every file save) don't rebuild the adjacency maps and node sets.
+use std::rc::Rc;
+
+use crate::dbt_node_selector::{GraphPatch, SelectorCreateError};
+
+use super::{
+    node::{NodeTypeKey, WrapperNode, WrapperNodeExt},
+    parsed_graph::{NodeSet, ParsedGraph},
+    UniqueId,
+};
+
+impl ParsedGraph {
+    /// The set of unique IDs kept for nodes of the resource type, if any
+    fn resource_type_set(&mut self, resource_type: NodeTypeKey) -> Option<&mut NodeSet> {
+        match resource_type {
+            NodeTypeKey::Source => Some(&mut self.sources),
+            NodeTypeKey::Exposure => Some(&mut self.exposures),
+            NodeTypeKey::Metric => Some(&mut self.metrics),
+            NodeTypeKey::Macro => Some(&mut self.macros),
+            _ => None,
+        }
+    }
+
+    /// Removes `child` from the children of `parent`, dropping the entry once
+    /// it has none left, as `from_parents` never creates empty ones
+    fn remove_child(&mut self, parent: &UniqueId, child: &UniqueId) {
+        if let Some(children) = self.children_map.get_mut(parent) {
+            children.remove(child);
+            if children.is_empty() {
+                self.children_map.remove(parent);
+            }
+        }
+    }
+
+    /// Removes a node along with every edge to or from it
+    pub fn delete_node(&mut self, unique_id: &UniqueId) {
+        if let Some(node) = self.node_map.remove(unique_id) {
+            if let Some(set) = self.resource_type_set(node.resource_type().key()) {
+                set.remove(unique_id);
+            }
+        }
+        for parent in self.parents_map.remove(unique_id).unwrap_or_default() {
+            self.remove_child(&parent, unique_id);
+        }
+        for child in self.children_map.remove(unique_id).unwrap_or_default() {
+            if let Some(parents) = self.parents_map.get_mut(&child) {
+                parents.remove(unique_id);
+            }
+        }
+    }
+
+    /// Adds a node, or replaces the one with the same unique ID. Edges are
+    /// left as they are.
+    pub fn upsert_node(&mut self, node: WrapperNode) {
+        let unique_id = node.unique_id().clone();
+        if let Some(previous) = self.node_map.get(&unique_id) {
+            if let Some(set) = self.resource_type_set(previous.resource_type().key()) {
+                set.remove(&unique_id);
+            }
+        }
+        if let Some(set) = self.resource_type_set(node.resource_type().key()) {
+            set.insert(unique_id.clone());
+        }
+        self.node_map.insert(unique_id, Rc::new(node));
+    }
+
+    /// Replaces the parents of a node, updating the children of the parents
+    /// it gains or loses
+    pub fn set_parents(&mut self, unique_id: &UniqueId, parents: NodeSet) {
+        let previous = self
+            .parents_map
+            .insert(unique_id.clone(), parents.clone())
+            .unwrap_or_default();
+        for parent in previous.iter().filter(|parent| !parents.contains(*parent)) {
+            self.remove_child(parent, unique_id);
+        }
+        for parent in parents.iter().filter(|parent| !previous.contains(*parent)) {
+            self.children_map
+                .entry(parent.clone())
+                .or_default()
+                .insert(unique_id.clone());
+        }
+    }
+
+    /// Applies the deletions, then the upserts, then the edges of `patch`.
+    /// Only the nodes and edges it names are touched.
+    pub fn apply_patch(&mut self, patch: &GraphPatch) -> Result<(), SelectorCreateError> {
+        // Converted first, so an invalid node leaves the graph as it was
+        let upserted = patch
+            .upsert_nodes
+            .iter()
+            .map(WrapperNode::from)
+            .collect::<Result<Vec<_>, _>>()?;
+        for unique_id in patch.delete_nodes.iter() {
+            self.delete_node(unique_id);
+        }
+        for node in upserted {
+            self.upsert_node(node);
+        }
+        for edge in patch.set_edges.iter() {
+            self.set_parents(&edge.unique_id, edge.parents.iter().cloned().collect());
+        }
+        Ok(())
+    }
+}
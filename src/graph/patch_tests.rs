@@ -0,0 +1,253 @@
+#[cfg(test)]
+mod patch_tests {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::{
+        dbt_node_selector::{Edge, GraphPatch, Node, NodeType, ResourceTypeFilter},
+        graph::parsed_graph::EdgeMap,
+        selector::node_selector::NodeSelector,
+        util::test::{
+            get_node, get_resource_type, random_dag, vec_to_node_set, vec_to_set, GraphBuilder, Rng,
+        },
+    };
+
+    use super::super::*;
+
+    fn node(unique_id: &str, node_type: NodeType) -> Node {
+        Node {
+            node_type,
+            ..get_node(unique_id)
+        }
+    }
+
+    fn edge(unique_id: &str, parents: &[&str]) -> Edge {
+        Edge {
+            unique_id: unique_id.to_string(),
+            parents: parents.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    /// source_a -> model_a -> exposure_a, and macro_a
+    fn get_graph() -> ParsedGraph {
+        GraphBuilder::new()
+            .node("source_a", &[])
+            .node("model_a", &["source_a"])
+            .node("exposure_a", &["model_a"])
+            .node("macro_a", &[])
+            .build()
+    }
+
+    /// Whether both graphs have the same nodes, edges and node sets
+    fn assert_same_graph(graph: &ParsedGraph, expected: &ParsedGraph, context: &str) {
+        let keys = |graph: &ParsedGraph| graph.node_map.keys().cloned().collect::<HashSet<_>>();
+        assert_eq!(keys(graph), keys(expected), "{}", context);
+        assert_eq!(graph.parents_map, expected.parents_map, "{}", context);
+        assert_eq!(graph.children_map, expected.children_map, "{}", context);
+        assert_eq!(graph.sources, expected.sources, "{}", context);
+        assert_eq!(graph.exposures, expected.exposures, "{}", context);
+        assert_eq!(graph.metrics, expected.metrics, "{}", context);
+        assert_eq!(graph.macros, expected.macros, "{}", context);
+    }
+
+    #[test]
+    fn deletes_nodes_with_their_edges() {
+        let mut graph = get_graph();
+        graph.delete_node(&"model_a".to_string());
+        graph.delete_node(&"macro_a".to_string());
+        // Unknown nodes are ignored
+        graph.delete_node(&"model_missing".to_string());
+
+        assert_eq!(
+            graph.node_map.keys().cloned().collect::<HashSet<_>>(),
+            vec_to_set(vec!["source_a", "exposure_a"])
+        );
+        assert!(!graph.parents_map.contains_key("model_a"));
+        assert_eq!(graph.parents_map["exposure_a"], NodeSet::new());
+        assert!(graph.children_map.is_empty());
+        assert!(graph.macros.is_empty());
+        assert_eq!(graph.sources, vec_to_node_set(vec!["source_a"]));
+    }
+
+    #[test]
+    fn upserts_keep_node_sets_current() {
+        let mut graph = get_graph();
+        // A node which changes resource type moves to the matching set
+        graph
+            .apply_patch(&GraphPatch {
+                upsert_nodes: vec![node("source_a", get_resource_type("metric_a"))],
+                delete_nodes: vec![],
+                set_edges: vec![],
+            })
+            .unwrap();
+
+        assert!(graph.sources.is_empty());
+        assert_eq!(graph.metrics, vec_to_node_set(vec!["source_a"]));
+        assert_eq!(
+            graph.children_map["source_a"],
+            vec_to_node_set(vec!["model_a"]),
+            "upserts keep the edges of the node"
+        );
+    }
+
+    #[test]
+    fn sets_edges() {
+        let mut graph = get_graph();
+        graph.set_parents(&"exposure_a".to_string(), vec_to_node_set(vec!["source_a"]));
+
+        assert!(!graph.children_map.contains_key("model_a"));
+        assert_eq!(
+            graph.children_map["source_a"],
+            vec_to_node_set(vec!["model_a", "exposure_a"])
+        );
+        assert_eq!(
+            graph.parents_map["exposure_a"],
+            vec_to_node_set(vec!["source_a"])
+        );
+    }
+
+    #[test]
+    fn random_patches_match_a_rebuild() {
+        const TYPES: &[&str] = &["model", "source", "exposure", "metric", "macro"];
+        for seed in 0..200 {
+            let mut rng = Rng::new(seed);
+            let num_nodes = 1 + rng.below(30);
+            let max_parents = 1 + rng.below(4);
+            let graph = random_dag(&mut rng, num_nodes, max_parents);
+            let mut unique_ids: Vec<UniqueId> = graph.node_map.keys().cloned().collect();
+            unique_ids.sort();
+
+            let mut subset = |one_in: usize| {
+                let mut subset: Vec<UniqueId> =
+                    rng.subset(unique_ids.iter(), one_in).into_iter().collect();
+                subset.sort();
+                subset
+            };
+            let delete_nodes = subset(5);
+            let mut upserted = subset(4);
+            upserted.extend((0..rng.below(4)).map(|index| format!("new_{}", index)));
+            let upsert_nodes: Vec<Node> = upserted
+                .iter()
+                .map(|id| node(id, get_resource_type(TYPES[rng.below(TYPES.len())])))
+                .collect();
+            unique_ids.extend(upserted.iter().cloned());
+            let set_edges: Vec<Edge> = rng
+                .subset(unique_ids.iter(), 3)
+                .into_iter()
+                .map(|id| Edge {
+                    unique_id: id,
+                    parents: (0..rng.below(3))
+                        .map(|_| unique_ids[rng.below(unique_ids.len())].clone())
+                        .collect(),
+                })
+                .collect();
+            let patch = GraphPatch {
+                upsert_nodes,
+                delete_nodes,
+                set_edges,
+            };
+
+            let mut patched = graph.clone();
+            patched.apply_patch(&patch).unwrap();
+
+            // The same patch on the lists a host would pass to `update`
+            let mut node_map = graph.get_nodes(graph.node_map.keys());
+            let mut parents_map: HashMap<UniqueId, HashSet<UniqueId>> = graph
+                .parents_map
+                .iter()
+                .map(|(unique_id, parents)| (unique_id.clone(), parents.iter().cloned().collect()))
+                .collect();
+            for unique_id in patch.delete_nodes.iter() {
+                node_map.remove(unique_id);
+                parents_map.remove(unique_id);
+                parents_map.values_mut().for_each(|parents| {
+                    parents.remove(unique_id);
+                });
+            }
+            for node in patch.upsert_nodes.iter() {
+                node_map.insert(node.unique_id.clone(), WrapperNode::from(node).unwrap());
+            }
+            for edge in patch.set_edges.iter() {
+                parents_map.insert(
+                    edge.unique_id.clone(),
+                    edge.parents.iter().cloned().collect(),
+                );
+            }
+            let rebuilt = ParsedGraph::from_parents(node_map, parents_map);
+
+            assert_same_graph(&patched, &rebuilt, &format!("seed {}", seed));
+        }
+    }
+
+    #[test]
+    fn patching_copies_only_what_the_patch_changes() {
+        let graph = random_dag(&mut Rng::new(0), 1000, 3);
+        let mut patched = graph.clone();
+        patched
+            .apply_patch(&GraphPatch {
+                upsert_nodes: vec![
+                    node("model_0", get_resource_type("model_0")),
+                    node("model_new", get_resource_type("model_new")),
+                ],
+                delete_nodes: vec![],
+                set_edges: vec![edge("model_new", &["model_0"])],
+            })
+            .unwrap();
+
+        let copied_nodes = patched
+            .node_map
+            .iter()
+            .filter(|(id, node)| {
+                !graph
+                    .node_map
+                    .get(*id)
+                    .is_some_and(|old| Rc::ptr_eq(old, node))
+            })
+            .count();
+        let copied_edges = |edge_map: &EdgeMap, old_edge_map: &EdgeMap| {
+            edge_map
+                .iter()
+                .filter(|(id, ids)| !old_edge_map.get(*id).is_some_and(|old| old.ptr_eq(ids)))
+                .count()
+        };
+        assert_eq!(copied_nodes, 2);
+        assert_eq!(copied_edges(&patched.parents_map, &graph.parents_map), 1);
+        assert_eq!(copied_edges(&patched.children_map, &graph.children_map), 1);
+    }
+
+    #[test]
+    fn patched_selectors_keep_the_previous_graph() {
+        let nodes = ["source_a", "model_a"]
+            .into_iter()
+            .map(|id| node(id, get_resource_type(id)))
+            .collect();
+        let edges = vec![edge("model_a", &["source_a"])];
+        let node_selector = NodeSelector::from(nodes, edges, None).unwrap();
+
+        let patched = node_selector
+            .patched(GraphPatch {
+                upsert_nodes: vec![node("model_b", get_resource_type("model_b"))],
+                delete_nodes: vec![],
+                set_edges: vec![edge("model_b", &["model_a"])],
+            })
+            .unwrap();
+
+        let select = |selector: &str| {
+            let mut selected = patched._select(selector.to_string()).unwrap();
+            selected.sort();
+            selected
+        };
+        assert_eq!(select("model_a+"), ["model_a", "model_b"]);
+        assert_eq!(select("state:new"), ["model_b"]);
+        assert_eq!(patched._diff().unwrap().added, ["model_b"]);
+        assert_eq!(
+            patched
+                .search_index
+                .search(&patched.graph, "model_b", &ResourceTypeFilter::All, 1)[0]
+                .unique_id,
+            "model_b"
+        );
+        // The original selector is unchanged
+        assert_eq!(node_selector.graph.node_map.len(), 2);
+        assert!(node_selector.previous_state().is_none());
+    }
+}
@@ -0,0 +1,159 @@
+#[cfg(test)]
+#[path = "paths_tests.rs"]
+mod paths_tests;
+
+/// Path queries between two nodes, for "how does A flow into B" questions.
+/// Paths always go downstream, from parents to children.
+use std::collections::{HashMap, HashSet};
+
+use crate::dbt_node_selector::{SelectionError, SelectionError::*};
+
+use super::{parsed_graph::ParsedGraph, UniqueId};
+
+impl ParsedGraph {
+    fn check_in_graph(&self, node_ids: &[&UniqueId]) -> Result<(), SelectionError> {
+        match node_ids.iter().find(|id| !self.node_map.contains_key(**id)) {
+            Some(node_id) => Err(NodeNotInGraph(node_id.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Children in order, so paths are the same on every call
+    fn sorted_children(&self, node_id: &UniqueId) -> Vec<&UniqueId> {
+        let mut children: Vec<&UniqueId> = self
+            .children_map
+            .get(node_id)
+            .map(|children| children.iter().collect())
+            .unwrap_or_default();
+        children.sort();
+        children
+    }
+
+    /// Returns the nodes of a shortest path from `upstream` to `downstream`,
+    /// both included, or an empty path when `downstream` isn't a descendant
+    /// of `upstream`
+    pub fn shortest_path(
+        &self,
+        upstream: &UniqueId,
+        downstream: &UniqueId,
+    ) -> Result<Vec<UniqueId>, SelectionError> {
+        self.check_in_graph(&[upstream, downstream])?;
+
+        let mut predecessors: HashMap<&UniqueId, &UniqueId> = HashMap::new();
+        let mut frontier = vec![upstream];
+        while !frontier.is_empty()
+            && upstream != downstream
+            && !predecessors.contains_key(downstream)
+        {
+            let mut next = vec![];
+            for node_id in frontier {
+                for child in self.sorted_children(node_id) {
+                    if child != upstream && !predecessors.contains_key(child) {
+                        predecessors.insert(child, node_id);
+                        next.push(child);
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        if upstream != downstream && !predecessors.contains_key(downstream) {
+            return Ok(vec![]);
+        }
+        let mut path = vec![downstream.to_string()];
+        let mut node_id = downstream;
+        while let Some(predecessor) = predecessors.get(node_id) {
+            path.push(predecessor.to_string());
+            node_id = predecessor;
+        }
+        path.reverse();
+        Ok(path)
+    }
+
+    /// Returns every node on some path from `upstream` to `downstream`, both
+    /// included, i.e. the descendants of `upstream` which are also ancestors
+    /// of `downstream`. Sorted, and empty when there is no path.
+    pub fn between(
+        &self,
+        upstream: &UniqueId,
+        downstream: &UniqueId,
+    ) -> Result<Vec<UniqueId>, SelectionError> {
+        let mut between: Vec<UniqueId> = self
+            .between_set(upstream, downstream)?
+            .into_iter()
+            .cloned()
+            .collect();
+        between.sort();
+        Ok(between)
+    }
+
+    fn between_set<'a>(
+        &'a self,
+        upstream: &'a UniqueId,
+        downstream: &'a UniqueId,
+    ) -> Result<HashSet<&'a UniqueId>, SelectionError> {
+        self.check_in_graph(&[upstream, downstream])?;
+        if upstream == downstream {
+            return Ok(HashSet::from([upstream]));
+        }
+
+        let descendants = self.select_children(&HashSet::from([upstream.clone()]), &None)?;
+        if !descendants.contains(downstream) {
+            return Ok(HashSet::new());
+        }
+        let ancestors = self.select_parents(&HashSet::from([downstream.clone()]), &None)?;
+        let mut between: HashSet<&UniqueId> = self
+            .node_map
+            .keys()
+            .filter(|node_id| descendants.contains(*node_id) && ancestors.contains(*node_id))
+            .collect();
+        between.extend([upstream, downstream]);
+        Ok(between)
+    }
+
+    /// Returns the paths from `upstream` to `downstream`, ordered by the
+    /// unique IDs along them, stopping after `limit` paths. A `limit` of 0
+    /// returns every path, of which there can be exponentially many.
+    pub fn all_paths(
+        &self,
+        upstream: &UniqueId,
+        downstream: &UniqueId,
+        limit: usize,
+    ) -> Result<Vec<Vec<UniqueId>>, SelectionError> {
+        // Only nodes between the two can be part of a path, which keeps the
+        // search from walking into branches which never reach `downstream`
+        let between = self.between_set(upstream, downstream)?;
+        let mut paths = vec![];
+        if !between.is_empty() {
+            let mut path = vec![upstream];
+            self.extend_paths(&between, downstream, limit, &mut path, &mut paths);
+        }
+        Ok(paths)
+    }
+
+    fn extend_paths<'a>(
+        &'a self,
+        between: &HashSet<&UniqueId>,
+        downstream: &UniqueId,
+        limit: usize,
+        path: &mut Vec<&'a UniqueId>,
+        paths: &mut Vec<Vec<UniqueId>>,
+    ) {
+        let node_id = path[path.len() - 1];
+        if node_id == downstream {
+            paths.push(path.iter().map(|node_id| node_id.to_string()).collect());
+            return;
+        }
+        for child in self.sorted_children(node_id) {
+            if limit != 0 && paths.len() >= limit {
+                return;
+            }
+            // A node already on the path would be a cycle
+            if between.contains(child) && !path.contains(&child) {
+                path.push(child);
+                self.extend_paths(between, downstream, limit, path, paths);
+                path.pop();
+            }
+        }
+    }
+}
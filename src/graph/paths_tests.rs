@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod paths_tests {
+    use crate::{assert_err, dbt_node_selector::SelectionError::*, util::test::GraphBuilder};
+
+    use super::super::*;
+
+    /// a -> b -> c -> d, a -> d, a -> e -> f, and g on its own
+    fn get_graph() -> ParsedGraph {
+        GraphBuilder::new()
+            .node("a", &[])
+            .node("b", &["a"])
+            .node("c", &["b"])
+            .node("d", &["c", "a"])
+            .node("e", &["a"])
+            .node("f", &["e"])
+            .node("g", &[])
+            .build()
+    }
+
+    fn ids(ids: &[&str]) -> Vec<UniqueId> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn shortest_path_follows_children() {
+        let graph = get_graph();
+        let shortest_path = |a: &str, b: &str| graph.shortest_path(&a.into(), &b.into()).unwrap();
+
+        assert_eq!(shortest_path("a", "d"), ids(&["a", "d"]));
+        assert_eq!(shortest_path("b", "d"), ids(&["b", "c", "d"]));
+        assert_eq!(shortest_path("a", "f"), ids(&["a", "e", "f"]));
+        assert_eq!(shortest_path("a", "a"), ids(&["a"]));
+        // Paths only go downstream
+        assert_eq!(shortest_path("d", "a"), ids(&[]));
+        assert_eq!(shortest_path("a", "g"), ids(&[]));
+    }
+
+    #[test]
+    fn between_intersects_descendants_and_ancestors() {
+        let graph = get_graph();
+        let between = |a: &str, b: &str| graph.between(&a.into(), &b.into()).unwrap();
+
+        assert_eq!(between("a", "d"), ids(&["a", "b", "c", "d"]));
+        assert_eq!(between("b", "d"), ids(&["b", "c", "d"]));
+        assert_eq!(between("a", "f"), ids(&["a", "e", "f"]));
+        assert_eq!(between("c", "c"), ids(&["c"]));
+        assert_eq!(between("d", "a"), ids(&[]));
+        assert_eq!(between("e", "d"), ids(&[]));
+    }
+
+    #[test]
+    fn all_paths_are_ordered_and_limited() {
+        let graph = get_graph();
+        let all_paths =
+            |a: &str, b: &str, limit| graph.all_paths(&a.into(), &b.into(), limit).unwrap();
+
+        assert_eq!(
+            all_paths("a", "d", 0),
+            [ids(&["a", "b", "c", "d"]), ids(&["a", "d"])]
+        );
+        assert_eq!(all_paths("a", "d", 1), [ids(&["a", "b", "c", "d"])]);
+        assert_eq!(all_paths("a", "d", 5).len(), 2);
+        assert_eq!(all_paths("c", "c", 0), [ids(&["c"])]);
+        assert_eq!(all_paths("d", "a", 0), Vec::<Vec<UniqueId>>::new());
+    }
+
+    #[test]
+    fn all_paths_counts_every_combination() {
+        // A ladder of `n` diamonds has 2^n paths from top to bottom
+        let n = 10;
+        let mut builder = GraphBuilder::new().node("top_0", &[]);
+        let mut node_ids = vec!["top_0".to_string()];
+        for i in 0..n {
+            let (top, left, right, bottom) = (
+                format!("top_{}", i),
+                format!("left_{}", i),
+                format!("right_{}", i),
+                format!("top_{}", i + 1),
+            );
+            builder = builder
+                .node(&left, &[&top])
+                .node(&right, &[&top])
+                .node(&bottom, &[&left, &right]);
+            node_ids.extend([left, right, bottom]);
+        }
+        let graph = builder.build();
+
+        let (top, bottom) = ("top_0".to_string(), format!("top_{}", n));
+        assert_eq!(graph.all_paths(&top, &bottom, 0).unwrap().len(), 1 << n);
+        assert_eq!(graph.all_paths(&top, &bottom, 7).unwrap().len(), 7);
+        assert_eq!(graph.between(&top, &bottom).unwrap().len(), node_ids.len());
+    }
+
+    #[test]
+    fn missing_nodes_are_errors() {
+        let graph = get_graph();
+        let (a, missing) = ("a".to_string(), "missing".to_string());
+
+        assert_err!(graph.shortest_path(&a, &missing), Err(NodeNotInGraph(_)));
+        assert_err!(graph.between(&missing, &a), Err(NodeNotInGraph(_)));
+        assert_err!(graph.all_paths(&a, &missing, 0), Err(NodeNotInGraph(_)));
+    }
+}
@@ -0,0 +1,471 @@
+#[cfg(test)]
+#[path = "search_index_tests.rs"]
+mod search_index_tests;
+
+/// Fuzzy search over node names, FQNs, paths and tags, for Explorer's search
+/// box. Built once per graph so each keystroke only scores candidates, then
+/// patched node by node along with the graph.
+use std::{collections::HashMap, ops::Range, rc::Rc};
+
+use crate::dbt_node_selector::{Highlight, ResourceTypeFilter, SearchField, SearchMatch, UniqueId};
+
+use super::{
+    node::{WrapperNode, WrapperNodeExt},
+    parsed_graph::ParsedGraph,
+};
+
+type Trigram = [u8; 3];
+
+/// Below this Dice coefficient, a trigram-only match is considered noise
+const MIN_TRIGRAM_SIMILARITY: f64 = 0.3;
+
+/// Subsequence matches are tried from at most this many starting positions
+const MAX_SUBSEQUENCE_STARTS: usize = 8;
+
+/// A patched index is rebuilt once more than one in this many nodes are
+/// patched, so patching stays proportional to the patch size on average
+const MAX_PATCHED_FRACTION: usize = 4;
+
+struct Document {
+    field: SearchField,
+    text: String,
+    /// `text` lowercased without changing any byte offsets
+    lower: String,
+    /// Which character classes appear in `lower`, see `char_mask`
+    mask: u64,
+    /// The distinct trigrams of `lower`, sorted
+    trigrams: Box<[Trigram]>,
+}
+
+/// The index of a whole graph, as built by `from_graph`
+struct BaseIndex {
+    unique_ids: Vec<UniqueId>,
+    /// The documents of each node, indexing into `documents`
+    node_documents: Vec<Range<u32>>,
+    documents: Vec<Document>,
+    trigrams: HashMap<Trigram, Vec<u32>>,
+}
+
+pub struct SearchIndex {
+    /// Shared by every index patched from the same one
+    base: Rc<BaseIndex>,
+    /// The documents of the nodes upserted or deleted since `base` was
+    /// built, replacing theirs in `base`. Deleted nodes have none.
+    patched: im_rc::HashMap<UniqueId, Rc<[Document]>>,
+}
+
+/// The best scoring document of a node
+#[derive(Clone, Copy)]
+struct Candidate<'a> {
+    score: f64,
+    unique_id: &'a UniqueId,
+    document: &'a Document,
+}
+
+fn char_mask(text: &str) -> u64 {
+    text.bytes().fold(0, |mask, byte| {
+        let bit = match byte {
+            b'a'..=b'z' => byte - b'a',
+            b'0'..=b'9' => 26 + byte - b'0',
+            b'_' => 36,
+            b'.' => 37,
+            b'/' => 38,
+            b'-' => 39,
+            b' ' => 40,
+            _ => 41,
+        };
+        mask | (1 << bit)
+    })
+}
+
+fn distinct_trigrams(text: &str) -> Vec<Trigram> {
+    let mut trigrams: Vec<Trigram> = text
+        .as_bytes()
+        .windows(3)
+        .map(|window| [window[0], window[1], window[2]])
+        .collect();
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    trigrams
+}
+
+fn is_boundary(byte: u8) -> bool {
+    matches!(byte, b'_' | b'.' | b'/' | b'-' | b' ')
+}
+
+fn field_weight(field: SearchField) -> f64 {
+    match field {
+        SearchField::Name => 1.0,
+        SearchField::Fqn => 0.9,
+        SearchField::Tag => 0.8,
+        SearchField::Path => 0.7,
+    }
+}
+
+/// Merges sorted byte ranges into highlights
+fn to_highlights(ranges: impl IntoIterator<Item = (usize, usize)>) -> Vec<Highlight> {
+    let mut highlights: Vec<Highlight> = vec![];
+    for (start, end) in ranges {
+        match highlights.last_mut() {
+            Some(last) if last.end as usize >= start => last.end = last.end.max(end as u32),
+            _ => highlights.push(Highlight {
+                start: start as u32,
+                end: end as u32,
+            }),
+        }
+    }
+    highlights
+}
+
+/// Greedily matches `query` as a subsequence of `text`, starting from `first`.
+/// Consecutive characters and characters at word boundaries score higher.
+/// Matched positions are pushed to `positions`, if given.
+fn score_subsequence_from(
+    text: &[u8],
+    query: &[u8],
+    first: usize,
+    mut positions: Option<&mut Vec<usize>>,
+) -> Option<f64> {
+    let mut score = 0.0;
+    let mut previous: Option<usize> = None;
+    let mut next = first;
+    for &query_byte in query {
+        let position = next + text[next..].iter().position(|&byte| byte == query_byte)?;
+        score += 1.0;
+        if position == 0 || is_boundary(text[position - 1]) {
+            score += 2.0;
+        }
+        match previous {
+            Some(previous) if previous + 1 == position => score += 3.0,
+            Some(previous) => score -= 0.05 * (position - previous - 1) as f64,
+            None => {}
+        }
+        if let Some(positions) = positions.as_mut() {
+            positions.push(position);
+        }
+        previous = Some(position);
+        next = position + 1;
+    }
+    Some(score)
+}
+
+fn subsequence_starts<'a>(text: &'a [u8], query: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+    text.iter()
+        .enumerate()
+        .filter(move |(_, &byte)| byte == query[0])
+        .map(|(position, _)| position)
+        .take(MAX_SUBSEQUENCE_STARTS)
+}
+
+/// The best of the subsequence matches from each start, normalised to at
+/// most 1 plus a bonus for an exact match
+fn score_subsequence(text: &str, query: &str) -> Option<f64> {
+    let (text, query) = (text.as_bytes(), query.as_bytes());
+    let score = subsequence_starts(text, query)
+        .filter_map(|first| score_subsequence_from(text, query, first, None))
+        .max_by(f64::total_cmp)?;
+    let exact_bonus = if text == query { 0.5 } else { 0.0 };
+    Some((score / (query.len() as f64 * 6.0)).max(0.0) + exact_bonus)
+}
+
+fn highlight_subsequence(text: &str, query: &str) -> Option<Vec<Highlight>> {
+    let (text, query) = (text.as_bytes(), query.as_bytes());
+    let first = subsequence_starts(text, query).max_by(|a, b| {
+        let score = |first| score_subsequence_from(text, query, first, None).unwrap_or(f64::MIN);
+        score(*a).total_cmp(&score(*b))
+    })?;
+    let mut positions = vec![];
+    score_subsequence_from(text, query, first, Some(&mut positions))?;
+    Some(to_highlights(
+        positions
+            .into_iter()
+            .map(|position| (position, position + 1)),
+    ))
+}
+
+/// How many of the sorted `query_trigrams` are in the sorted `trigrams`
+fn count_shared(trigrams: &[Trigram], query_trigrams: &[Trigram]) -> u16 {
+    let (mut shared, mut rest) = (0, trigrams);
+    for trigram in query_trigrams {
+        match rest.binary_search(trigram) {
+            Ok(index) => {
+                shared += 1;
+                rest = &rest[index + 1..];
+            }
+            Err(index) => rest = &rest[index..],
+        }
+    }
+    shared
+}
+
+fn highlight_trigrams(text: &str, query_trigrams: &[Trigram]) -> Vec<Highlight> {
+    let bytes = text.as_bytes();
+    to_highlights(bytes.windows(3).enumerate().filter_map(|(start, window)| {
+        let trigram = [window[0], window[1], window[2]];
+        query_trigrams
+            .binary_search(&trigram)
+            .is_ok()
+            .then_some((start, start + 3))
+    }))
+}
+
+fn documents_of(node: &WrapperNode) -> Vec<Document> {
+    let mut fields = vec![(SearchField::Name, node.name().clone())];
+    if let Some(fqn) = node.fqn() {
+        fields.push((SearchField::Fqn, fqn.join(".")));
+    }
+    fields.push((SearchField::Path, node.original_file_path().clone()));
+    let mut tags: Vec<&String> = node.tags().iter().collect();
+    tags.sort();
+    fields.extend(tags.into_iter().map(|tag| (SearchField::Tag, tag.clone())));
+
+    fields
+        .into_iter()
+        .filter(|(_, text)| !text.is_empty())
+        .map(|(field, text)| {
+            let lower = text.to_ascii_lowercase();
+            Document {
+                field,
+                mask: char_mask(&lower),
+                trigrams: distinct_trigrams(&lower).into(),
+                text,
+                lower,
+            }
+        })
+        .collect()
+}
+
+impl BaseIndex {
+    fn from_graph(graph: &ParsedGraph) -> Self {
+        // Sorted so that ties are broken the same way for every build
+        let mut unique_ids: Vec<UniqueId> = graph.node_map.keys().cloned().collect();
+        unique_ids.sort();
+
+        let mut node_documents = vec![];
+        let mut documents = vec![];
+        let mut postings: Vec<(Trigram, u32)> = vec![];
+        for unique_id in unique_ids.iter() {
+            let first = documents.len() as u32;
+            for document in documents_of(&graph.node_map[unique_id]) {
+                let document_index = documents.len() as u32;
+                postings.extend(document.trigrams.iter().map(|t| (*t, document_index)));
+                documents.push(document);
+            }
+            node_documents.push(first..documents.len() as u32);
+        }
+
+        // Cheaper than growing a posting list per trigram one at a time
+        postings.sort_unstable();
+        let mut trigrams: HashMap<Trigram, Vec<u32>> = HashMap::new();
+        for chunk in postings.chunk_by(|a, b| a.0 == b.0) {
+            trigrams.insert(chunk[0].0, chunk.iter().map(|(_, index)| *index).collect());
+        }
+
+        Self {
+            unique_ids,
+            node_documents,
+            documents,
+            trigrams,
+        }
+    }
+
+    /// How many trigrams of the query each document shares, for queries which
+    /// aren't a subsequence, i.e. with words swapped around
+    fn shared_trigrams(&self, query_trigrams: &[Trigram]) -> Vec<u16> {
+        let mut shared = vec![0; self.documents.len()];
+        for trigram in query_trigrams {
+            for &index in self.trigrams.get(trigram).into_iter().flatten() {
+                shared[index as usize] += 1;
+            }
+        }
+        shared
+    }
+}
+
+impl SearchIndex {
+    pub fn from_graph(graph: &ParsedGraph) -> Self {
+        Self {
+            base: Rc::new(BaseIndex::from_graph(graph)),
+            patched: im_rc::HashMap::new(),
+        }
+    }
+
+    /// The index of `graph`, given this one was of the graph before the nodes
+    /// of `unique_ids` were upserted or deleted. Only those nodes are indexed
+    /// again, until enough are patched that rebuilding pays off.
+    pub fn patched<'a>(
+        &self,
+        graph: &ParsedGraph,
+        unique_ids: impl IntoIterator<Item = &'a UniqueId>,
+    ) -> Self {
+        let mut patched = self.patched.clone();
+        for unique_id in unique_ids {
+            let documents = match graph.node_map.get(unique_id) {
+                Some(node) => documents_of(node),
+                None => vec![],
+            };
+            patched.insert(unique_id.clone(), documents.into());
+        }
+        if patched.len() * MAX_PATCHED_FRACTION > self.base.unique_ids.len() {
+            return Self::from_graph(graph);
+        }
+        Self {
+            base: self.base.clone(),
+            patched,
+        }
+    }
+
+    fn score(
+        document: &Document,
+        query: &str,
+        query_mask: u64,
+        query_trigrams: &[Trigram],
+        shared: impl FnOnce() -> u16,
+    ) -> Option<f64> {
+        let subsequence = match document.mask & query_mask == query_mask {
+            true => score_subsequence(&document.lower, query),
+            false => None,
+        };
+        let score = subsequence.or_else(|| {
+            let shared = shared();
+            if shared == 0 {
+                return None;
+            }
+            let similarity =
+                2.0 * shared as f64 / (query_trigrams.len() + document.trigrams.len()) as f64;
+            // Halved, as words in the wrong order are a weaker signal than a subsequence
+            (similarity >= MIN_TRIGRAM_SIMILARITY).then_some(similarity * 0.5)
+        })?;
+        Some(score * field_weight(document.field))
+    }
+
+    fn to_match(candidate: Candidate, query: &str, query_trigrams: &[Trigram]) -> SearchMatch {
+        let document = candidate.document;
+        let highlights = highlight_subsequence(&document.lower, query)
+            .unwrap_or_else(|| highlight_trigrams(&document.lower, query_trigrams));
+        SearchMatch {
+            unique_id: candidate.unique_id.clone(),
+            field: document.field,
+            text: document.text.clone(),
+            score: candidate.score,
+            highlights,
+        }
+    }
+
+    /// The best match per node, best first. A `limit` of 0 returns every match.
+    pub fn search(
+        &self,
+        graph: &ParsedGraph,
+        query: &str,
+        resource_type_filter: &ResourceTypeFilter,
+        limit: usize,
+    ) -> Vec<SearchMatch> {
+        let query = query.trim().to_ascii_lowercase();
+        if query.is_empty() {
+            return vec![];
+        }
+        let query_mask = char_mask(&query);
+        let query_trigrams = distinct_trigrams(&query);
+        let shared = self.base.shared_trigrams(&query_trigrams);
+
+        let base = &self.base;
+        let mut candidates: Vec<Candidate> = vec![];
+        for (unique_id, range) in base.unique_ids.iter().zip(base.node_documents.iter()) {
+            if !self.patched.is_empty() && self.patched.contains_key(unique_id) {
+                continue;
+            }
+            let documents = range.clone().map(|index| {
+                let document = &base.documents[index as usize];
+                let score = Self::score(document, &query, query_mask, &query_trigrams, || {
+                    shared[index as usize]
+                });
+                (document, score)
+            });
+            candidates.extend(Self::best_candidate(unique_id, documents));
+        }
+        for (unique_id, documents) in self.patched.iter() {
+            let documents = documents.iter().map(|document| {
+                let score = Self::score(document, &query, query_mask, &query_trigrams, || {
+                    count_shared(&document.trigrams, &query_trigrams)
+                });
+                (document, score)
+            });
+            candidates.extend(Self::best_candidate(unique_id, documents));
+        }
+
+        let limit = if limit == 0 { usize::MAX } else { limit };
+        let mut matches: Vec<Candidate> = vec![];
+        // Only the best `limit` candidates need sorting, unless the resource
+        // type filter rejects some of them
+        let mut sorted_count = limit.min(candidates.len());
+        loop {
+            Self::sort_best(&mut candidates, sorted_count);
+            matches.clear();
+            matches.extend(
+                candidates[..sorted_count]
+                    .iter()
+                    .filter(|candidate| {
+                        Self::should_include(graph, candidate, resource_type_filter)
+                    })
+                    .take(limit)
+                    .copied(),
+            );
+            if matches.len() == limit || sorted_count == candidates.len() {
+                break;
+            }
+            sorted_count = candidates.len().min(sorted_count.saturating_mul(4));
+        }
+        matches
+            .into_iter()
+            .map(|candidate| Self::to_match(candidate, &query, &query_trigrams))
+            .collect()
+    }
+
+    /// The first of the best scoring documents of a node, if any matches
+    fn best_candidate<'a>(
+        unique_id: &'a UniqueId,
+        documents: impl Iterator<Item = (&'a Document, Option<f64>)>,
+    ) -> Option<Candidate<'a>> {
+        documents
+            .filter_map(|(document, score)| {
+                Some(Candidate {
+                    score: score?,
+                    unique_id,
+                    document,
+                })
+            })
+            .reduce(|best, candidate| match best.score >= candidate.score {
+                true => best,
+                false => candidate,
+            })
+    }
+
+    /// Moves the best `count` candidates to the front, in order. Ties go to
+    /// the shorter text, then to the unique ID.
+    fn sort_best(candidates: &mut [Candidate], count: usize) {
+        let compare = |a: &Candidate, b: &Candidate| {
+            b.score
+                .total_cmp(&a.score)
+                .then(a.document.text.len().cmp(&b.document.text.len()))
+                .then(a.unique_id.cmp(b.unique_id))
+        };
+        if count == 0 {
+            return;
+        }
+        if count < candidates.len() {
+            candidates.select_nth_unstable_by(count - 1, compare);
+        }
+        candidates[..count].sort_unstable_by(compare);
+    }
+
+    fn should_include(
+        graph: &ParsedGraph,
+        candidate: &Candidate,
+        resource_type_filter: &ResourceTypeFilter,
+    ) -> bool {
+        graph
+            .node_map
+            .get(candidate.unique_id)
+            .is_some_and(|node| resource_type_filter.should_include(node.resource_type()))
+    }
+}
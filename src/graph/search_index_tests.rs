@@ -0,0 +1,351 @@
+#[cfg(test)]
+mod search_index_tests {
+    use crate::{
+        dbt_node_selector::SearchField,
+        util::test::{get_resource_type, random_dag, to_strings, GraphBuilder, Rng},
+    };
+
+    use std::time::{Duration, Instant};
+
+    use super::super::*;
+
+    /// (unique_id, name, original_file_path, tags)
+    fn get_graph(nodes: Vec<(&str, &str, &str, Vec<&str>)>) -> ParsedGraph {
+        nodes
+            .into_iter()
+            .fold(
+                GraphBuilder::new(),
+                |builder, (unique_id, name, path, tags)| {
+                    builder.node_with(unique_id, &[], |node| {
+                        node.name = name.to_string();
+                        node.path = path.to_string();
+                        node.original_file_path = path.to_string();
+                        node.tags = to_strings(&tags);
+                    })
+                },
+            )
+            .build()
+    }
+
+    fn get_default_graph() -> ParsedGraph {
+        get_graph(vec![
+            (
+                "model_orders",
+                "orders",
+                "models/orders.sql",
+                vec!["finance"],
+            ),
+            (
+                "model_order_items",
+                "order_items",
+                "models/order_items.sql",
+                vec![],
+            ),
+            (
+                "model_customers",
+                "stg_customers",
+                "models/staging/stg_customers.sql",
+                vec![],
+            ),
+            (
+                "test_orders",
+                "not_null_orders_id",
+                "models/schema.yml",
+                vec![],
+            ),
+            (
+                "seed_countries",
+                "countries",
+                "seeds/countries.csv",
+                vec!["nightly"],
+            ),
+        ])
+    }
+
+    fn search(graph: &ParsedGraph, query: &str) -> Vec<SearchMatch> {
+        SearchIndex::from_graph(graph).search(graph, query, &ResourceTypeFilter::All, 0)
+    }
+
+    fn ids(matches: &[SearchMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.unique_id.as_str()).collect()
+    }
+
+    fn spans(search_match: &SearchMatch) -> Vec<(u32, u32)> {
+        search_match
+            .highlights
+            .iter()
+            .map(|highlight| (highlight.start, highlight.end))
+            .collect()
+    }
+
+    #[test]
+    fn search_matches_subsequences_with_highlights() {
+        let graph = get_default_graph();
+
+        let matches = search(&graph, "ordit");
+
+        assert_eq!(ids(&matches), vec!["model_order_items"]);
+        assert_eq!(matches[0].field, SearchField::Name);
+        assert_eq!(matches[0].text, "order_items");
+        assert_eq!(spans(&matches[0]), vec![(0, 3), (6, 8)]);
+    }
+
+    #[test]
+    fn search_ranks_exact_and_prefix_matches_first() {
+        let graph = get_default_graph();
+
+        let matches = search(&graph, "orders");
+
+        assert_eq!(ids(&matches)[..2], ["model_orders", "test_orders"]);
+        assert!(matches[0].score > matches[1].score);
+    }
+
+    #[test]
+    fn search_is_case_insensitive() {
+        let graph = get_default_graph();
+
+        let matches = search(&graph, "ORDERS");
+
+        assert_eq!(ids(&matches)[0], "model_orders");
+        assert_eq!(matches[0].text, "orders");
+    }
+
+    #[test]
+    fn search_reports_the_best_field() {
+        let graph = get_default_graph();
+
+        let by_tag = search(&graph, "nightly");
+        assert_eq!(ids(&by_tag), vec!["seed_countries"]);
+        assert_eq!(by_tag[0].field, SearchField::Tag);
+
+        let by_path = search(&graph, "staging/");
+        assert_eq!(ids(&by_path), vec!["model_customers"]);
+        assert_eq!(by_path[0].field, SearchField::Path);
+        assert_eq!(spans(&by_path[0]), vec![(7, 15)]);
+    }
+
+    #[test]
+    fn search_matches_words_out_of_order() {
+        let graph = get_default_graph();
+
+        let matches = search(&graph, "customers_stg");
+
+        assert_eq!(ids(&matches), vec!["model_customers"]);
+        assert_eq!(matches[0].text, "stg_customers");
+        assert_eq!(spans(&matches[0]), vec![(0, 3), (4, 13)]);
+    }
+
+    #[test]
+    fn search_filters_resource_types_and_limits() {
+        let graph = get_default_graph();
+        let index = SearchIndex::from_graph(&graph);
+        let tests_only = ResourceTypeFilter::Some(vec![get_resource_type("test_")]);
+
+        let matches = index.search(&graph, "orders", &tests_only, 0);
+        assert_eq!(ids(&matches), vec!["test_orders"]);
+
+        let matches = index.search(&graph, "o", &ResourceTypeFilter::All, 2);
+        assert_eq!(matches.len(), 2);
+
+        let matches = index.search(&graph, "orders", &ResourceTypeFilter::None, 0);
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn search_ignores_empty_queries() {
+        let graph = get_default_graph();
+
+        assert!(search(&graph, "  ").is_empty());
+    }
+
+    #[test]
+    fn search_handles_non_ascii() {
+        let graph = get_graph(vec![("model_a", "café_orders", "models/café.sql", vec![])]);
+
+        let matches = search(&graph, "café");
+
+        assert_eq!(ids(&matches), vec!["model_a"]);
+        assert_eq!(spans(&matches[0]), vec![(0, 5)]);
+    }
+
+    /// The default graph and enough other nodes to patch a few of them
+    /// without a rebuild, optionally with the nodes of `changes` replacing
+    /// theirs (or deleting them, if they have no name)
+    fn get_large_graph(changes: &[(&str, &str, &str)]) -> ParsedGraph {
+        let mut nodes: Vec<(String, String, String)> = get_default_graph()
+            .node_map
+            .values()
+            .map(|node| {
+                let path = node.original_file_path().clone();
+                (node.unique_id().clone(), node.name().clone(), path)
+            })
+            .chain((0..20).map(|index| {
+                let name = format!("events_{}", index);
+                (
+                    format!("model_{}", name),
+                    name.clone(),
+                    format!("models/{}.sql", name),
+                )
+            }))
+            .collect();
+        for (unique_id, name, path) in changes {
+            nodes.retain(|(id, _, _)| id != unique_id);
+            if !name.is_empty() {
+                nodes.push((unique_id.to_string(), name.to_string(), path.to_string()));
+            }
+        }
+        get_graph(
+            nodes
+                .iter()
+                .map(|(id, name, path)| (id.as_str(), name.as_str(), path.as_str(), vec![]))
+                .collect(),
+        )
+    }
+
+    /// (unique_id, field, text, score bits, highlights), as `SearchMatch`
+    /// can't be compared
+    type Summary = (UniqueId, SearchField, String, u64, Vec<(u32, u32)>);
+
+    fn summary(matches: &[SearchMatch]) -> Vec<Summary> {
+        matches
+            .iter()
+            .map(|m| {
+                let score = m.score.to_bits();
+                (
+                    m.unique_id.clone(),
+                    m.field,
+                    m.text.clone(),
+                    score,
+                    spans(m),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn patched_indexes_search_like_rebuilt_ones() {
+        let graph = get_large_graph(&[]);
+        let changes = [
+            ("seed_countries", "", ""),
+            (
+                "model_customers",
+                "customers_stg",
+                "models/marts/customers_stg.sql",
+            ),
+            ("model_revenue", "revenue", "models/marts/revenue.sql"),
+        ];
+        let patched_graph = get_large_graph(&changes);
+
+        let index = SearchIndex::from_graph(&graph);
+        let changed: Vec<UniqueId> = changes.iter().map(|c| c.0.to_string()).collect();
+        let patched = index.patched(&patched_graph, &changed);
+        let rebuilt = SearchIndex::from_graph(&patched_graph);
+
+        for query in [
+            "orders",
+            "countries",
+            "stg_customers",
+            "marts",
+            "revenue",
+            "e",
+        ] {
+            assert_eq!(
+                summary(&patched.search(&patched_graph, query, &ResourceTypeFilter::All, 0)),
+                summary(&rebuilt.search(&patched_graph, query, &ResourceTypeFilter::All, 0)),
+                "{}",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn patching_indexes_only_the_patched_nodes() {
+        let graph = get_large_graph(&[]);
+        let index = SearchIndex::from_graph(&graph);
+        let patch = |index: &SearchIndex, unique_id: &str| {
+            let name = format!("{}_renamed", unique_id);
+            let path = format!("models/{}.sql", name);
+            let graph = get_large_graph(&[(unique_id, name.as_str(), path.as_str())]);
+            index.patched(&graph, [&unique_id.to_string()])
+        };
+
+        let patched = patch(&index, "model_orders");
+        assert!(Rc::ptr_eq(&patched.base, &index.base));
+        assert_eq!(patched.patched.len(), 1);
+
+        // Until patching more than a quarter of the nodes
+        let patched = (0..6).fold(patched, |index, i| {
+            patch(&index, &format!("model_events_{}", i))
+        });
+        assert!(!Rc::ptr_eq(&patched.base, &index.base));
+        assert!(patched.patched.is_empty());
+    }
+
+    /// Times building, searching and patching the index of a random graph of
+    /// 50k nodes, the size Explorer's search box has to stay interactive on.
+    /// Run with `cargo test --release search_timings -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn search_timings_on_a_large_graph() {
+        const WORDS: &[&str] = &[
+            "orders",
+            "customers",
+            "payments",
+            "revenue",
+            "events",
+            "sessions",
+            "stg",
+            "fct",
+            "dim",
+            "int",
+            "daily",
+            "weekly",
+        ];
+        let mut rng = Rng::new(0);
+        let mut graph = random_dag(&mut rng, 50_000, 3);
+        for (index, node) in graph.node_map.iter_mut() {
+            let node = Rc::make_mut(node);
+            let words: Vec<&str> = (0..3).map(|_| WORDS[rng.below(WORDS.len())]).collect();
+            node.common.name = format!("{}_{}", words.join("_"), index);
+            node.common.original_file_path = format!("models/{}/{}.sql", words[0], node.name());
+        }
+
+        let start = Instant::now();
+        let index = SearchIndex::from_graph(&graph);
+        println!("from_graph: {:?}", start.elapsed());
+
+        for query in [
+            "orders",
+            "stg_ord",
+            "cstmrs",
+            "daily revenue",
+            "models/fct",
+            "x",
+        ] {
+            let start = Instant::now();
+            let matches = index.search(&graph, query, &ResourceTypeFilter::All, 20);
+            let elapsed = start.elapsed();
+            println!(
+                "search {:?}: {:?}, {} matches",
+                query,
+                elapsed,
+                matches.len()
+            );
+            if !cfg!(debug_assertions) {
+                assert!(elapsed < Duration::from_millis(50), "{}", query);
+            }
+        }
+
+        let unique_id = graph.node_map.keys().next().unwrap().clone();
+        let mut patched_graph = graph.clone();
+        let node = Rc::make_mut(patched_graph.node_map.get_mut(&unique_id).unwrap());
+        node.common.name = "renamed_orders".to_string();
+        let start = Instant::now();
+        index.patched(&patched_graph, [&unique_id]);
+        let elapsed = start.elapsed();
+        println!("patched: {:?}", elapsed);
+        if !cfg!(debug_assertions) {
+            assert!(elapsed < Duration::from_millis(5));
+        }
+    }
+}
@@ -21,8 +21,6 @@ pub trait GraphNodeExt: ComparableContents {
 
 trait ParsedNodePrivate {
     fn should_persist_relation_docs(&self) -> bool;
-
-    fn should_persist_column_docs(&self) -> bool;
 }
 
 pub trait ParsedNodeExt: ParsedNodePrivate + GraphNodeExt {
@@ -75,10 +73,6 @@ macro_rules! impl_ParsedNodeExt {
             fn should_persist_relation_docs(&self) -> bool {
                 todo!()
             }
-
-            fn should_persist_column_docs(&self) -> bool {
-                todo!()
-            }
         }
     };
 }
@@ -95,10 +89,6 @@ macro_rules! impl_CompiledNodeExt {
             fn should_persist_relation_docs(&self) -> bool {
                 todo!()
             }
-
-            fn should_persist_column_docs(&self) -> bool {
-                todo!()
-            }
         }
 
         impl ParsedNodeExt for $T {
@@ -187,6 +177,9 @@ impl_GraphNodeExt!(OperationNode);
 impl_GraphNodeExt!(SourceNode);
 impl_GraphNodeExt!(ExposureNode);
 impl_GraphNodeExt!(MetricNode);
+impl_GraphNodeExt!(SemanticModelNode);
+impl_GraphNodeExt!(SavedQueryNode);
+impl_GraphNodeExt!(UnitTestNode);
 
 impl_ParsedNodeExt!(SeedNode);
 
@@ -232,6 +225,11 @@ impl NodeType {
             (NodeType::Group(this), NodeType::Group(other)) => this.same_content(other),
             (NodeType::Doc(this), NodeType::Doc(other)) => this.same_content(other),
             (NodeType::Macro(this), NodeType::Macro(other)) => this.same_content(other),
+            (NodeType::SemanticModel(this), NodeType::SemanticModel(other)) => {
+                this.same_content(other)
+            }
+            (NodeType::SavedQuery(this), NodeType::SavedQuery(other)) => this.same_content(other),
+            (NodeType::UnitTest(this), NodeType::UnitTest(other)) => this.same_content(other),
             (_, _) => false,
         }
     }
@@ -300,6 +298,24 @@ impl ComparableContents for MetricNode {
     }
 }
 
+impl ComparableContents for SemanticModelNode {
+    fn same_content(&self, other: &Self) -> bool {
+        self.same_fqn(other) && self.depends_on == other.depends_on
+    }
+}
+
+impl ComparableContents for SavedQueryNode {
+    fn same_content(&self, other: &Self) -> bool {
+        self.same_fqn(other) && self.depends_on == other.depends_on
+    }
+}
+
+impl ComparableContents for UnitTestNode {
+    fn same_content(&self, other: &Self) -> bool {
+        self.same_fqn(other) && self.depends_on == other.depends_on && self.model == other.model
+    }
+}
+
 impl ComparableContents for DocNode {
     /// The only thing that makes one doc different from another with the
     /// same name/package is its content
@@ -328,6 +344,8 @@ impl PartialEq for ModelNode {
             && self.depends_on == other.depends_on
             && self.access == other.access
             && self.raw_code == other.raw_code
+            && self.version == other.version
+            && self.latest_version == other.latest_version
     }
 }
 
@@ -397,6 +415,24 @@ impl PartialEq for GroupNode {
     }
 }
 
+impl PartialEq for SemanticModelNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.fqn == other.fqn && self.depends_on == other.depends_on
+    }
+}
+
+impl PartialEq for SavedQueryNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.fqn == other.fqn && self.depends_on == other.depends_on
+    }
+}
+
+impl PartialEq for UnitTestNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.fqn == other.fqn && self.depends_on == other.depends_on && self.model == other.model
+    }
+}
+
 impl PartialEq for DocNode {
     fn eq(&self, other: &Self) -> bool {
         todo!()
@@ -426,6 +462,9 @@ impl PartialEq for NodeType {
             (Self::Group(l0), Self::Group(r0)) => l0 == r0,
             (Self::Doc(l0), Self::Doc(r0)) => l0 == r0,
             (Self::Macro(l0), Self::Macro(r0)) => l0 == r0,
+            (Self::SemanticModel(l0), Self::SemanticModel(r0)) => l0 == r0,
+            (Self::SavedQuery(l0), Self::SavedQuery(r0)) => l0 == r0,
+            (Self::UnitTest(l0), Self::UnitTest(r0)) => l0 == r0,
             _ => false,
         }
     }
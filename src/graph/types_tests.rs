@@ -134,12 +134,16 @@ mod types_tests {
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
         let b = ModelNode {
             fqn: vec!["a".to_string(), "b".to_string(), "c".to_string()],
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
 
         assert!(a.eq(&b));
@@ -152,12 +156,16 @@ mod types_tests {
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
         let b = ModelNode {
             fqn: vec!["a".to_string(), "b".to_string()],
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
 
         assert!(!a.eq(&b));
@@ -170,12 +178,16 @@ mod types_tests {
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
         let b = ModelNode {
             fqn: vec!["a".to_string(), "b".to_string(), "c".to_string()],
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
 
         assert!(!a.eq(&b));
@@ -188,12 +200,16 @@ mod types_tests {
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
         let b = ModelNode {
             fqn: vec!["a".to_string(), "b".to_string(), "c".to_string()],
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
 
         assert!(!a.eq(&b));
@@ -206,12 +222,16 @@ mod types_tests {
             depends_on: vec!["other".to_string(), "another".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
         let b = ModelNode {
             fqn: vec!["a".to_string(), "b".to_string(), "c".to_string()],
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
 
         assert!(!a.eq(&b));
@@ -224,12 +244,16 @@ mod types_tests {
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
         let b = ModelNode {
             fqn: vec!["a".to_string(), "b".to_string(), "c".to_string()],
             depends_on: vec!["other".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
 
         assert!(!a.eq(&b));
@@ -242,12 +266,16 @@ mod types_tests {
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
         let b = ModelNode {
             fqn: vec!["a".to_string(), "b".to_string(), "c".to_string()],
             depends_on: vec!["other".to_string(), "thing".to_string(), "2".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
 
         assert!(!a.eq(&b));
@@ -260,12 +288,16 @@ mod types_tests {
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
         let b = ModelNode {
             fqn: vec!["a".to_string(), "b".to_string(), "c".to_string()],
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "different contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
 
         assert!(!a.eq(&b));
@@ -278,12 +310,16 @@ mod types_tests {
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Public,
+            version: None,
+            latest_version: None,
         };
         let b = ModelNode {
             fqn: vec!["a".to_string(), "b".to_string(), "c".to_string()],
             depends_on: vec!["other".to_string(), "thing".to_string()],
             raw_code: "model contents".to_string(),
             access: AccessType::Protected,
+            version: None,
+            latest_version: None,
         };
 
         assert!(!a.eq(&b));
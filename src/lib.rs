@@ -8,16 +8,39 @@ extern crate lazy_static;
 wai_bindgen_rust::export!("dbt-node-selector.wai");
 
 mod args;
+#[cfg(feature = "cli")]
+pub mod cli;
 mod graph;
+#[cfg(feature = "manifest")]
+pub mod manifest;
 mod selector;
 mod util;
 
-use crate::graph::UniqueId;
+/// The Rust API, for hosts which link the crate instead of going through WAI.
+/// `tests/rust_api.rs` selects through it alone.
+pub use crate::args::{
+    from_args, parse_difference, parse_union, parse_union_with_syntax, InputArgs,
+};
+pub use crate::graph::UniqueId;
+pub use selector::{
+    node_selector::{NodeSelector, PreviousState},
+    spec::{IndirectSelection, SelectionCriteria, SelectionGroup},
+};
+
+pub use crate::dbt_node_selector::{
+    AccessType, AnalysisNode, DocNode, Edge, ExposureNode, GroupNode, GroupOwner, MacroNode,
+    MetricNode, ModelNode, Node, NodeType, OperationNode, RpcNode, SavedQueryNode, SeedNode,
+    SelectionError, SelectorCreateError, SelectorSyntax, SemanticModelNode, SnapshotNode,
+    SourceNode, SqlOperationNode, TestNode, UnitTestNode,
+};
 
-use selector::{node_selector::NodeSelector, spec::IndirectSelection};
 use wai_bindgen_rust::Handle;
 
-use crate::dbt_node_selector::{Edge, Node, ResourceTypeFilter, SelectionError, SelectorCreateError};
+use crate::dbt_node_selector::{
+    AccessViolation, Completion, GraphDiff, GraphLayout, GraphPatch, GroupMembership, ImpactReport,
+    LayoutOptions, NodeExplanation, ResourceTypeFilter, SearchMatch, SelectionSpecRecord,
+    SelectorWarning,
+};
 
 pub struct DbtNodeSelector;
 
@@ -36,6 +59,17 @@ impl dbt_node_selector::DbtNodeSelector for DbtNodeSelector {
     ) -> Result<wai_bindgen_rust::Handle<crate::NodeSelector>, SelectorCreateError> {
         NodeSelector::_new(nodes, edges)
     }
+
+    fn parse_selector(selector: String) -> Result<Vec<SelectionSpecRecord>, SelectionError> {
+        let selection_group = parse_union(&vec![selector], false, IndirectSelection::default())?;
+        Ok(selection_group.to_records())
+    }
+
+    fn format_selector(selector: String, syntax: SelectorSyntax) -> Result<String, SelectionError> {
+        let selection_group =
+            parse_union_with_syntax(&vec![selector], false, IndirectSelection::default(), syntax)?;
+        selection_group.print()
+    }
 }
 
 //core/dbt/graph/selector.py
@@ -60,10 +94,22 @@ impl dbt_node_selector::NodeSelector for NodeSelector {
         self._update(nodes, edges)
     }
 
+    fn patch(&self, patch: GraphPatch) -> Result<Handle<Self>, SelectorCreateError> {
+        self._patch(patch)
+    }
+
     fn select(&self, selector: String) -> Result<Vec<UniqueId>, SelectionError> {
         self._select(selector)
     }
 
+    fn select_with_syntax(
+        &self,
+        selector: String,
+        syntax: SelectorSyntax,
+    ) -> Result<Vec<UniqueId>, SelectionError> {
+        self._select_with_syntax(selector, syntax)
+    }
+
     fn select_type(
         &self,
         selector: String,
@@ -80,4 +126,86 @@ impl dbt_node_selector::NodeSelector for NodeSelector {
     ) -> Result<Vec<UniqueId>, SelectionError> {
         self._select_included(included_nodes, selector, resource_type_filter)
     }
+
+    fn traverse(
+        &self,
+        specs: Vec<SelectionSpecRecord>,
+        target_ids: Vec<Vec<UniqueId>>,
+    ) -> Result<Vec<UniqueId>, SelectionError> {
+        self._traverse(specs, target_ids)
+    }
+
+    fn select_explained(&self, selector: String) -> Result<Vec<NodeExplanation>, SelectionError> {
+        self._select_explained(selector)
+    }
+
+    fn layout(
+        &self,
+        node_ids: Vec<UniqueId>,
+        options: LayoutOptions,
+    ) -> Result<GraphLayout, SelectionError> {
+        self._layout(node_ids, options)
+    }
+
+    fn complete(&self, selector: String, cursor: u32) -> Vec<Completion> {
+        self._complete(selector, cursor)
+    }
+
+    fn search(
+        &self,
+        query: String,
+        resource_type_filter: ResourceTypeFilter,
+        limit: u32,
+    ) -> Vec<SearchMatch> {
+        self._search(query, resource_type_filter, limit)
+    }
+
+    fn validate_access(&self) -> Vec<AccessViolation> {
+        self._validate_access()
+    }
+
+    fn group_members(&self, group: String) -> Result<GroupMembership, SelectionError> {
+        self._group_members(group)
+    }
+
+    fn shortest_path(
+        &self,
+        upstream: UniqueId,
+        downstream: UniqueId,
+    ) -> Result<Vec<UniqueId>, SelectionError> {
+        self._shortest_path(upstream, downstream)
+    }
+
+    fn all_paths(
+        &self,
+        upstream: UniqueId,
+        downstream: UniqueId,
+        limit: u32,
+    ) -> Result<Vec<Vec<UniqueId>>, SelectionError> {
+        self._all_paths(upstream, downstream, limit)
+    }
+
+    fn between(
+        &self,
+        upstream: UniqueId,
+        downstream: UniqueId,
+    ) -> Result<Vec<UniqueId>, SelectionError> {
+        self._between(upstream, downstream)
+    }
+
+    fn impact(&self, changed: Vec<UniqueId>) -> Result<ImpactReport, SelectionError> {
+        self._impact(changed)
+    }
+
+    fn impact_of_selection(&self, selector: String) -> Result<ImpactReport, SelectionError> {
+        self._impact_of_selection(selector)
+    }
+
+    fn analyze(&self, selector: String) -> Result<Vec<SelectorWarning>, SelectionError> {
+        self._analyze(selector)
+    }
+
+    fn diff(&self) -> Result<GraphDiff, SelectionError> {
+        self._diff()
+    }
 }
@@ -4,6 +4,8 @@ mod select_nodes_tests {
 
     use super::super::*;
     use crate::dbt_node_selector::*;
+    // The WAI trait shares its name with the struct implementing it
+    use crate::dbt_node_selector::DbtNodeSelector as _;
 
     /// Any node with an id: "PREFIX_" will have the node "PREFIX" as
     /// a direct parent.
@@ -136,6 +138,10 @@ mod select_nodes_tests {
         node_selector.unwrap()
     }
 
+    fn parse_selector(selector: &str) -> Vec<SelectionSpecRecord> {
+        crate::DbtNodeSelector::parse_selector(selector.to_string()).unwrap()
+    }
+
     fn get_expected(ids: Vec<&str>) -> Vec<String> {
         ids.iter().map(|s| s.to_string()).collect()
     }
@@ -231,4 +237,121 @@ mod select_nodes_tests {
         expected.sort();
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn it_traverses_from_target_ids() {
+        let node_selector = get_test_node_selector(get_test_nodes(), get_test_edges());
+        let records = parse_selector("1+target+ ba");
+        // The method of "1+target+" resolved elsewhere to "and", "ba" to "abs"
+        let target_ids = vec![
+            vec![],
+            vec![],
+            vec!["and".to_string()],
+            vec![],
+            vec!["abs".to_string()],
+        ];
+
+        let result = node_selector._traverse(records, target_ids);
+
+        let mut expected = get_expected(vec![
+            "an",
+            "and",
+            "andr",
+            "andre",
+            "andrew",
+            "andrew_test",
+            "abs",
+        ]);
+        let mut result = result.unwrap();
+        result.sort();
+        expected.sort();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn it_fails_to_traverse_from_missing_target_ids() {
+        let node_selector = get_test_node_selector(get_test_nodes(), get_test_edges());
+        let records = parse_selector("target");
+
+        let result =
+            node_selector._traverse(records, vec![vec![], vec![], vec!["spoon".to_string()]]);
+
+        assert!(matches!(result, Err(SelectionError::NodeNotInGraph(_))));
+    }
+
+    #[test]
+    fn it_formats_selectors() {
+        let format = |selector: &str, syntax| {
+            crate::DbtNodeSelector::format_selector(selector.to_string(), syntax)
+        };
+
+        assert_eq!(
+            format("b+0 2+a", SelectorSyntax::Dbt).unwrap(),
+            "2+fqn:a fqn:b"
+        );
+        assert_eq!(
+            format("a..b", SelectorSyntax::Extended).unwrap(),
+            "+fqn:b,fqn:a+"
+        );
+        assert_eq!(format("a..b", SelectorSyntax::Dbt).unwrap(), "fqn:a..b");
+    }
+
+    #[test]
+    fn it_only_selects_included_nodes() {
+        let node_selector = get_test_node_selector(get_test_nodes(), get_test_edges());
+        let included = get_expected(vec!["and", "andre", "andrew", "andrew_test", "ab"]);
+
+        let result =
+            node_selector._select_included(included, "and+".to_string(), ResourceTypeFilter::All);
+
+        // "andr" is skipped over rather than selected
+        let mut expected = get_expected(vec!["and", "andre", "andrew", "andrew_test"]);
+        let mut result = result.unwrap();
+        result.sort();
+        expected.sort();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn it_only_matches_methods_against_included_nodes() {
+        let node_selector = get_test_node_selector(get_test_nodes(), get_test_edges());
+
+        let result = node_selector._select_included(
+            get_expected(vec!["an", "ab", "source_a"]),
+            "resource_type:model".to_string(),
+            ResourceTypeFilter::All,
+        );
+
+        let mut expected = get_expected(vec!["an", "ab"]);
+        let mut result = result.unwrap();
+        result.sort();
+        expected.sort();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn it_filters_included_nodes_by_resource_type() {
+        let node_selector = get_test_node_selector(get_test_nodes(), get_test_edges());
+
+        let result = node_selector._select_included(
+            get_expected(vec!["source_a", "and", "andr"]),
+            "+and".to_string(),
+            ResourceTypeFilter::Some(vec![get_resource_type("source_a")]),
+        );
+
+        assert_eq!(result.unwrap(), get_expected(vec!["source_a"]));
+    }
+
+    #[test]
+    fn it_fails_to_select_from_missing_included_nodes() {
+        let node_selector = get_test_node_selector(get_test_nodes(), get_test_edges());
+
+        let result = node_selector._select_included(
+            get_expected(vec!["and", "spoon"]),
+            "and".to_string(),
+            ResourceTypeFilter::All,
+        );
+
+        assert!(matches!(result, Err(SelectionError::NodeNotInGraph(_))));
+    }
 }
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod manifest_tests {
+    use serde_json::json;
+
+    use crate::dbt_node_selector::Edge;
+
+    use super::super::*;
+
+    const FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/jaffle_shop/manifest.json"
+    );
+
+    fn parents<'a>(edges: &'a [Edge], unique_id: &str) -> &'a [UniqueId] {
+        let edge = edges.iter().find(|edge| edge.unique_id == unique_id);
+        &edge.expect("Missing edge").parents
+    }
+
+    #[test]
+    fn loads_a_manifest() {
+        let manifest = Manifest::load(FIXTURE).unwrap();
+
+        assert_eq!(manifest.nodes.len(), 32);
+        assert_eq!(manifest.edges.len(), 32);
+        assert!(manifest.selectors.is_empty());
+
+        let orders = manifest
+            .nodes
+            .iter()
+            .find(|node| node.unique_id == "model.jaffle_shop.orders")
+            .unwrap();
+        assert!(orders
+            .config
+            .contains(&("meta.owner".to_string(), "finance-team".to_string())));
+        assert_eq!(
+            parents(&manifest.edges, "model.jaffle_shop.orders"),
+            [
+                "model.jaffle_shop.stg_order_items",
+                "model.jaffle_shop.stg_orders",
+                "model.jaffle_shop.stg_payments"
+            ]
+        );
+    }
+
+    #[test]
+    fn loads_an_id_map() {
+        let id_map = json!({
+            "ids": [
+                "source.pkg.ecom.raw_orders",
+                "model.pkg.orders.v2",
+                "test.pkg.not_null_orders_id.abc123",
+                "unit_test.pkg.orders.test_total",
+            ],
+            "children": [[1], [2, 3], [], []],
+        });
+        let manifest = Manifest::from_json(&id_map.to_string()).unwrap();
+
+        let names: Vec<&str> = manifest.nodes.iter().map(|node| &node.name[..]).collect();
+        assert_eq!(
+            names,
+            ["raw_orders", "orders", "not_null_orders_id", "test_total"]
+        );
+        let NodeType::Source(source) = &manifest.nodes[0].node_type else {
+            panic!("Expected a source")
+        };
+        assert_eq!(source.fqn, ["pkg", "ecom", "raw_orders"]);
+        let NodeType::Model(model) = &manifest.nodes[1].node_type else {
+            panic!("Expected a model")
+        };
+        assert_eq!(model.fqn, ["pkg", "orders"]);
+        assert_eq!(model.version.as_deref(), Some("2"));
+
+        assert!(parents(&manifest.edges, "source.pkg.ecom.raw_orders").is_empty());
+        assert_eq!(
+            parents(&manifest.edges, "test.pkg.not_null_orders_id.abc123"),
+            ["model.pkg.orders.v2"]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_id_maps() {
+        let invalid = [
+            json!({"ids": ["model.pkg.a"], "children": [[1]]}),
+            json!({"ids": ["model.pkg.a"], "children": []}),
+            json!({"ids": ["documentation.pkg.a"], "children": [[]]}),
+            json!({"ids": ["model"], "children": [[]]}),
+        ];
+        for id_map in invalid {
+            assert!(
+                Manifest::from_json(&id_map.to_string()).is_err(),
+                "{}",
+                id_map
+            );
+        }
+    }
+}
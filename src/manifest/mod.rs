@@ -0,0 +1,351 @@
+#[cfg(test)]
+#[path = "manifest_tests.rs"]
+mod manifest_tests;
+
+/// Loads the nodes and edges of a dbt `manifest.json`, or of the compressed
+/// ID map from `graph_compression_testing`, the way a host would before handing
+/// them to the selector.
+use std::{collections::HashMap, fs, path::Path};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    dbt_node_selector::{
+        AccessType, AnalysisNode, DocNode, Edge, ExposureNode, GroupNode, GroupOwner, MacroNode,
+        MetricNode, ModelNode, Node, NodeType, OperationNode, RpcNode, SavedQueryNode, SeedNode,
+        SemanticModelNode, SnapshotNode, SourceNode, SqlOperationNode, TestNode, UnitTestNode,
+    },
+    graph::{node::NodeTypeKey, UniqueId},
+};
+
+/// Every manifest table which holds graph nodes
+const NODE_TABLES: &[&str] = &[
+    "nodes",
+    "sources",
+    "exposures",
+    "metrics",
+    "groups",
+    "semantic_models",
+    "saved_queries",
+    "unit_tests",
+];
+
+/// The `run_results.json` and `sources.json` dbt writes next to a
+/// `manifest.json`, for the `result:` and `source_status:` methods. Either is
+/// `None` when its file doesn't exist.
+pub struct RunArtifacts {
+    /// The status of each node which ran
+    pub run_results: Option<HashMap<UniqueId, String>>,
+    /// The `max_loaded_at` of each source, which sources whose freshness
+    /// check errored have none of
+    pub sources: Option<HashMap<UniqueId, Option<String>>>,
+}
+
+pub struct Manifest {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    /// The `selectors.yml` definitions of the project, by name
+    pub selectors: Map<String, Value>,
+}
+
+fn read_json(path: &Path) -> Result<Value, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+    serde_json::from_str(&contents)
+        .map_err(|err| format!("Could not load {}: {}", path.display(), err))
+}
+
+fn string(node: &Value, key: &str) -> String {
+    node[key].as_str().unwrap_or_default().to_string()
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Versions may be numbers or strings in the manifest
+fn version(value: &Value) -> Option<String> {
+    match value {
+        Value::String(version) => Some(version.clone()),
+        Value::Number(version) => Some(version.to_string()),
+        _ => None,
+    }
+}
+
+/// Flattens nested config into "meta.my_key" keys with string values, as
+/// `node.wai` expects. Lists and nulls are dropped.
+fn flatten_config(prefix: &str, config: &Map<String, Value>, output: &mut Vec<(String, String)>) {
+    for (key, value) in config {
+        let key = format!("{}{}", prefix, key);
+        match value {
+            Value::Object(nested) => flatten_config(&format!("{}.", key), nested, output),
+            Value::String(value) => output.push((key, value.clone())),
+            Value::Bool(value) => output.push((key, value.to_string())),
+            Value::Number(value) => output.push((key, value.to_string())),
+            Value::Array(_) | Value::Null => {}
+        }
+    }
+}
+
+/// Builds the node type of a manifest node, `fqn` and `depends_on` included
+fn node_type(
+    resource_type: &str,
+    node: &Value,
+    fqn: Vec<String>,
+    depends_on: Vec<String>,
+) -> Result<NodeType, String> {
+    let raw_code = string(node, "raw_code");
+    let key = NodeTypeKey::from_key(resource_type)
+        .map_err(|_| format!("Unknown resource_type '{}'", resource_type))?;
+    Ok(match key {
+        NodeTypeKey::Model => NodeType::Model(ModelNode {
+            fqn,
+            depends_on,
+            raw_code,
+            access: match node["access"].as_str() {
+                Some("public") => AccessType::Public,
+                Some("private") => AccessType::Private,
+                _ => AccessType::Protected,
+            },
+            version: version(&node["version"]),
+            latest_version: version(&node["latest_version"]),
+        }),
+        NodeTypeKey::Analysis => NodeType::Analysis(AnalysisNode {
+            fqn,
+            depends_on,
+            raw_code,
+        }),
+        NodeTypeKey::Test => NodeType::Test(TestNode {
+            fqn,
+            raw_code,
+            test_metadata_name: node["test_metadata"]["name"]
+                .as_str()
+                .map(|s| s.to_string()),
+        }),
+        NodeTypeKey::Snapshot => NodeType::Snapshot(SnapshotNode { fqn, raw_code }),
+        NodeTypeKey::Operation => NodeType::Operation(OperationNode { fqn, raw_code }),
+        NodeTypeKey::Seed => NodeType::Seed(SeedNode {
+            fqn,
+            depends_on,
+            raw_code,
+        }),
+        NodeTypeKey::Rpc => NodeType::Rpc(RpcNode {
+            fqn,
+            depends_on,
+            raw_code,
+        }),
+        NodeTypeKey::SqlOperation => NodeType::SqlOperation(SqlOperationNode {
+            fqn,
+            depends_on,
+            raw_code,
+        }),
+        NodeTypeKey::Doc => NodeType::Doc(DocNode {
+            block_contents: string(node, "block_contents"),
+        }),
+        NodeTypeKey::Source => NodeType::Source(SourceNode { fqn, raw_code }),
+        NodeTypeKey::Macro => NodeType::Macro(MacroNode {
+            macro_sql: string(node, "macro_sql"),
+            depends_on,
+        }),
+        NodeTypeKey::Exposure => NodeType::Exposure(ExposureNode { fqn, raw_code }),
+        NodeTypeKey::Metric => NodeType::Metric(MetricNode { fqn }),
+        NodeTypeKey::Group => NodeType::Group(GroupNode {
+            fqn,
+            owner: GroupOwner {
+                name: node["owner"]["name"].as_str().map(|s| s.to_string()),
+                email: node["owner"]["email"].as_str().map(|s| s.to_string()),
+            },
+        }),
+        NodeTypeKey::SemanticModel => {
+            NodeType::SemanticModel(SemanticModelNode { fqn, depends_on })
+        }
+        NodeTypeKey::SavedQuery => NodeType::SavedQuery(SavedQueryNode { fqn, depends_on }),
+        NodeTypeKey::UnitTest => NodeType::UnitTest(UnitTestNode {
+            fqn,
+            depends_on,
+            model: string(node, "model"),
+        }),
+    })
+}
+
+fn manifest_node(node: &Value) -> Result<Node, String> {
+    let resource_type = node["resource_type"].as_str().unwrap_or_default();
+    let depends_on = strings(&node["depends_on"]["nodes"]);
+    let fqn = match resource_type {
+        // Groups have no FQN in the manifest
+        "group" => vec![string(node, "package_name"), string(node, "name")],
+        _ => strings(&node["fqn"]),
+    };
+    let mut config = vec![];
+    if let Some(node_config) = node["config"].as_object() {
+        flatten_config("", node_config, &mut config);
+    }
+    Ok(Node {
+        unique_id: string(node, "unique_id"),
+        name: string(node, "name"),
+        node_type: node_type(resource_type, node, fqn, depends_on.clone())?,
+        package_name: string(node, "package_name"),
+        path: string(node, "path"),
+        original_file_path: string(node, "original_file_path"),
+        depends_on,
+        config,
+        tags: strings(&node["tags"]),
+    })
+}
+
+/// Builds a node from nothing but its unique ID, i.e.
+/// `<resource_type>.<package>.<name>[.<version or hash>]`. Sources are
+/// `source.<package>.<source>.<table>`.
+fn id_map_node(unique_id: &UniqueId) -> Result<Node, String> {
+    let parts: Vec<&str> = unique_id.split('.').collect();
+    let [resource_type, package_name, rest @ ..] = parts.as_slice() else {
+        return Err(format!("Invalid unique ID '{}'", unique_id));
+    };
+    let (name, fqn) = match (*resource_type, rest) {
+        ("source", [.., table]) => (table, rest.to_vec()),
+        ("unit_test", [.., name]) => (name, vec![*name]),
+        (_, [name, ..]) => (name, vec![*name]),
+        _ => return Err(format!("Invalid unique ID '{}'", unique_id)),
+    };
+    let fqn = [*package_name]
+        .iter()
+        .chain(fqn.iter())
+        .map(|s| s.to_string())
+        .collect();
+    let version = match (*resource_type, rest) {
+        ("model", [_, version]) => version.strip_prefix('v'),
+        _ => None,
+    };
+    let node = serde_json::json!({ "version": version });
+    Ok(Node {
+        unique_id: unique_id.clone(),
+        name: name.to_string(),
+        node_type: node_type(resource_type, &node, fqn, vec![])?,
+        package_name: package_name.to_string(),
+        path: String::new(),
+        original_file_path: String::new(),
+        depends_on: vec![],
+        config: vec![],
+        tags: vec![],
+    })
+}
+
+impl Manifest {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+        Self::from_json(&contents)
+            .map_err(|err| format!("Could not load {}: {}", path.display(), err))
+    }
+
+    /// Parses either a `manifest.json`, or an ID map of the form
+    /// `{"ids": [...], "children": [[<index>, ...], ...]}`
+    pub fn from_json(contents: &str) -> Result<Self, String> {
+        let manifest: Value = serde_json::from_str(contents).map_err(|err| err.to_string())?;
+        match (&manifest["ids"], &manifest["children"]) {
+            (Value::Array(ids), Value::Array(children)) => Self::from_id_map(ids, children),
+            _ => Self::from_manifest(&manifest),
+        }
+    }
+
+    fn from_manifest(manifest: &Value) -> Result<Self, String> {
+        let nodes: Vec<Node> = NODE_TABLES
+            .iter()
+            .filter_map(|table| manifest[*table].as_object())
+            .flat_map(|table| table.values().map(manifest_node))
+            .collect::<Result<_, _>>()?;
+        // Groups aren't in `parent_map`, but every node needs an edge
+        let edges = nodes
+            .iter()
+            .map(|node| Edge {
+                unique_id: node.unique_id.clone(),
+                parents: strings(&manifest["parent_map"][&node.unique_id]),
+            })
+            .collect();
+        let selectors = manifest["selectors"]
+            .as_object()
+            .cloned()
+            .unwrap_or_default();
+        Ok(Manifest {
+            nodes,
+            edges,
+            selectors,
+        })
+    }
+
+    fn from_id_map(ids: &[Value], children: &[Value]) -> Result<Self, String> {
+        let ids: Vec<UniqueId> = strings(&Value::Array(ids.to_vec()));
+        if ids.len() != children.len() {
+            return Err("'ids' and 'children' must have the same length".to_string());
+        }
+        let mut edges: Vec<Edge> = ids
+            .iter()
+            .map(|unique_id| Edge {
+                unique_id: unique_id.clone(),
+                parents: vec![],
+            })
+            .collect();
+        for (parent, children) in children.iter().enumerate() {
+            for child in children.as_array().into_iter().flatten() {
+                let edge = child
+                    .as_u64()
+                    .and_then(|child| edges.get_mut(child as usize))
+                    .ok_or_else(|| format!("Invalid child index {} of '{}'", child, ids[parent]))?;
+                edge.parents.push(ids[parent].clone());
+            }
+        }
+        Ok(Manifest {
+            nodes: ids.iter().map(id_map_node).collect::<Result<_, _>>()?,
+            edges,
+            selectors: Map::new(),
+        })
+    }
+}
+
+impl RunArtifacts {
+    /// Loads the artifacts in the directory of the manifest at `manifest_path`
+    pub fn load_beside(manifest_path: impl AsRef<Path>) -> Result<Self, String> {
+        let directory = manifest_path.as_ref().parent().unwrap_or(Path::new(""));
+        let load = |name: &str| {
+            let path = directory.join(name);
+            match path.exists() {
+                true => read_json(&path).map(Some),
+                false => Ok(None),
+            }
+        };
+        let run_results = load("run_results.json")?.map(|artifact| {
+            Self::results(&artifact)
+                .map(|(unique_id, result)| (unique_id, string(result, "status")))
+                .collect()
+        });
+        let sources = load("sources.json")?.map(|artifact| {
+            Self::results(&artifact)
+                .map(|(unique_id, result)| {
+                    let max_loaded_at = result["max_loaded_at"].as_str();
+                    (unique_id, max_loaded_at.map(|s| s.to_string()))
+                })
+                .collect()
+        });
+        Ok(Self {
+            run_results,
+            sources,
+        })
+    }
+
+    fn results(artifact: &Value) -> impl Iterator<Item = (UniqueId, &Value)> {
+        artifact["results"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|result| (string(result, "unique_id"), result))
+    }
+}
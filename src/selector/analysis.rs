@@ -0,0 +1,298 @@
+#[cfg(test)]
+#[path = "analysis_tests.rs"]
+mod analysis_tests;
+
+/// Static analysis of spec trees: likely mistakes which can be found without a
+/// graph, such as union members which select nothing more. Findings are
+/// warnings, as the selector still evaluates.
+use crate::dbt_node_selector::{SelectorWarning, SelectorWarningKind};
+
+use super::{
+    spec::{IndirectSelection, SelectionCriteria, SelectionGroup, SelectionSpec, SetOperation},
+    MethodName,
+};
+
+/// Methods matching each node on a single value, so two different values of
+/// them never match the same node
+const SINGLE_VALUED_METHODS: &[MethodName] = &[
+    MethodName::ResourceType,
+    MethodName::Package,
+    MethodName::Group,
+    MethodName::Access,
+    MethodName::Version,
+];
+
+impl SelectorWarningKind {
+    pub fn key(&self) -> &str {
+        match self {
+            SelectorWarningKind::Contradiction => "contradiction",
+            SelectorWarningKind::RedundantMember => "redundant-member",
+            SelectorWarningKind::ExclusionCoversInclusion => "exclusion-covers-inclusion",
+            SelectorWarningKind::RequiresPreviousState => "requires-previous-state",
+        }
+    }
+}
+
+fn warning(
+    kind: SelectorWarningKind,
+    group: &SelectionGroup,
+    message: String,
+    suggestion: Option<String>,
+) -> SelectorWarning {
+    SelectorWarning {
+        kind,
+        selector: group.normalize().raw,
+        message,
+        suggestion,
+    }
+}
+
+/// Whether the tests `outer` adds to its nodes include those `inner` adds.
+/// Eager selection adds every test the other modes could.
+fn indirect_selection_covers(outer: IndirectSelection, inner: IndirectSelection) -> bool {
+    outer == inner || outer == IndirectSelection::Eager || inner == IndirectSelection::Empty
+}
+
+fn depth_covers(outer: Option<usize>, inner: Option<usize>) -> bool {
+    match (outer, inner) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(outer), Some(inner)) => outer >= inner,
+    }
+}
+
+impl SelectionCriteria {
+    /// Whether every node this criteria selects is also selected by `other`,
+    /// judging by their methods and graph operators alone
+    fn is_covered_by(&self, other: &SelectionCriteria) -> bool {
+        let (inner, outer) = (self.normalize(), other.normalize());
+        if inner.method != outer.method
+            || inner.method_arguments != outer.method_arguments
+            || inner.value != outer.value
+            || !indirect_selection_covers(outer.indirect_selection, inner.indirect_selection)
+        {
+            return false;
+        }
+        // `@` selects the parents of the node and of all its children
+        if outer.childrens_parents {
+            return true;
+        }
+        !inner.childrens_parents
+            && (!inner.parents
+                || outer.parents && depth_covers(outer.parents_depth, inner.parents_depth))
+            && (!inner.children
+                || outer.children && depth_covers(outer.children_depth, inner.children_depth))
+    }
+
+    fn has_graph_operators(&self) -> bool {
+        let criteria = self.normalize();
+        criteria.childrens_parents || criteria.parents || criteria.children
+    }
+
+    fn has_wildcards(&self) -> bool {
+        self.value.contains(['*', '?', '['])
+    }
+}
+
+impl SelectionGroup {
+    /// The criteria of an intersection, which a node has to match all of, or
+    /// None when the group is another set operation we can't reason about
+    fn intersected_criteria(&self) -> Option<Vec<&SelectionCriteria>> {
+        match &self.spec {
+            SelectionSpec::SelectionCriteria(criteria) => Some(vec![criteria]),
+            SelectionSpec::SetOperation(SetOperation::Intersection) => {
+                let mut intersected = vec![];
+                for component in self.components.iter() {
+                    intersected.extend(component.intersected_criteria()?);
+                }
+                Some(intersected)
+            }
+            _ => None,
+        }
+    }
+
+    /// The criteria of an intersection and of intersections nested in it.
+    /// Unlike `intersected_criteria`, other set operations are skipped, as
+    /// nodes still have to match every criteria found.
+    fn collect_intersected_criteria<'a>(&'a self, criteria: &mut Vec<&'a SelectionCriteria>) {
+        match &self.spec {
+            SelectionSpec::SelectionCriteria(found) => criteria.push(found),
+            SelectionSpec::SetOperation(SetOperation::Intersection) => {
+                for component in self.components.iter() {
+                    component.collect_intersected_criteria(criteria);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether every node this group selects is also selected by `other`. An
+    /// intersection is covered when each criteria of `other` covers one of
+    /// its own.
+    fn is_covered_by(&self, other: &SelectionGroup) -> bool {
+        match (self.intersected_criteria(), other.intersected_criteria()) {
+            (Some(inner), Some(outer)) => outer
+                .iter()
+                .all(|outer| inner.iter().any(|inner| inner.is_covered_by(outer))),
+            _ => false,
+        }
+    }
+
+    /// The members of a union, or the group itself
+    fn union_members(&self) -> Vec<&SelectionGroup> {
+        match &self.spec {
+            SelectionSpec::SetOperation(SetOperation::Union) => self
+                .components
+                .iter()
+                .flat_map(|c| c.union_members())
+                .collect(),
+            _ => vec![self],
+        }
+    }
+
+    /// Lints the tree without evaluating it. `has_previous_state` is whether
+    /// `state:` methods have a previous state to compare against.
+    pub fn analyze(&self, has_previous_state: bool) -> Vec<SelectorWarning> {
+        let mut warnings = vec![];
+        self.analyze_into(has_previous_state, None, &mut warnings);
+        warnings
+    }
+
+    /// `parent` is the set operation the group is a component of. Unions and
+    /// intersections are checked along with those they are nested in.
+    fn analyze_into(
+        &self,
+        has_previous_state: bool,
+        parent: Option<&SetOperation>,
+        warnings: &mut Vec<SelectorWarning>,
+    ) {
+        match &self.spec {
+            SelectionSpec::SelectionCriteria(criteria) => {
+                if criteria.method == MethodName::State && !has_previous_state {
+                    warnings.push(warning(
+                        SelectorWarningKind::RequiresPreviousState,
+                        self,
+                        format!(
+                            "'{}' compares against a previous state, but none is loaded",
+                            self.normalize().raw
+                        ),
+                        None,
+                    ));
+                }
+            }
+            SelectionSpec::Traversal(_) => {}
+            SelectionSpec::SetOperation(operation) => {
+                match operation {
+                    _ if parent == Some(operation) && operation != &SetOperation::Difference => {}
+                    SetOperation::Intersection => self.find_contradictions(warnings),
+                    SetOperation::Union => self.find_redundant_members(warnings),
+                    SetOperation::Difference => self.find_covering_exclusions(warnings),
+                }
+                for component in self.components.iter() {
+                    component.analyze_into(has_previous_state, Some(operation), warnings);
+                }
+            }
+        }
+    }
+
+    fn find_contradictions(&self, warnings: &mut Vec<SelectorWarning>) {
+        let mut intersected = vec![];
+        self.collect_intersected_criteria(&mut intersected);
+        // Graph operators select neighbors, which may have the other value
+        let exact: Vec<&SelectionCriteria> = intersected
+            .into_iter()
+            .filter(|criteria| {
+                SINGLE_VALUED_METHODS.contains(&criteria.method)
+                    && !criteria.has_graph_operators()
+                    && !criteria.has_wildcards()
+            })
+            .collect();
+        for (index, a) in exact.iter().enumerate() {
+            let contradicting = exact[index + 1..].iter().find(|b| {
+                a.method == b.method
+                    && a.method_arguments == b.method_arguments
+                    && a.value != b.value
+            });
+            if let Some(b) = contradicting {
+                warnings.push(warning(
+                    SelectorWarningKind::Contradiction,
+                    self,
+                    format!(
+                        "No node matches both '{}' and '{}'",
+                        a.normalize().raw,
+                        b.normalize().raw
+                    ),
+                    None,
+                ));
+                return;
+            }
+        }
+    }
+
+    fn find_redundant_members(&self, warnings: &mut Vec<SelectorWarning>) {
+        let members = self.union_members();
+        let mut kept = vec![];
+        let mut redundant = vec![];
+        for (index, member) in members.iter().enumerate() {
+            // Of identical members, only the first is kept
+            let covering = members.iter().enumerate().find(|(other_index, other)| {
+                *other_index != index
+                    && member.is_covered_by(other)
+                    && (*other_index < index || !other.is_covered_by(member))
+            });
+            match covering {
+                None => kept.push((*member).clone()),
+                Some((_, other)) => redundant.push((member, other)),
+            }
+        }
+        if redundant.is_empty() {
+            return;
+        }
+        let suggestion = SelectionGroup::union(
+            self.raw.clone(),
+            kept,
+            self.indirect_selection,
+            self.expect_exists,
+        )
+        .print()
+        .ok();
+        for (member, other) in redundant {
+            warnings.push(warning(
+                SelectorWarningKind::RedundantMember,
+                member,
+                format!(
+                    "'{}' selects nothing '{}' doesn't",
+                    member.normalize().raw,
+                    other.normalize().raw
+                ),
+                suggestion.clone(),
+            ));
+        }
+    }
+
+    fn find_covering_exclusions(&self, warnings: &mut Vec<SelectorWarning>) {
+        let Some((included, excluded)) = self.components.split_first() else {
+            return;
+        };
+        let excluded: Vec<&SelectionGroup> =
+            excluded.iter().flat_map(|c| c.union_members()).collect();
+        let included_members = included.union_members();
+        let covered = !excluded.is_empty()
+            && included_members.iter().all(|member| {
+                excluded
+                    .iter()
+                    .any(|exclusion| member.is_covered_by(exclusion))
+            });
+        if covered {
+            warnings.push(warning(
+                SelectorWarningKind::ExclusionCoversInclusion,
+                self,
+                format!(
+                    "The exclusions remove every node '{}' selects, so nothing is selected",
+                    included.normalize().raw
+                ),
+                None,
+            ));
+        }
+    }
+}
@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod analysis_tests {
+    use crate::{
+        args::{parse_difference, parse_union},
+        dbt_node_selector::{SelectorSyntax, SelectorWarning, SelectorWarningKind},
+    };
+
+    use super::super::*;
+
+    fn parse(selector: &str) -> SelectionGroup {
+        parse_union(
+            &vec![selector.to_string()],
+            false,
+            IndirectSelection::default(),
+        )
+        .unwrap()
+    }
+
+    fn criteria(selector: &str) -> SelectionGroup {
+        let criteria =
+            SelectionCriteria::from_single_spec(selector, &IndirectSelection::default()).unwrap();
+        SelectionGroup::from_criteria(criteria)
+    }
+
+    fn analyze(selector: &str) -> Vec<SelectorWarning> {
+        parse(selector).analyze(true)
+    }
+
+    fn kinds(warnings: &[SelectorWarning]) -> Vec<&str> {
+        warnings.iter().map(|warning| warning.kind.key()).collect()
+    }
+
+    #[test]
+    fn finds_contradictions() {
+        let warnings = analyze("resource_type:model,resource_type:seed");
+        assert_eq!(kinds(&warnings), ["contradiction"]);
+        assert_eq!(
+            warnings[0].selector,
+            "resource_type:model,resource_type:seed"
+        );
+        assert_eq!(
+            warnings[0].message,
+            "No node matches both 'resource_type:model' and 'resource_type:seed'"
+        );
+
+        assert_eq!(
+            kinds(&analyze("package:a,tag:x,package:b")),
+            ["contradiction"]
+        );
+        assert_eq!(
+            kinds(&analyze("access:public,access:private")),
+            ["contradiction"]
+        );
+        // Graph operators reach neighbors, which can have the other value
+        assert!(analyze("resource_type:model+,resource_type:seed").is_empty());
+        // Nodes can have many tags, and wildcards match many packages
+        assert!(analyze("tag:a,tag:b").is_empty());
+        assert!(analyze("package:a*,package:ab").is_empty());
+        assert!(analyze("package:a,package:a").is_empty());
+    }
+
+    #[test]
+    fn finds_contradictions_nested_in_definitions() {
+        // As `selectors.yml` nests them, rather than a selector string
+        let definition = SelectionGroup::intersection(
+            "definition".to_string(),
+            vec![
+                parse("a b"),
+                criteria("resource_type:model"),
+                SelectionGroup::intersection(
+                    "nested".to_string(),
+                    vec![criteria("resource_type:seed"), criteria("c")],
+                    IndirectSelection::default(),
+                    false,
+                ),
+            ],
+            IndirectSelection::default(),
+            false,
+        );
+        let warnings = definition.analyze(true);
+        assert_eq!(kinds(&warnings), ["contradiction"]);
+    }
+
+    #[test]
+    fn finds_redundant_union_members() {
+        let warnings = analyze("a a+ b");
+        assert_eq!(kinds(&warnings), ["redundant-member"]);
+        assert_eq!(warnings[0].selector, "fqn:a");
+        assert_eq!(
+            warnings[0].message,
+            "'fqn:a' selects nothing 'fqn:a+' doesn't"
+        );
+        assert_eq!(warnings[0].suggestion, Some("fqn:a+ fqn:b".to_string()));
+
+        // Only the second of two identical members is redundant
+        let warnings = analyze("+a b +a");
+        assert_eq!(kinds(&warnings), ["redundant-member"]);
+        assert_eq!(warnings[0].suggestion, Some("+fqn:a fqn:b".to_string()));
+
+        assert_eq!(kinds(&analyze("a+1 a+2")), ["redundant-member"]);
+        assert_eq!(kinds(&analyze("2+a+ @a")), ["redundant-member"]);
+        assert_eq!(kinds(&analyze("a,tag:x a")), ["redundant-member"]);
+        assert!(analyze("a+2 a+ +a").len() == 1);
+        assert!(analyze("a+ +a").is_empty());
+        assert!(analyze("a,tag:x a,tag:y").is_empty());
+    }
+
+    #[test]
+    fn indirect_selection_limits_redundancy() {
+        let union = |a: IndirectSelection, b: IndirectSelection| {
+            let member = |indirect_selection| {
+                parse_union(&vec!["a".to_string()], false, indirect_selection)
+                    .unwrap()
+                    .components
+                    .remove(0)
+            };
+            SelectionGroup::union(
+                "a a".to_string(),
+                vec![member(a), member(b)],
+                IndirectSelection::Eager,
+                false,
+            )
+            .analyze(true)
+        };
+
+        // Eager adds every test cautious selection would
+        assert_eq!(
+            kinds(&union(
+                IndirectSelection::Cautious,
+                IndirectSelection::Eager
+            )),
+            ["redundant-member"]
+        );
+        assert_eq!(
+            union(IndirectSelection::Cautious, IndirectSelection::Eager)[0].selector,
+            "fqn:a"
+        );
+        assert_eq!(
+            kinds(&union(IndirectSelection::Eager, IndirectSelection::Empty)),
+            ["redundant-member"]
+        );
+        assert!(union(IndirectSelection::Cautious, IndirectSelection::Buildable).is_empty());
+    }
+
+    #[test]
+    fn finds_exclusions_covering_the_inclusion() {
+        let difference = |select: &str, exclude: &str| {
+            parse_difference(
+                &Some(vec![select.to_string()]),
+                &Some(vec![exclude.to_string()]),
+                IndirectSelection::default(),
+                SelectorSyntax::Dbt,
+            )
+            .unwrap()
+            .analyze(true)
+        };
+
+        let warnings = difference("a b", "b a+");
+        assert_eq!(kinds(&warnings), ["exclusion-covers-inclusion"]);
+        assert_eq!(warnings[0].selector, "fqn:a fqn:b --exclude fqn:a+ fqn:b");
+        assert_eq!(
+            warnings[0].message,
+            "The exclusions remove every node 'fqn:a fqn:b' selects, so nothing is selected"
+        );
+        // Exclusions are eager, so they cover cautious inclusions too
+        let cautious = parse_difference(
+            &Some(vec!["a".to_string()]),
+            &Some(vec!["a".to_string()]),
+            IndirectSelection::Cautious,
+            SelectorSyntax::Dbt,
+        )
+        .unwrap();
+        assert_eq!(
+            kinds(&cautious.analyze(true)),
+            ["exclusion-covers-inclusion"]
+        );
+
+        assert!(difference("a+", "a").is_empty());
+        assert!(difference("a b", "a").is_empty());
+    }
+
+    #[test]
+    fn flags_state_methods_without_previous_state() {
+        let selection_group = parse_union(
+            &vec!["state:modified+ tag:x,state:new".to_string()],
+            false,
+            IndirectSelection::default(),
+        )
+        .unwrap();
+
+        let warnings = selection_group.analyze(false);
+        assert_eq!(
+            kinds(&warnings),
+            ["requires-previous-state", "requires-previous-state"]
+        );
+        assert_eq!(warnings[0].selector, "state:modified+");
+        assert_eq!(
+            warnings[0].message,
+            "'state:modified+' compares against a previous state, but none is loaded"
+        );
+        assert!(selection_group.analyze(true).is_empty());
+    }
+
+    #[test]
+    fn nested_unions_are_checked_once() {
+        let definition = SelectionGroup::union(
+            "definition".to_string(),
+            vec![parse("a"), parse("a b")],
+            IndirectSelection::default(),
+            false,
+        );
+        let warnings = definition.analyze(true);
+        assert_eq!(kinds(&warnings), ["redundant-member"]);
+        assert_eq!(warnings[0].kind, SelectorWarningKind::RedundantMember);
+    }
+}
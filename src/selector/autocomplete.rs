@@ -0,0 +1,298 @@
+#[cfg(test)]
+#[path = "autocomplete_tests.rs"]
+mod autocomplete_tests;
+
+/// Completions for partially typed selectors, i.e. for a search box.
+use std::{collections::HashSet, path::Path};
+
+use crate::{
+    dbt_node_selector::{Completion, CompletionKind, NodeType},
+    graph::{node::WrapperNodeExt, parsed_graph::ParsedGraph},
+};
+
+use super::{
+    methods::VERSION_SELECTORS, state_selector_method::StateSelectorMethod, AccessType, MethodName,
+};
+
+const CONFIG_PREFIX: &str = "config.";
+
+/// How well `candidate` matches the typed `prefix`, if at all. Prefix matches
+/// beat matches at a word boundary, which beat any other substring, which beat
+/// a subsequence. Within each tier, candidates closer in length score higher.
+fn score(candidate: &str, prefix: &str) -> Option<f64> {
+    let candidate_lower = candidate.to_lowercase();
+    let prefix = prefix.to_lowercase();
+    let coverage = prefix.len() as f64 / candidate_lower.len().max(1) as f64;
+    if candidate_lower.starts_with(&prefix) {
+        return Some(3.0 + coverage);
+    }
+    if let Some(position) = candidate_lower.find(&prefix) {
+        let at_boundary = candidate_lower[..position].ends_with(['_', '.', '/', ' ']);
+        return Some(if at_boundary { 2.0 } else { 1.0 } + coverage);
+    }
+    let mut chars = candidate_lower.chars();
+    prefix
+        .chars()
+        .all(|c| chars.any(|candidate_char| candidate_char == c))
+        .then_some(coverage)
+}
+
+/// The token being typed, with any graph operator prefix removed
+struct Token<'a> {
+    text: &'a str,
+    start: usize,
+}
+
+impl<'a> Token<'a> {
+    fn at_cursor(selector: &'a str, cursor: usize) -> Self {
+        let mut cursor = cursor.min(selector.len());
+        while !selector.is_char_boundary(cursor) {
+            cursor -= 1;
+        }
+        let before_cursor = &selector[..cursor];
+        let mut start = before_cursor.rfind([' ', ',']).map_or(0, |i| i + 1);
+        if before_cursor[start..].starts_with('@') {
+            start += 1;
+        }
+        let digits = before_cursor[start..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(cursor - start);
+        if before_cursor[start + digits..].starts_with('+') {
+            start += digits + 1;
+        }
+        Self {
+            text: &before_cursor[start..],
+            start,
+        }
+    }
+
+    fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+}
+
+struct Completions {
+    completions: Vec<Completion>,
+    seen: HashSet<(String, u8)>,
+}
+
+impl Completions {
+    fn new() -> Self {
+        Self {
+            completions: vec![],
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Adds each candidate which matches `prefix`, replacing `prefix` which
+    /// ends at `end`. `suffix` is appended to the completed text.
+    fn extend<'a>(
+        &mut self,
+        candidates: impl IntoIterator<Item = &'a str>,
+        kind: CompletionKind,
+        prefix: &str,
+        end: usize,
+        suffix: &str,
+    ) {
+        for candidate in candidates {
+            let Some(score) = score(candidate, prefix) else {
+                continue;
+            };
+            let text = format!("{}{}", candidate, suffix);
+            if !self.seen.insert((text.clone(), kind as u8)) {
+                continue;
+            }
+            self.completions.push(Completion {
+                text,
+                kind,
+                score,
+                replace_start: (end - prefix.len()) as u32,
+                replace_end: end as u32,
+            });
+        }
+    }
+
+    fn sorted(mut self) -> Vec<Completion> {
+        self.completions.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then((a.kind as u8).cmp(&(b.kind as u8)))
+                .then(a.text.cmp(&b.text))
+        });
+        self.completions
+    }
+}
+
+impl ParsedGraph {
+    fn node_names(&self) -> impl Iterator<Item = &str> {
+        self.node_map.values().map(|node| node.name().as_str())
+    }
+
+    fn tag_names(&self) -> impl Iterator<Item = &str> {
+        self.node_map
+            .values()
+            .flat_map(|node| node.tags().iter().map(|tag| tag.as_str()))
+    }
+
+    fn package_names(&self) -> impl Iterator<Item = &str> {
+        self.node_map
+            .values()
+            .map(|node| node.package_name().as_str())
+    }
+
+    fn config_keys(&self) -> impl Iterator<Item = &str> {
+        self.node_map
+            .values()
+            .flat_map(|node| node.config().keys().map(|key| key.as_str()))
+    }
+
+    fn config_values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.node_map
+            .values()
+            .filter_map(move |node| node.config().get(key).map(|value| value.as_str()))
+    }
+
+    /// Both configured groups and the group nodes themselves
+    fn group_names(&self) -> impl Iterator<Item = &str> {
+        self.config_values("group").chain(
+            self.node_map
+                .values()
+                .filter(|node| matches!(node.resource_type(), NodeType::Group(_)))
+                .map(|node| node.name().as_str()),
+        )
+    }
+
+    /// `source_name` and `source_name.table_name` for every source
+    fn source_names(&self) -> Vec<String> {
+        self.node_map
+            .values()
+            .filter_map(|node| match node.resource_type() {
+                NodeType::Source(source) => Some(source.fqn.clone()),
+                _ => None,
+            })
+            .flat_map(|fqn| match fqn.as_slice() {
+                [.., source_name, table_name] => {
+                    vec![
+                        source_name.clone(),
+                        format!("{}.{}", source_name, table_name),
+                    ]
+                }
+                _ => vec![],
+            })
+            .collect()
+    }
+
+    /// Completions for the selector token which ends at byte offset `cursor`
+    pub fn complete(&self, selector: &str, cursor: usize) -> Vec<Completion> {
+        let token = Token::at_cursor(selector, cursor);
+        let end = token.end();
+        let mut completions = Completions::new();
+
+        if let Some((method, value)) = token.text.split_once(':') {
+            self.complete_value(&mut completions, method, value, end);
+        } else if let Some(key) = token.text.strip_prefix(CONFIG_PREFIX) {
+            completions.extend(self.config_keys(), CompletionKind::ConfigKey, key, end, ":");
+        } else {
+            let method_keys = MethodName::all().iter().map(|method| method.key());
+            completions.extend(method_keys, CompletionKind::Method, token.text, end, ":");
+            completions.extend(self.node_names(), CompletionKind::Node, token.text, end, "");
+        }
+
+        completions.sorted()
+    }
+
+    fn complete_value(&self, completions: &mut Completions, method: &str, value: &str, end: usize) {
+        let mut method_arguments = method.split('.');
+        let method_name = method_arguments.next().and_then(MethodName::from_string);
+        let Some(method_name) = method_name else {
+            return;
+        };
+
+        match method_name {
+            MethodName::FQN => {
+                completions.extend(self.node_names(), CompletionKind::Node, value, end, "");
+            }
+            MethodName::Tag => {
+                completions.extend(self.tag_names(), CompletionKind::Tag, value, end, "");
+            }
+            MethodName::Package => {
+                completions.extend(
+                    self.package_names(),
+                    CompletionKind::Package,
+                    value,
+                    end,
+                    "",
+                );
+            }
+            MethodName::Group => {
+                completions.extend(self.group_names(), CompletionKind::Group, value, end, "");
+            }
+            MethodName::Source => {
+                let source_names = self.source_names();
+                let source_names = source_names.iter().map(|name| name.as_str());
+                completions.extend(source_names, CompletionKind::Source, value, end, "");
+            }
+            MethodName::Config => {
+                let key = method_arguments.collect::<Vec<&str>>().join(".");
+                let values = self.config_values(&key);
+                completions.extend(values, CompletionKind::ConfigValue, value, end, "");
+            }
+            MethodName::ResourceType => {
+                let resource_types: HashSet<String> = self
+                    .node_map
+                    .values()
+                    .map(|node| node.resource_type().key().key().to_string())
+                    .collect();
+                let resource_types = resource_types.iter().map(|key| key.as_str());
+                completions.extend(resource_types, CompletionKind::ResourceType, value, end, "");
+            }
+            MethodName::State => {
+                let selectors = StateSelectorMethod::SELECTORS
+                    .iter()
+                    .filter(|selector| {
+                        !StateSelectorMethod::UNSUPPORTED_SELECTORS.contains(selector)
+                    })
+                    .copied();
+                completions.extend(selectors, CompletionKind::StateSelector, value, end, "");
+            }
+            MethodName::Access => {
+                let access_types = AccessType::all().iter().map(|access| access.key());
+                completions.extend(access_types, CompletionKind::AccessType, value, end, "");
+            }
+            MethodName::Version => {
+                let selectors = VERSION_SELECTORS.iter().copied();
+                completions.extend(selectors, CompletionKind::VersionSelector, value, end, "");
+            }
+            MethodName::Exposure
+            | MethodName::Metric
+            | MethodName::SemanticModel
+            | MethodName::SavedQuery
+            | MethodName::UnitTest => {
+                let names = self
+                    .node_map
+                    .values()
+                    .filter(|node| {
+                        Some(node.resource_type().key()) == method_name.named_resource_type()
+                    })
+                    .map(|node| node.name().as_str());
+                completions.extend(names, CompletionKind::Node, value, end, "");
+            }
+            MethodName::Path => {
+                let paths = self
+                    .node_map
+                    .values()
+                    .map(|node| node.original_file_path().as_str());
+                completions.extend(paths, CompletionKind::Node, value, end, "");
+            }
+            MethodName::File => {
+                let file_names = self.node_map.values().filter_map(|node| {
+                    Path::new(node.original_file_path())
+                        .file_name()
+                        .and_then(|file_name| file_name.to_str())
+                });
+                completions.extend(file_names, CompletionKind::Node, value, end, "");
+            }
+            _ => {}
+        }
+    }
+}
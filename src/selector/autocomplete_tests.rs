@@ -0,0 +1,190 @@
+#[cfg(test)]
+mod autocomplete_tests {
+    use crate::{
+        dbt_node_selector::SourceNode,
+        util::test::{to_config, to_strings, GraphBuilder},
+    };
+
+    use super::super::*;
+
+    fn get_graph() -> ParsedGraph {
+        GraphBuilder::new()
+            .node_with("model_orders", &[], |node| {
+                node.name = "orders".to_string();
+                node.package_name = "jaffle_shop".to_string();
+                node.path = "orders.sql".to_string();
+                node.original_file_path = "models/marts/orders.sql".to_string();
+                node.tags = to_strings(&["nightly", "finance"]);
+                node.config = to_config(&[("materialized", "table"), ("group", "finance")]);
+            })
+            .node_with("model_order_items", &[], |node| {
+                node.name = "order_items".to_string();
+                node.package_name = "jaffle_shop".to_string();
+                node.path = "order_items.sql".to_string();
+                node.original_file_path = "models/staging/order_items.sql".to_string();
+                node.tags = to_strings(&["hourly"]);
+                node.config = to_config(&[("materialized", "view")]);
+            })
+            .node_with("source_raw_orders", &[], |node| {
+                node.name = "orders".to_string();
+                node.package_name = "dbt_utils".to_string();
+                node.path = "sources.yml".to_string();
+                node.original_file_path = "models/sources.yml".to_string();
+                node.node_type = NodeType::Source(SourceNode {
+                    fqn: to_strings(&["jaffle_shop", "raw", "orders"]),
+                    raw_code: "".to_string(),
+                });
+            })
+            .node_with("group_marketing", &[], |node| {
+                node.name = "marketing".to_string();
+                node.package_name = "jaffle_shop".to_string();
+                node.path = "groups.yml".to_string();
+                node.original_file_path = "models/groups.yml".to_string();
+            })
+            .build()
+    }
+
+    fn texts(completions: &[Completion]) -> Vec<&str> {
+        completions
+            .iter()
+            .map(|completion| completion.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn completes_method_names_for_an_empty_token() {
+        let completions = get_graph().complete("", 0);
+
+        assert!(completions
+            .iter()
+            .any(|c| c.text == "tag:" && c.kind == CompletionKind::Method));
+        assert!(completions
+            .iter()
+            .any(|c| c.text == "orders" && c.kind == CompletionKind::Node));
+        // Methods win ties with node names
+        assert_eq!(completions[0].kind, CompletionKind::Method);
+    }
+
+    #[test]
+    fn ranks_prefix_matches_first() {
+        let completions = get_graph().complete("ord", 3);
+
+        assert_eq!(texts(&completions), vec!["orders", "order_items"]);
+        assert_eq!(completions[0].kind, CompletionKind::Node);
+        assert_eq!(
+            (completions[0].replace_start, completions[0].replace_end),
+            (0, 3)
+        );
+        assert!(completions[0].score > completions[1].score);
+    }
+
+    #[test]
+    fn completes_values_of_the_typed_method() {
+        let graph = get_graph();
+
+        assert_eq!(texts(&graph.complete("tag:ni", 6)), vec!["nightly"]);
+        assert_eq!(
+            texts(&graph.complete("package:", 8)),
+            vec!["dbt_utils", "jaffle_shop"]
+        );
+        assert_eq!(
+            texts(&graph.complete("group:", 6)),
+            vec!["finance", "marketing"]
+        );
+        assert_eq!(
+            texts(&graph.complete("source:raw", 10)),
+            vec!["raw", "raw.orders"]
+        );
+        assert_eq!(
+            texts(&graph.complete("config.materialized:t", 21)),
+            vec!["table"]
+        );
+        assert_eq!(
+            texts(&graph.complete("resource_type:s", 15)),
+            vec!["source"]
+        );
+    }
+
+    #[test]
+    fn completes_version_selectors() {
+        let completions = get_graph().complete("version:p", 9);
+
+        assert_eq!(texts(&completions), vec!["prerelease"]);
+        assert_eq!(completions[0].kind, CompletionKind::VersionSelector);
+    }
+
+    #[test]
+    fn completes_config_keys() {
+        let completions = get_graph().complete("config.mat", 10);
+
+        assert_eq!(texts(&completions), vec!["materialized:"]);
+        assert_eq!(completions[0].kind, CompletionKind::ConfigKey);
+        assert_eq!(
+            (completions[0].replace_start, completions[0].replace_end),
+            (7, 10)
+        );
+    }
+
+    #[test]
+    fn completes_exposure_and_metric_names() {
+        let graph = GraphBuilder::new()
+            .node_with("exposure_weekly_orders", &[], |node| {
+                node.name = "weekly_orders".to_string();
+            })
+            .node_with("metric_order_count", &[], |node| {
+                node.name = "order_count".to_string();
+            })
+            .build();
+
+        assert_eq!(
+            texts(&graph.complete("exposure:week", 13)),
+            vec!["weekly_orders"]
+        );
+        assert_eq!(
+            texts(&graph.complete("metric:order", 12)),
+            vec!["order_count"]
+        );
+    }
+
+    #[test]
+    fn unsupported_selectors_are_not_completed() {
+        assert!(get_graph().complete("state:modified.p", 16).is_empty());
+    }
+
+    #[test]
+    fn completes_state_selectors() {
+        let completions = get_graph().complete("state:modified.b", 16);
+
+        assert_eq!(texts(&completions), vec!["modified.body"]);
+        assert_eq!(completions[0].kind, CompletionKind::StateSelector);
+    }
+
+    #[test]
+    fn completes_the_token_at_the_cursor() {
+        let graph = get_graph();
+
+        let completions = graph.complete("my_model 2+tag:hour+ other", 19);
+
+        assert_eq!(texts(&completions), vec!["hourly"]);
+        assert_eq!(
+            (completions[0].replace_start, completions[0].replace_end),
+            (15, 19)
+        );
+
+        let completions = graph.complete("a,@order_", 9);
+        assert_eq!(texts(&completions), vec!["order_items"]);
+        assert_eq!(completions[0].replace_start, 3);
+    }
+
+    #[test]
+    fn unknown_methods_have_no_completions() {
+        assert!(get_graph().complete("tga:n", 5).is_empty());
+    }
+
+    #[test]
+    fn cursor_is_clamped_to_the_selector() {
+        let completions = get_graph().complete("tag:fin", 100);
+
+        assert_eq!(texts(&completions), vec!["finance"]);
+    }
+}
@@ -0,0 +1,159 @@
+#[cfg(test)]
+#[path = "cache_tests.rs"]
+mod cache_tests;
+
+/// Memoised selection results. Evaluating a selector only depends on the
+/// graph, the previous state and the parsed spec tree, so results are keyed
+/// by the normalized tree, which ignores how the selector was written.
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::dbt_node_selector::ResourceTypeFilter;
+use crate::graph::UniqueId;
+
+use super::{
+    node_selector::{DirectNodes, IndirectNodes},
+    spec::{SelectionCriteria, SelectionGroup, SelectionSpec, SetOperation},
+};
+
+/// Entries kept per map before the cache starts over, which bounds memory for
+/// hosts generating many distinct selectors
+const CAPACITY: usize = 1024;
+
+#[derive(Default)]
+pub struct SelectionCache {
+    /// Direct and indirect nodes of every evaluated group and subgroup
+    groups: RefCell<HashMap<String, (DirectNodes, IndirectNodes)>>,
+    /// Final selections, keyed by group and resource type filter
+    selections: RefCell<HashMap<(String, String), HashSet<UniqueId>>>,
+}
+
+fn insert_bounded<K: Eq + Hash, V>(map: &RefCell<HashMap<K, V>>, key: K, value: V) {
+    let mut map = map.borrow_mut();
+    if map.len() >= CAPACITY && !map.contains_key(&key) {
+        map.clear();
+    }
+    map.insert(key, value);
+}
+
+impl SelectionCache {
+    pub fn get_group(&self, key: &str) -> Option<(DirectNodes, IndirectNodes)> {
+        self.groups.borrow().get(key).cloned()
+    }
+
+    pub fn insert_group(&self, key: String, nodes: (DirectNodes, IndirectNodes)) {
+        insert_bounded(&self.groups, key, nodes);
+    }
+
+    pub fn get_selection(&self, key: &(String, String)) -> Option<HashSet<UniqueId>> {
+        self.selections.borrow().get(key).cloned()
+    }
+
+    pub fn insert_selection(&self, key: (String, String), selected: HashSet<UniqueId>) {
+        insert_bounded(&self.selections, key, selected);
+    }
+
+    /// Forgets every result, for when what they were computed from changes
+    pub fn clear(&self) {
+        self.groups.borrow_mut().clear();
+        self.selections.borrow_mut().clear();
+    }
+
+    /// Number of cached groups and subgroups
+    pub fn group_count(&self) -> usize {
+        self.groups.borrow().len()
+    }
+
+    /// Number of cached final selections
+    pub fn selection_count(&self) -> usize {
+        self.selections.borrow().len()
+    }
+}
+
+pub fn resource_type_filter_key(resource_type_filter: &ResourceTypeFilter) -> String {
+    match resource_type_filter {
+        ResourceTypeFilter::All => "all".to_string(),
+        ResourceTypeFilter::None => "none".to_string(),
+        ResourceTypeFilter::Some(types) => {
+            let mut keys: Vec<String> = types.iter().map(|t| t.key().key().to_string()).collect();
+            keys.sort();
+            keys.dedup();
+            keys.join(",")
+        }
+    }
+}
+
+impl SelectionCriteria {
+    fn cache_key(&self) -> String {
+        let parents = match (self.childrens_parents, self.parents, self.parents_depth) {
+            (true, _, _) => "@".to_string(),
+            (false, true, Some(depth)) => format!("{}+", depth),
+            (false, true, None) => "+".to_string(),
+            (false, false, _) => "".to_string(),
+        };
+        let children = match (self.children, self.children_depth) {
+            (true, Some(depth)) => format!("+{}", depth),
+            (true, None) => "+".to_string(),
+            (false, _) => "".to_string(),
+        };
+        let method = std::iter::once(self.method.key().to_string())
+            .chain(self.method_arguments.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(".");
+        format!(
+            "{}{}:{:?}{}[{}]",
+            parents,
+            method,
+            self.value,
+            children,
+            self.indirect_selection.key()
+        )
+    }
+}
+
+impl SelectionGroup {
+    /// A key which is the same for every spec tree selecting the same nodes
+    /// in the same way, which is the case when their normalized trees match.
+    pub fn cache_key(&self) -> String {
+        self.normalize().normalized_cache_key()
+    }
+
+    /// The cache key of a tree which is already normalized. Unlike the
+    /// canonical selector string, every tree has one and values are quoted,
+    /// so no two trees share a key.
+    pub fn normalized_cache_key(&self) -> String {
+        match &self.spec {
+            SelectionSpec::SelectionCriteria(criteria) => criteria.cache_key(),
+            SelectionSpec::Traversal(traversal) => {
+                let mut target_ids: Vec<&UniqueId> = traversal.target_ids.iter().collect();
+                target_ids.sort();
+                format!("{}{:?}", traversal.criteria.cache_key(), target_ids)
+            }
+            SelectionSpec::SetOperation(operation) => {
+                let components: Vec<String> = self
+                    .components
+                    .iter()
+                    .map(|c| c.normalized_cache_key())
+                    .collect();
+                let name = match operation {
+                    SetOperation::Intersection => "intersection",
+                    SetOperation::Difference => "difference",
+                    SetOperation::Union => "union",
+                };
+                format!(
+                    "{}[{}{}]({})",
+                    name,
+                    self.indirect_selection.key(),
+                    match self.expect_exists {
+                        true => ",expect_exists",
+                        false => "",
+                    },
+                    components.join(" ")
+                )
+            }
+        }
+    }
+}
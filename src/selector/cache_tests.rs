@@ -0,0 +1,225 @@
+#[cfg(test)]
+mod cache_tests {
+    use std::rc::Rc;
+
+    use crate::{
+        args::{parse_difference, parse_union},
+        assert_err,
+        dbt_node_selector::{Edge, Node, SelectionError, SelectorSyntax},
+        selector::{
+            node_selector::{NodeSelector, PreviousState},
+            spec::IndirectSelection,
+        },
+        util::test::{
+            get_model_node, get_node, get_resource_type, to_strings, vec_to_set, GraphBuilder,
+        },
+    };
+
+    use super::super::*;
+
+    /// source_a -> a -> b -> test_b
+    const EDGES: [(&str, &[&str]); 4] = [
+        ("source_a", &[]),
+        ("a", &["source_a"]),
+        ("b", &["a"]),
+        ("test_b", &["b"]),
+    ];
+
+    fn get_node_selector(edges: &[(&str, &[&str])]) -> NodeSelector {
+        let nodes = edges
+            .iter()
+            .map(|(unique_id, parents)| Node {
+                depends_on: to_strings(parents),
+                ..get_node(*unique_id)
+            })
+            .collect();
+        let edges = edges
+            .iter()
+            .map(|(unique_id, parents)| Edge {
+                unique_id: unique_id.to_string(),
+                parents: parents.iter().map(|s| s.to_string()).collect(),
+            })
+            .collect();
+        NodeSelector::from(nodes, edges, None).unwrap()
+    }
+
+    fn parse(selector: &str) -> SelectionGroup {
+        parse_union(
+            &vec![selector.to_string()],
+            false,
+            IndirectSelection::default(),
+        )
+        .unwrap()
+    }
+
+    fn select(
+        node_selector: &NodeSelector,
+        selector: &str,
+    ) -> Result<HashSet<UniqueId>, SelectionError> {
+        node_selector.get_selected(&parse(selector))
+    }
+
+    #[test]
+    fn keys_ignore_how_selectors_are_written() {
+        let key = |selector: &str| parse(selector).cache_key();
+
+        assert_eq!(key("a b"), key("b a"));
+        assert_eq!(key("a b a"), key("b a"));
+        assert_eq!(key("a,b+"), key("b+,a"));
+        assert_eq!(key("path:models/a.sql"), key("models/a.sql"));
+        assert_ne!(key("a"), key("a+"));
+        assert_ne!(key("a+1"), key("a+"));
+        assert_ne!(key("a+"), key("+a"));
+        assert_ne!(key("tag:a"), key("a"));
+        assert_ne!(key("a b"), key("a,b"));
+
+        let cautious = parse_union(&vec!["a".to_string()], false, IndirectSelection::Cautious);
+        assert_ne!(cautious.unwrap().cache_key(), key("a"));
+    }
+
+    #[test]
+    fn keys_keep_what_differences_subtract_from() {
+        let difference = |include: &[&str], exclude: &[&str]| {
+            let words = |words: &[&str]| Some(words.iter().map(|w| w.to_string()).collect());
+            parse_difference(
+                &words(include),
+                &words(exclude),
+                IndirectSelection::default(),
+                SelectorSyntax::Dbt,
+            )
+            .unwrap()
+            .cache_key()
+        };
+
+        assert_eq!(difference(&["a"], &["b c"]), difference(&["a"], &["c b"]));
+        assert_ne!(difference(&["a"], &["b"]), difference(&["b"], &["a"]));
+    }
+
+    #[test]
+    fn resource_type_filter_keys_ignore_order() {
+        let model = get_model_node("a");
+        let test = get_resource_type("test_a");
+
+        assert_eq!(
+            resource_type_filter_key(&ResourceTypeFilter::Some(vec![model.clone(), test.clone()])),
+            resource_type_filter_key(&ResourceTypeFilter::Some(vec![test.clone(), model.clone()]))
+        );
+        assert_ne!(
+            resource_type_filter_key(&ResourceTypeFilter::Some(vec![model])),
+            resource_type_filter_key(&ResourceTypeFilter::All)
+        );
+    }
+
+    #[test]
+    fn repeated_selections_are_cached() {
+        let node_selector = get_node_selector(&EDGES);
+        let selected = select(&node_selector, "a b").unwrap();
+        assert_eq!(selected, vec_to_set(vec!["a", "b", "test_b"]));
+        // The union, both intersections and both criteria
+        assert_eq!(node_selector.cache.group_count(), 5);
+        assert_eq!(node_selector.cache.selection_count(), 1);
+
+        assert_eq!(select(&node_selector, "b a").unwrap(), selected);
+        assert_eq!(node_selector.cache.group_count(), 5);
+        assert_eq!(node_selector.cache.selection_count(), 1);
+
+        // Filtered selections are cached separately
+        let models = node_selector
+            .get_selected_type(
+                &parse("b a"),
+                &ResourceTypeFilter::Some(vec![get_model_node("a")]),
+            )
+            .unwrap();
+        assert_eq!(models, vec_to_set(vec!["a", "b"]));
+        assert_eq!(node_selector.cache.selection_count(), 2);
+    }
+
+    #[test]
+    fn subgroups_are_shared_across_selections() {
+        let node_selector = get_node_selector(&EDGES);
+        select(&node_selector, "a b").unwrap();
+
+        // Only the new union is evaluated, `b` is already known
+        assert_eq!(
+            select(&node_selector, "b").unwrap(),
+            vec_to_set(vec!["b", "test_b"])
+        );
+        assert_eq!(node_selector.cache.group_count(), 6);
+        let b = parse("b").components.remove(0);
+        assert!(node_selector.cache.get_group(&b.cache_key()).is_some());
+    }
+
+    #[test]
+    fn restricted_selections_are_not_cached() {
+        let node_selector = get_node_selector(&EDGES);
+        let selected = node_selector
+            .get_selected_included(
+                &parse("a+"),
+                &vec_to_set(vec!["a", "source_a"]),
+                &ResourceTypeFilter::All,
+            )
+            .unwrap();
+        assert_eq!(selected, vec_to_set(vec!["a"]));
+        assert_eq!(node_selector.cache.group_count(), 0);
+
+        assert_eq!(
+            select(&node_selector, "a+").unwrap(),
+            vec_to_set(vec!["a", "b", "test_b"])
+        );
+    }
+
+    #[test]
+    fn errors_are_not_cached() {
+        let node_selector = get_node_selector(&EDGES);
+        assert_err!(
+            select(&node_selector, "state:new"),
+            Err(SelectionError::RequiresPreviousState(_))
+        );
+        assert_eq!(node_selector.cache.selection_count(), 0);
+    }
+
+    #[test]
+    fn previous_state_changes_clear_the_cache() {
+        let mut node_selector = get_node_selector(&EDGES);
+        let previous_graph = |unique_ids: &[&str]| {
+            let graph = unique_ids
+                .iter()
+                .fold(GraphBuilder::new(), |builder, id| builder.node(id, &[]))
+                .build();
+            Some(Rc::new(PreviousState::from_graph(Rc::new(graph))))
+        };
+        let models = ResourceTypeFilter::Some(vec![get_model_node("a")]);
+        let select_new = |node_selector: &NodeSelector| {
+            node_selector.get_selected_type(&parse("state:new"), &models)
+        };
+
+        node_selector.set_previous_state(previous_graph(&["source_a", "a"]));
+        assert_eq!(select_new(&node_selector).unwrap(), vec_to_set(vec!["b"]));
+
+        node_selector.set_previous_state(previous_graph(&["source_a"]));
+        assert_eq!(node_selector.cache.selection_count(), 0);
+        assert_eq!(
+            select_new(&node_selector).unwrap(),
+            vec_to_set(vec!["a", "b"])
+        );
+    }
+
+    #[test]
+    fn updated_graphs_start_with_an_empty_cache() {
+        let node_selector = get_node_selector(&EDGES);
+        assert_eq!(
+            select(&node_selector, "a+").unwrap(),
+            vec_to_set(vec!["a", "b", "test_b"])
+        );
+
+        // What `update` does: a new selector, with the old graph as state
+        let edges = [EDGES[0], EDGES[1], ("c", &["a"][..])];
+        let updated = get_node_selector(&edges);
+        assert_eq!(updated.cache.group_count(), 0);
+        assert_eq!(select(&updated, "a+").unwrap(), vec_to_set(vec!["a", "c"]));
+        assert_eq!(
+            select(&node_selector, "a+").unwrap(),
+            vec_to_set(vec!["a", "b", "test_b"])
+        );
+    }
+}
@@ -0,0 +1,236 @@
+#[cfg(test)]
+#[path = "canonical_tests.rs"]
+mod canonical_tests;
+
+/// Canonical selector strings, for sharing selections in URLs and keying
+/// cached results. Selectors which select the same nodes in the same way print
+/// the same, and printed selectors parse back into the tree they came from.
+use crate::dbt_node_selector::SelectionError;
+use crate::SelectionError::*;
+
+use super::spec::{SelectionCriteria, SelectionGroup, SelectionSpec, SetOperation, TraversalSpec};
+
+/// Whether `value` parses back as the value of a spec: it can't hold the union
+/// and intersection delimiters, or end in what reads as a `+` operator.
+fn is_printable_value(value: &str) -> bool {
+    let without_digits = value.trim_end_matches(|c: char| c.is_ascii_digit());
+    !value.contains([' ', ',', '\n']) && !without_digits.ends_with('+')
+}
+
+fn is_printable_argument(argument: &str) -> bool {
+    !argument.is_empty() && argument.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn unprintable(raw: &str, reason: &str) -> SelectionError {
+    InvalidSelector(format!(
+        "'{}' cannot be written as a selector string: {}",
+        raw, reason
+    ))
+}
+
+impl SelectionCriteria {
+    /// The same criteria, without graph operators which select nothing more:
+    /// `+0` depths, and `+` prefixes next to `@`, which already selects the
+    /// parents of every child.
+    pub fn normalize(&self) -> Self {
+        let mut criteria = self.clone();
+        if criteria.childrens_parents || criteria.parents_depth == Some(0) {
+            criteria.parents = false;
+        }
+        if criteria.childrens_parents || criteria.children_depth == Some(0) {
+            criteria.children = false;
+        }
+        if !criteria.parents {
+            criteria.parents_depth = None;
+        }
+        if !criteria.children {
+            criteria.children_depth = None;
+        }
+        criteria.raw = criteria.text();
+        criteria
+    }
+
+    /// The criteria as a spec with an explicit method, i.e. `2+fqn:orders+`
+    fn text(&self) -> String {
+        let prefix = match (self.childrens_parents, self.parents, self.parents_depth) {
+            (true, _, _) => "@".to_string(),
+            (false, true, Some(depth)) => format!("{}+", depth),
+            (false, true, None) => "+".to_string(),
+            (false, false, _) => "".to_string(),
+        };
+        let suffix = match (self.children, self.children_depth) {
+            (true, Some(depth)) => format!("+{}", depth),
+            (true, None) => "+".to_string(),
+            (false, _) => "".to_string(),
+        };
+        let method = std::iter::once(self.method.key())
+            .chain(
+                self.method_arguments
+                    .iter()
+                    .map(|argument| argument.as_str()),
+            )
+            .collect::<Vec<_>>()
+            .join(".");
+        format!("{}{}:{}{}", prefix, method, self.value, suffix)
+    }
+
+    /// Prints the normalized criteria, which parses back into it with
+    /// `SelectionCriteria::from_single_spec`
+    pub fn print(&self) -> Result<String, SelectionError> {
+        if !is_printable_value(&self.value) {
+            return Err(unprintable(&self.raw, "the value can't be parsed back"));
+        }
+        if !self
+            .method_arguments
+            .iter()
+            .all(|a| is_printable_argument(a))
+        {
+            return Err(unprintable(
+                &self.raw,
+                "method arguments can only hold letters, digits and '_'",
+            ));
+        }
+        Ok(self.normalize().text())
+    }
+}
+
+impl SelectionGroup {
+    /// The same tree in canonical form: criteria are normalized, set
+    /// operations nested in one of the same kind are merged into it, and the
+    /// members of unions, intersections and the excluded side of differences
+    /// are sorted and deduplicated, as their order doesn't change the result.
+    pub fn normalize(&self) -> Self {
+        let operation = match &self.spec {
+            SelectionSpec::SelectionCriteria(criteria) => {
+                let criteria = criteria.normalize();
+                return Self {
+                    raw: criteria.raw.clone(),
+                    spec: SelectionSpec::SelectionCriteria(criteria),
+                    ..self.clone()
+                };
+            }
+            SelectionSpec::Traversal(traversal) => {
+                let criteria = traversal.criteria.normalize();
+                return Self {
+                    raw: criteria.raw.clone(),
+                    spec: SelectionSpec::Traversal(TraversalSpec {
+                        criteria,
+                        target_ids: traversal.target_ids.clone(),
+                    }),
+                    ..self.clone()
+                };
+            }
+            SelectionSpec::SetOperation(operation) => operation,
+        };
+
+        let mut components = vec![];
+        for component in self.components.iter().map(|c| c.normalize()) {
+            let mergeable = matches!(
+                (&component.spec, operation),
+                (
+                    SelectionSpec::SetOperation(SetOperation::Union),
+                    SetOperation::Union
+                ) | (
+                    SelectionSpec::SetOperation(SetOperation::Intersection),
+                    SetOperation::Intersection
+                )
+            ) && component.indirect_selection == self.indirect_selection
+                && !component.expect_exists;
+            match mergeable {
+                true => components.extend(component.components),
+                false => components.push(component),
+            }
+        }
+        // Only what a difference subtracts from has to come first
+        let ordered = match operation {
+            SetOperation::Difference => components.len().min(1),
+            _ => 0,
+        };
+        let mut members = components.split_off(ordered);
+        members.sort_by_cached_key(|member| member.normalized_cache_key());
+        members.dedup_by(|a, b| a == b);
+        components.extend(members);
+
+        let raws: Vec<&str> = components.iter().map(|c| c.raw.as_str()).collect();
+        // The same as `parse_union` and `parse_difference` would have
+        let raw = match operation {
+            SetOperation::Union => raws.join(" "),
+            SetOperation::Intersection => raws.join(","),
+            SetOperation::Difference => match raws.split_first() {
+                Some((included, excluded)) if !excluded.is_empty() => {
+                    format!("{} --exclude {}", included, excluded.join(" "))
+                }
+                _ => raws.join(" "),
+            },
+        };
+        Self {
+            components,
+            indirect_selection: self.indirect_selection,
+            expect_exists: self.expect_exists,
+            spec: self.spec.clone(),
+            raw,
+        }
+    }
+
+    /// Prints the canonical selector string of the tree. Unions and
+    /// intersections of criteria can be printed, as long as they share one
+    /// indirect selection mode, so that for a tree parsed by `parse_union`,
+    /// parsing the printed string the same way gives back `self.normalize()`.
+    pub fn print(&self) -> Result<String, SelectionError> {
+        let normalized = self.normalize();
+        normalized.check_indirect_selection(&normalized)?;
+        normalized.print_normalized(0)
+    }
+
+    fn check_indirect_selection(&self, root: &SelectionGroup) -> Result<(), SelectionError> {
+        let indirect_selection = match &self.spec {
+            SelectionSpec::SelectionCriteria(criteria) => criteria.indirect_selection,
+            _ => self.indirect_selection,
+        };
+        if indirect_selection != root.indirect_selection {
+            return Err(unprintable(
+                &root.raw,
+                "parts of it use different indirect selection modes",
+            ));
+        }
+        self.components
+            .iter()
+            .try_for_each(|component| component.check_indirect_selection(root))
+    }
+
+    /// `depth` is how many set operations the group is nested in: a string
+    /// can only hold a union of intersections.
+    fn print_normalized(&self, depth: usize) -> Result<String, SelectionError> {
+        let (delimiter, max_depth) = match &self.spec {
+            SelectionSpec::SelectionCriteria(criteria) => return criteria.print(),
+            SelectionSpec::Traversal(_) => {
+                return Err(unprintable(&self.raw, "it has resolved target IDs"))
+            }
+            SelectionSpec::SetOperation(SetOperation::Difference) => {
+                return Err(unprintable(&self.raw, "differences need --exclude"))
+            }
+            SelectionSpec::SetOperation(SetOperation::Union) => (" ", 0),
+            SelectionSpec::SetOperation(SetOperation::Intersection) => (",", 1),
+        };
+        if depth > max_depth {
+            return Err(unprintable(&self.raw, "set operations are nested too deep"));
+        }
+        let expect_exists = self.components.first().map(|c| c.expect_exists);
+        let mixed_expect_exists = self
+            .components
+            .iter()
+            .any(|component| Some(component.expect_exists) != expect_exists);
+        if delimiter == " " && (self.expect_exists || mixed_expect_exists) {
+            return Err(unprintable(
+                &self.raw,
+                "only some parts of it must select nodes",
+            ));
+        }
+        let components = self
+            .components
+            .iter()
+            .map(|component| component.print_normalized(depth + 1))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(components.join(delimiter))
+    }
+}
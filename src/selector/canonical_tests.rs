@@ -0,0 +1,215 @@
+#[cfg(test)]
+mod canonical_tests {
+    use indexmap::IndexMap;
+
+    use crate::{
+        args::{parse_union, parse_union_with_syntax},
+        assert_err,
+        dbt_node_selector::{SelectionError, SelectorSyntax},
+        selector::spec::IndirectSelection,
+        util::test::Rng,
+    };
+
+    use super::super::*;
+
+    fn parse(selector: &str) -> SelectionGroup {
+        parse_with(selector, IndirectSelection::default())
+    }
+
+    fn parse_with(selector: &str, indirect_selection: IndirectSelection) -> SelectionGroup {
+        parse_union(&vec![selector.to_string()], false, indirect_selection).unwrap()
+    }
+
+    fn print(selector: &str) -> String {
+        parse(selector).print().unwrap()
+    }
+
+    #[test]
+    fn prints_explicit_methods() {
+        assert_eq!(print("orders"), "fqn:orders");
+        assert_eq!(print("models/orders.sql"), "path:models/orders.sql");
+        assert_eq!(print("orders.sql"), "file:orders.sql");
+        assert_eq!(print("tag:nightly"), "tag:nightly");
+        assert_eq!(
+            print("config.materialized:view"),
+            "config.materialized:view"
+        );
+        assert_eq!(print("state:modified.body"), "state:modified.body");
+    }
+
+    #[test]
+    fn prints_graph_operators() {
+        assert_eq!(print("+orders"), "+fqn:orders");
+        assert_eq!(print("2+orders+3"), "2+fqn:orders+3");
+        assert_eq!(print("@orders"), "@fqn:orders");
+        // `@` already selects the parents of every child
+        assert_eq!(print("@2+orders"), "@fqn:orders");
+        // A depth of 0 selects nothing more
+        assert_eq!(print("0+orders+0"), "fqn:orders");
+        assert_eq!(print("orders+0"), "fqn:orders");
+    }
+
+    #[test]
+    fn prints_sorted_unions_and_intersections() {
+        assert_eq!(print("b a"), "fqn:a fqn:b");
+        assert_eq!(print("b a b"), "fqn:a fqn:b");
+        assert_eq!(print("tag:x,b c"), "fqn:b,tag:x fqn:c");
+        assert_eq!(print("b,a,a"), "fqn:a,fqn:b");
+        assert_eq!(print("a,b b,a"), "fqn:a,fqn:b");
+    }
+
+    #[test]
+    fn merges_nested_intersections() {
+        let between = parse_union_with_syntax(
+            &vec!["tag:x,a..b".to_string()],
+            false,
+            IndirectSelection::default(),
+            SelectorSyntax::Extended,
+        )
+        .unwrap();
+        assert_eq!(between.print().unwrap(), "+fqn:b,fqn:a+,tag:x");
+        assert_eq!(
+            between.normalize(),
+            parse("+fqn:b,fqn:a+,tag:x").normalize()
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        for selector in [
+            "a",
+            "b a",
+            "@a 2+b+1,tag:x",
+            "config.materialized:view,models/a.sql",
+            "state:modified+ resource_type:model,+exposure:dashboard",
+        ] {
+            let group = parse(selector);
+            let printed = group.print().unwrap();
+            assert_eq!(parse(&printed), group.normalize(), "{}", selector);
+            assert_eq!(parse(&printed).print().unwrap(), printed);
+        }
+
+        let group = parse_with("b,a", IndirectSelection::Cautious);
+        let printed = group.print().unwrap();
+        assert_eq!(
+            parse_with(&printed, IndirectSelection::Cautious),
+            group.normalize()
+        );
+    }
+
+    #[test]
+    fn random_selectors_round_trip() {
+        const VALUES: &[&str] = &[
+            "orders",
+            "models/orders.sql",
+            "orders.sql",
+            "tag:nightly",
+            "config.materialized:view",
+            "state:modified",
+            "resource_type:model",
+        ];
+        for seed in 0..200 {
+            let mut rng = Rng::new(seed);
+            let mut union = vec![];
+            for _ in 0..1 + rng.below(4) {
+                let mut intersection = vec![];
+                for _ in 0..1 + rng.below(3) {
+                    let prefix = ["", "@", "+", "0+", "2+"][rng.below(5)];
+                    let suffix = match prefix {
+                        "@" => "",
+                        _ => ["", "+", "+0", "+1"][rng.below(4)],
+                    };
+                    let value = VALUES[rng.below(VALUES.len())];
+                    intersection.push(format!("{}{}{}", prefix, value, suffix));
+                }
+                union.push(intersection.join(","));
+            }
+            let selector = union.join(" ");
+
+            let group = parse(&selector);
+            let printed = group.print().unwrap();
+            let reparsed = parse(&printed);
+            assert_eq!(reparsed, group.normalize(), "seed {}: {}", seed, selector);
+            assert_eq!(reparsed.print().unwrap(), printed, "seed {}", seed);
+            assert_eq!(reparsed.cache_key(), group.cache_key(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn unprintable_trees_are_errors() {
+        let unprintable = |group: SelectionGroup| match group.print() {
+            Err(SelectionError::InvalidSelector(message)) => message,
+            result => panic!("Expected an unprintable tree, got {:?}", result),
+        };
+
+        // Values from selectors.yml can hold anything
+        let criteria = |value: &str| {
+            let definition = IndexMap::from([("value".to_string(), value.to_string())]);
+            SelectionGroup::from_criteria(
+                SelectionCriteria::selection_criteria_from_indexmap("", &definition, None).unwrap(),
+            )
+        };
+        assert!(unprintable(criteria("a b")).contains("the value can't be parsed back"));
+        assert!(unprintable(criteria("a+1")).contains("the value can't be parsed back"));
+        assert_eq!(criteria("a1").print().unwrap(), "fqn:a1");
+
+        let difference = SelectionGroup::difference(
+            "a --exclude b".to_string(),
+            vec![parse("a"), parse("b")],
+            IndirectSelection::default(),
+            false,
+        );
+        assert!(unprintable(difference).contains("differences need --exclude"));
+
+        let nested = SelectionGroup::intersection(
+            "(a b),c".to_string(),
+            vec![parse("a b"), parse("c")],
+            IndirectSelection::default(),
+            false,
+        );
+        assert!(unprintable(nested).contains("nested too deep"));
+
+        let mixed = SelectionGroup::union(
+            "a b".to_string(),
+            vec![
+                parse_with("a", IndirectSelection::Eager)
+                    .components
+                    .remove(0),
+                parse_with("b", IndirectSelection::Cautious)
+                    .components
+                    .remove(0),
+            ],
+            IndirectSelection::default(),
+            false,
+        );
+        assert!(unprintable(mixed).contains("indirect selection modes"));
+
+        assert_err!(
+            SelectionGroup::from_traversal(
+                SelectionCriteria::from_single_raw_spec("a").unwrap(),
+                ["a".to_string()].into()
+            )
+            .print(),
+            Err(SelectionError::InvalidSelector(_))
+        );
+    }
+
+    #[test]
+    fn normalizes_differences() {
+        let difference = |excluded: &[&str]| {
+            SelectionGroup::difference(
+                "".to_string(),
+                std::iter::once(parse("a"))
+                    .chain(excluded.iter().map(|selector| parse(selector)))
+                    .collect(),
+                IndirectSelection::default(),
+                false,
+            )
+            .normalize()
+        };
+
+        assert_eq!(difference(&["c", "b"]), difference(&["b", "c", "b"]));
+        assert_eq!(difference(&["c", "b"]).raw, "fqn:a --exclude fqn:b fqn:c");
+        assert_eq!(difference(&[]).raw, "fqn:a");
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+#[path = "diagnostic_tests.rs"]
+mod diagnostic_tests;
+
+/// Structured, located parse errors for selector strings.
+use crate::dbt_node_selector::{SelectionError, SelectorDiagnostic};
+use crate::SelectionError::*;
+
+use super::MethodName;
+
+/// Optimal string alignment distance: the Levenshtein distance, where swapping
+/// two adjacent characters also only counts as a single edit.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+/// Candidates close enough to `input` to be a likely typo, closest first.
+pub fn suggest<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let input = input.to_lowercase();
+    // Short inputs only tolerate a single edit, otherwise everything matches
+    let max_distance = (input.chars().count() / 3).clamp(1, 3);
+    let mut scored: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&input, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    scored.sort();
+    scored
+        .into_iter()
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+impl SelectorDiagnostic {
+    pub fn new(message: impl Into<String>, start: usize, end: usize) -> Self {
+        Self {
+            message: message.into(),
+            start: start as u32,
+            end: end as u32,
+            expected: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    pub fn with_expected(mut self, expected: Vec<String>) -> Self {
+        self.expected = expected;
+        self
+    }
+
+    pub fn with_suggestions(mut self, suggestions: Vec<String>) -> Self {
+        self.suggestions = suggestions;
+        self
+    }
+
+    /// `method_name` starts at byte `start` of the selector
+    pub fn invalid_method(method_name: &str, start: usize) -> Self {
+        let method_keys = MethodName::all().iter().map(|method| method.key());
+        Self::new(
+            format!("'{}' is not a valid method name", method_name),
+            start,
+            start + method_name.len(),
+        )
+        .with_expected(method_keys.clone().map(|key| key.to_string()).collect())
+        .with_suggestions(suggest(method_name, method_keys))
+    }
+}
+
+impl SelectionError {
+    /// Moves the span of a syntax error by `offset` bytes, for when a single
+    /// spec was parsed out of a longer selector string.
+    pub fn offset(self, offset: usize) -> Self {
+        match self {
+            InvalidSyntax(mut diagnostic) => {
+                diagnostic.start += offset as u32;
+                diagnostic.end += offset as u32;
+                InvalidSyntax(diagnostic)
+            }
+            error => error,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod diagnostic_tests {
+    use indexmap::IndexMap;
+
+    use crate::{
+        args::parse_union,
+        assert_err,
+        selector::spec::{IndirectSelection, SelectionCriteria},
+    };
+
+    use super::super::*;
+
+    fn get_diagnostic(result: Result<impl std::fmt::Debug, SelectionError>) -> SelectorDiagnostic {
+        match result {
+            Err(InvalidSyntax(diagnostic)) => diagnostic,
+            other => panic!("Expected a syntax error, got {:?}", other),
+        }
+    }
+
+    fn parse(selector: &str) -> SelectorDiagnostic {
+        get_diagnostic(parse_union(
+            &vec![selector.to_string()],
+            false,
+            IndirectSelection::default(),
+        ))
+    }
+
+    #[test]
+    fn edit_distance_counts_transpositions_once() {
+        assert_eq!(edit_distance("tag", "tag"), 0);
+        assert_eq!(edit_distance("tga", "tag"), 1);
+        assert_eq!(edit_distance("pakage", "package"), 1);
+        assert_eq!(edit_distance("", "fqn"), 3);
+    }
+
+    #[test]
+    fn suggest_orders_by_distance() {
+        let suggestions = suggest("Sorce", ["source", "resource_type", "tag"]);
+
+        assert_eq!(suggestions, vec!["source"]);
+        assert!(suggest("zzz", ["source", "tag"]).is_empty());
+    }
+
+    #[test]
+    fn all_methods_round_trip_through_their_key() {
+        for method in MethodName::all() {
+            assert_eq!(MethodName::from_string(method.key()), Some(*method));
+        }
+    }
+
+    #[test]
+    fn invalid_method_points_at_the_method() {
+        let diagnostic = parse("tga:nightly");
+
+        assert_eq!((diagnostic.start, diagnostic.end), (0, 3));
+        assert_eq!(diagnostic.suggestions, vec!["tag"]);
+        assert!(diagnostic.expected.contains(&"tag".to_string()));
+    }
+
+    #[test]
+    fn spans_are_relative_to_the_whole_selector() {
+        let diagnostic = parse("my_model tag:a,pakage:dbt_utils");
+
+        assert_eq!((diagnostic.start, diagnostic.end), (15, 21));
+        assert_eq!(diagnostic.suggestions, vec!["package"]);
+
+        let diagnostic = get_diagnostic(parse_union(
+            &vec!["a".to_string(), "b tga:c".to_string()],
+            false,
+            IndirectSelection::default(),
+        ));
+        assert_eq!((diagnostic.start, diagnostic.end), (4, 7));
+    }
+
+    #[test]
+    fn incompatible_prefix_and_suffix_points_at_the_suffix() {
+        let diagnostic = parse("@my_model+2");
+
+        assert_eq!((diagnostic.start, diagnostic.end), (9, 11));
+        assert_eq!(diagnostic.suggestions, vec!["my_model+2", "@my_model"]);
+    }
+
+    #[test]
+    fn overflowing_depth_points_at_the_digits() {
+        let diagnostic = parse("a 99999999999999999999999+my_model");
+
+        assert_eq!((diagnostic.start, diagnostic.end), (2, 25));
+        assert_eq!(diagnostic.expected, vec!["<integer>"]);
+    }
+
+    #[test]
+    fn definition_depths_are_not_located() {
+        // YAML definitions have no selector string to point into
+        let depth = |key: &str, depth: &str| {
+            let definition = IndexMap::from([
+                ("value".to_string(), "my_model".to_string()),
+                (key.to_string(), depth.to_string()),
+            ]);
+            SelectionCriteria::selection_criteria_from_indexmap("", &definition, None)
+        };
+
+        assert_err!(
+            depth("parents_depth", "two"),
+            Err(ParentsDepthParseIntError(_))
+        );
+        assert_err!(
+            depth("children_depth", "-1"),
+            Err(ChildrensDepthParseIntError(_))
+        );
+    }
+
+    #[test]
+    fn line_breaks_are_located() {
+        let diagnostic = get_diagnostic(SelectionCriteria::from_single_raw_spec("f\noo"));
+
+        assert_eq!((diagnostic.start, diagnostic.end), (1, 2));
+        assert_eq!(diagnostic.suggestions, vec!["f oo"]);
+    }
+
+    #[test]
+    fn other_errors_are_not_offset() {
+        let error = NodeNotInGraph("a".to_string()).offset(3);
+
+        assert_err!(Err::<(), _>(error), Err(NodeNotInGraph(_)));
+    }
+}
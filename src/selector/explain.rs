@@ -0,0 +1,187 @@
+#[cfg(test)]
+#[path = "explain_tests.rs"]
+mod explain_tests;
+
+/// Explains why each node ended up in a selection. This mirrors
+/// `NodeSelector::select_nodes_recursively`, but keeps track of where each
+/// unique ID came from instead of throwing that information away.
+use std::collections::{HashMap, HashSet};
+
+use crate::dbt_node_selector::{
+    IndirectSelectionMode, NodeExplanation, SelectionError, SelectionReason, SelectionVia,
+    SetOperationKind, SetOperationStep,
+};
+use crate::graph::UniqueId;
+
+use super::{
+    node_selector::{DirectNodes, IndirectNodes, NodeSelector},
+    spec::{IndirectSelection, SelectionCriteria, SelectionGroup, SelectionSpec, SetOperation},
+};
+
+type Reasons = HashMap<UniqueId, Vec<SelectionReason>>;
+
+impl From<&IndirectSelection> for IndirectSelectionMode {
+    fn from(indirect_selection: &IndirectSelection) -> Self {
+        match indirect_selection {
+            IndirectSelection::Eager => Self::Eager,
+            IndirectSelection::Cautious => Self::Cautious,
+            IndirectSelection::Buildable => Self::Buildable,
+            IndirectSelection::Empty => Self::Empty,
+        }
+    }
+}
+
+impl From<&SetOperation> for SetOperationKind {
+    fn from(operation: &SetOperation) -> Self {
+        match operation {
+            SetOperation::Intersection => Self::Intersection,
+            SetOperation::Difference => Self::Difference,
+            SetOperation::Union => Self::Union,
+        }
+    }
+}
+
+impl NodeSelector {
+    fn explain_criteria(
+        &self,
+        spec: &SelectionCriteria,
+        collected: &HashSet<UniqueId>,
+    ) -> Result<(DirectNodes, IndirectNodes, Reasons), SelectionError> {
+        let (direct_nodes, indirect_nodes) = self.get_nodes_from_collected(spec, collected)?;
+
+        let (childrens_parents, children, parents) =
+            match (spec.childrens_parents, spec.children, spec.parents) {
+                (true, _, _) => (
+                    self.graph.select_childrens_parents(collected)?,
+                    HashSet::new(),
+                    HashSet::new(),
+                ),
+                (false, children, parents) => (
+                    HashSet::new(),
+                    match children {
+                        true => self
+                            .graph
+                            .select_children(collected, &spec.children_depth)?,
+                        false => HashSet::new(),
+                    },
+                    match parents {
+                        true => self.graph.select_parents(collected, &spec.parents_depth)?,
+                        false => HashSet::new(),
+                    },
+                ),
+            };
+
+        let reason = |via: SelectionVia| SelectionReason {
+            criteria: spec.raw.clone(),
+            method: spec.method.key().to_string(),
+            value: spec.value.clone(),
+            via,
+            set_operations: vec![],
+        };
+        let indirect = || SelectionVia::Indirect((&spec.indirect_selection).into());
+
+        let mut reasons = Reasons::new();
+        for unique_id in direct_nodes.iter().chain(indirect_nodes.iter()) {
+            let mut node_reasons = vec![];
+            if collected.contains(unique_id) {
+                node_reasons.push(reason(SelectionVia::Direct));
+            }
+            if childrens_parents.contains(unique_id) && !collected.contains(unique_id) {
+                node_reasons.push(reason(SelectionVia::ChildrensParents));
+            }
+            if children.contains(unique_id) {
+                node_reasons.push(reason(SelectionVia::Children));
+            }
+            if parents.contains(unique_id) {
+                node_reasons.push(reason(SelectionVia::Parents));
+            }
+            if node_reasons.is_empty() {
+                node_reasons.push(reason(indirect()));
+            }
+            reasons.insert(unique_id.clone(), node_reasons);
+        }
+
+        Ok((direct_nodes, indirect_nodes, reasons))
+    }
+
+    /// Same as `select_nodes_recursively`, returning the reasons each
+    /// direct or indirect node was selected alongside the selection.
+    fn explain_nodes_recursively(
+        &self,
+        selection_group: &SelectionGroup,
+    ) -> Result<(DirectNodes, IndirectNodes, Reasons), SelectionError> {
+        let operation = match &selection_group.spec {
+            SelectionSpec::SelectionCriteria(spec) => {
+                let collected = self.collect_nodes_from_criteria(spec, &self.all_nodes())?;
+                return self.explain_criteria(spec, &collected);
+            }
+            SelectionSpec::Traversal(traversal) => {
+                let collected = self.get_traversal_targets(traversal)?;
+                return self.explain_criteria(&traversal.criteria, &collected);
+            }
+            SelectionSpec::SetOperation(operation) => operation,
+        };
+
+        let explained = selection_group
+            .components
+            .iter()
+            .map(|component| self.explain_nodes_recursively(component))
+            .collect::<Result<Vec<_>, SelectionError>>()?;
+        let bundles: Vec<(DirectNodes, IndirectNodes)> = explained
+            .iter()
+            .map(|(direct, indirect, _)| (direct.clone(), indirect.clone()))
+            .collect();
+        let (direct_nodes, indirect_nodes) =
+            self.combine_components(selection_group, operation, &bundles)?;
+
+        // Only the first component of a difference can contribute nodes
+        let contributing = match operation {
+            SetOperation::Difference => &explained[..explained.len().min(1)],
+            _ => &explained[..],
+        };
+        // A set operation over a single component doesn't filter anything out
+        let step = (selection_group.components.len() > 1).then(|| SetOperationStep {
+            operation: operation.into(),
+            raw: selection_group.raw.clone(),
+        });
+
+        let mut reasons = Reasons::new();
+        for unique_id in direct_nodes.iter().chain(indirect_nodes.iter()) {
+            let node_reasons: Vec<SelectionReason> = contributing
+                .iter()
+                .filter_map(|(_, _, component_reasons)| component_reasons.get(unique_id))
+                .flatten()
+                .cloned()
+                .map(|mut reason| {
+                    if let Some(step) = &step {
+                        reason.set_operations.push(step.clone());
+                    }
+                    reason
+                })
+                .collect();
+            reasons.insert(unique_id.clone(), node_reasons);
+        }
+
+        Ok((direct_nodes, indirect_nodes, reasons))
+    }
+
+    /// Selects nodes for the selection group, and explains which criteria,
+    /// graph operators and set operations each selected node came from.
+    pub fn explain_selected(
+        &self,
+        selection_group: &SelectionGroup,
+    ) -> Result<Vec<NodeExplanation>, SelectionError> {
+        let (direct_nodes, _indirect_nodes, mut reasons) =
+            self.explain_nodes_recursively(selection_group)?;
+
+        let mut unique_ids: Vec<UniqueId> = direct_nodes.into_iter().collect();
+        unique_ids.sort();
+        Ok(unique_ids
+            .into_iter()
+            .map(|unique_id| NodeExplanation {
+                reasons: reasons.remove(&unique_id).unwrap_or_default(),
+                unique_id,
+            })
+            .collect())
+    }
+}
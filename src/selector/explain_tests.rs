@@ -0,0 +1,172 @@
+#[cfg(test)]
+mod explain_tests {
+    use crate::{
+        assert_ok,
+        dbt_node_selector::{Edge, Node, NodeExplanation, SelectionReason},
+        util::test::get_resource_type,
+    };
+
+    use super::super::*;
+
+    /// source_a -> a -> b -> c, with `test_b` testing `b`
+    fn get_node_selector() -> NodeSelector {
+        let edges = vec![
+            ("source_a", vec![]),
+            ("a", vec!["source_a"]),
+            ("b", vec!["a"]),
+            ("c", vec!["b"]),
+            ("test_b", vec!["b"]),
+        ];
+        let nodes = edges
+            .iter()
+            .map(|(unique_id, _)| Node {
+                unique_id: unique_id.to_string(),
+                depends_on: vec![],
+                name: unique_id.to_string(),
+                package_name: "pkg".to_string(),
+                path: format!("{}.sql", unique_id),
+                original_file_path: format!("models/{}.sql", unique_id),
+                node_type: get_resource_type(*unique_id),
+                config: vec![],
+                tags: vec![],
+            })
+            .collect();
+        let edges = edges
+            .into_iter()
+            .map(|(unique_id, parents)| Edge {
+                unique_id: unique_id.to_string(),
+                parents: parents.into_iter().map(|s| s.to_string()).collect(),
+            })
+            .collect();
+        NodeSelector::from(nodes, edges, None).unwrap()
+    }
+
+    fn get_reasons<'a>(
+        explanations: &'a [NodeExplanation],
+        unique_id: &str,
+    ) -> &'a Vec<SelectionReason> {
+        &explanations
+            .iter()
+            .find(|explanation| explanation.unique_id == unique_id)
+            .expect("Node was not selected")
+            .reasons
+    }
+
+    fn is_via(reason: &SelectionReason, via: SelectionVia) -> bool {
+        format!("{:?}", reason.via) == format!("{:?}", via)
+    }
+
+    #[test]
+    fn explains_direct_and_indirect_selection() {
+        let node_selector = get_node_selector();
+
+        let explanations = assert_ok!(node_selector._select_explained("b".to_string()));
+
+        let unique_ids: Vec<&str> = explanations
+            .iter()
+            .map(|explanation| explanation.unique_id.as_str())
+            .collect();
+        assert_eq!(unique_ids, vec!["b", "test_b"]);
+
+        let b = get_reasons(&explanations, "b");
+        assert_eq!(b.len(), 1);
+        assert_eq!(b[0].criteria, "b");
+        assert_eq!(b[0].method, "fqn");
+        assert_eq!(b[0].value, "b");
+        assert!(is_via(&b[0], SelectionVia::Direct));
+        assert!(b[0].set_operations.is_empty());
+
+        let test_b = get_reasons(&explanations, "test_b");
+        assert!(is_via(
+            &test_b[0],
+            SelectionVia::Indirect(IndirectSelectionMode::Eager)
+        ));
+    }
+
+    #[test]
+    fn explains_graph_operators() {
+        let node_selector = get_node_selector();
+
+        let explanations = assert_ok!(node_selector._select_explained("+b+".to_string()));
+
+        assert!(is_via(
+            &get_reasons(&explanations, "b")[0],
+            SelectionVia::Direct
+        ));
+        assert!(is_via(
+            &get_reasons(&explanations, "a")[0],
+            SelectionVia::Parents
+        ));
+        assert!(is_via(
+            &get_reasons(&explanations, "source_a")[0],
+            SelectionVia::Parents
+        ));
+        assert!(is_via(
+            &get_reasons(&explanations, "c")[0],
+            SelectionVia::Children
+        ));
+    }
+
+    #[test]
+    fn explains_childrens_parents() {
+        let node_selector = get_node_selector();
+
+        let explanations = assert_ok!(node_selector._select_explained("@b".to_string()));
+
+        assert!(is_via(
+            &get_reasons(&explanations, "a")[0],
+            SelectionVia::ChildrensParents
+        ));
+        assert!(is_via(
+            &get_reasons(&explanations, "b")[0],
+            SelectionVia::Direct
+        ));
+    }
+
+    #[test]
+    fn explains_set_operations() {
+        let node_selector = get_node_selector();
+
+        let explanations = assert_ok!(node_selector._select_explained("a+,+c".to_string()));
+
+        let unique_ids: Vec<&str> = explanations
+            .iter()
+            .map(|explanation| explanation.unique_id.as_str())
+            .collect();
+        // "+c" eagerly selects "test_b" through "b"
+        assert_eq!(unique_ids, vec!["a", "b", "c", "test_b"]);
+
+        // "b" is kept by both sides of the intersection
+        let b = get_reasons(&explanations, "b");
+        assert_eq!(b.len(), 2);
+        assert!(is_via(&b[0], SelectionVia::Children) || is_via(&b[1], SelectionVia::Children));
+        assert!(is_via(&b[0], SelectionVia::Parents) || is_via(&b[1], SelectionVia::Parents));
+        for reason in b {
+            assert_eq!(reason.set_operations.len(), 1);
+            assert_eq!(reason.set_operations[0].raw, "a+,+c");
+            assert_eq!(
+                reason.set_operations[0].operation,
+                SetOperationKind::Intersection
+            );
+        }
+
+        let test_b = get_reasons(&explanations, "test_b");
+        assert!(test_b
+            .iter()
+            .any(|reason| is_via(reason, SelectionVia::Indirect(IndirectSelectionMode::Eager))));
+    }
+
+    #[test]
+    fn explains_unions() {
+        let node_selector = get_node_selector();
+
+        let explanations = assert_ok!(node_selector._select_explained("a c".to_string()));
+
+        let a = get_reasons(&explanations, "a");
+        assert_eq!(a.len(), 1);
+        assert_eq!(a[0].criteria, "a");
+        assert_eq!(a[0].set_operations.len(), 1);
+        assert_eq!(a[0].set_operations[0].operation, SetOperationKind::Union);
+        assert_eq!(a[0].set_operations[0].raw, "a c");
+    }
+}
@@ -1,13 +1,17 @@
+#[cfg(test)]
+#[path = "methods_tests.rs"]
+mod methods_tests;
+
 /// core/dbt/graph/selector_methods.py
-use std::{collections::HashSet, path::Path, rc::Rc};
+use std::{cmp::Ordering, collections::HashSet, path::Path, rc::Rc};
 
 use crate::{
-    dbt_node_selector::{SelectionError, UniqueId},
+    dbt_node_selector::{NodeType, SelectionError, UniqueId},
     graph::{
         node::{NodeTypeKey, WrapperNode, WrapperNodeExt},
         parsed_graph::ParsedGraph,
     },
-    util::file::fnmatch_normalized,
+    util::file::{fnmatch_normalized, fnmatchcase},
 };
 
 use super::{node_selector::PreviousState, state_selector_method::StateSelectorMethod, MethodName};
@@ -15,71 +19,221 @@ use crate::dbt_node_selector::SelectionError::*;
 
 use MethodName::*;
 
+/// Every selector accepted by `version:`
+pub const VERSION_SELECTORS: &[&str] = &["latest", "prerelease", "old", "none"];
+
+const SELECTOR_GLOB: &str = "*";
+
 impl MethodName {
     /// Dots in model names act as namespace separators
-    fn flatten_node_parts(fqn: &Vec<String>) -> Vec<String> {
+    fn flatten_node_parts(fqn: &[String]) -> Vec<String> {
         fqn.iter()
             .flat_map(|segment| segment.split("."))
             .map(|s| s.to_string())
             .collect()
     }
 
-    fn is_selected_node(fqn: &Vec<String>, node_selector: &str) -> bool {
-        let last = fqn.last();
-        if last == Some(&node_selector.to_string()) {
-            return true;
+    fn is_selected_node(fqn: &[String], node_selector: &str, is_versioned: bool) -> bool {
+        // If qualified_name exactly matches model name (fqn's leaf), return True
+        match fqn {
+            [.., name, version] if is_versioned => {
+                if name == node_selector {
+                    return true;
+                }
+                // The last two segments of a versioned model may match on either
+                // the '.' or '_' delimiter, so "my_model.v2" and "my_model_v2" both
+                // select version 2 of "my_model"
+                let selector_parts: Vec<&str> = node_selector.split('.').collect();
+                let selector_tail = &selector_parts[selector_parts.len().saturating_sub(2)..];
+                if format!("{}_{}", name, version) == selector_tail.join("_") {
+                    return true;
+                }
+            }
+            [.., last] if !is_versioned && last == node_selector => return true,
+            _ => {}
         }
         let flat_fqn = Self::flatten_node_parts(fqn);
         let selector_parts: Vec<&str> = node_selector.split(".").collect();
         if flat_fqn.len() < selector_parts.len() {
             return false;
         }
-        let wildcard = vec!['*', '?', '[', ']'];
+        let wildcard = ['*', '?', '[', ']'];
         for (i, part) in selector_parts.clone().into_iter().enumerate() {
-            for char in &wildcard {
-                if part.contains(*char) {
-                    // If we have a wildcard, we need to make sure that the selector matches the
-                    // rest of the fqn, this is 100% backwards compatible with the old behavior of
-                    // encountering a wildcard but more expressive in naturally allowing you to
-                    // match the rest of the fqn with more advanced patterns
-                    let flat_fqn = flat_fqn[i..].join(".");
-                    let regex = fnmatch_regex::glob_to_regex(flat_fqn.as_str());
-                    let remainder = &(selector_parts)[i..];
-                    let remainder = remainder.join(".");
-                    match regex {
-                        Err(_) => false,
-                        Ok(regex) => regex.is_match(remainder.as_str()),
-                    };
-                }
+            if wildcard.iter().any(|char| part.contains(*char)) {
+                // If we have a wildcard, we need to make sure that the selector matches the
+                // rest of the fqn, this is 100% backwards compatible with the old behavior of
+                // encountering a wildcard but more expressive in naturally allowing you to
+                // match the rest of the fqn with more advanced patterns
+                let flat_fqn = flat_fqn[i..].join(".");
+                let remainder = selector_parts[i..].join(".");
+                return fnmatchcase(&flat_fqn, &remainder).unwrap_or(false);
             }
             if flat_fqn[i] != part {
                 return false;
             }
         }
-        return true;
+        true
     }
 
-    fn is_node_match(&self, qualified_name: &str, fqn: &Option<Vec<String>>) -> bool {
-        let Some(fqn) = fqn else { return false };
-        if Self::is_selected_node(fqn, qualified_name) {
+    fn is_node_match(&self, qualified_name: &str, node: &WrapperNode) -> bool {
+        let Some(fqn) = node.fqn() else { return false };
+        let is_versioned = node.resource_type().is_versioned();
+        if Self::is_selected_node(&fqn, qualified_name, is_versioned) {
             true
         } else {
-            let unscoped_fqn = &fqn[1..].to_vec();
             // Match nodes across different packages
-            Self::is_selected_node(unscoped_fqn, qualified_name)
+            let unscoped_fqn = fqn.get(1..).unwrap_or_default();
+            Self::is_selected_node(unscoped_fqn, qualified_name, is_versioned)
+        }
+    }
+
+    /// Versions compare as numbers when both are numeric, otherwise as strings
+    fn compare_versions(a: &str, b: &str) -> Ordering {
+        match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.total_cmp(&b),
+            _ => a.cmp(b),
+        }
+    }
+
+    fn is_version_match(node_type: &NodeType, selector: &str) -> bool {
+        let NodeType::Model(_) = node_type else { return false };
+        let (version, latest_version) = (node_type.version(), node_type.latest_version());
+        match (selector, version, latest_version) {
+            ("latest", _, _) => node_type.is_latest_version(),
+            ("prerelease", Some(version), Some(latest_version)) => {
+                Self::compare_versions(version, latest_version) == Ordering::Greater
+            }
+            ("old", Some(version), Some(latest_version)) => {
+                Self::compare_versions(version, latest_version) == Ordering::Less
+            }
+            ("none", None, _) => true,
+            _ => false,
+        }
+    }
+
+    /// Flattened config values are strings, so booleans are "true" or "false",
+    /// which match ignoring case like test severities do in dbt
+    fn is_config_match(key: &str, value: &str, selector: &str) -> bool {
+        let ignore_case = key == "severity" || value == "true" || value == "false";
+        value == selector || (ignore_case && value.eq_ignore_ascii_case(selector))
+    }
+
+    /// The test types `test_type:` selects. dbt checks `selector in ("data")`,
+    /// which is a substring check, so "dat" selects like "data" does there too.
+    fn test_types(selector: &str) -> Result<&'static [&'static str], SelectionError> {
+        match selector {
+            "generic" | "schema" => Ok(&["generic"]),
+            _ if "data".contains(selector) => Ok(&["generic", "singular"]),
+            _ if "singular".contains(selector) => Ok(&["singular"]),
+            _ if "unit".contains(selector) => Ok(&["unit"]),
+            _ => Err(InvalidSelector(format!(
+                "Invalid test type selector '{}': expected one of: generic, singular, unit, data",
+                selector
+            ))),
+        }
+    }
+
+    /// Sources loaded since the previous run, or new to the current one,
+    /// leaving out those whose freshness check errored in either run. Both
+    /// runs write `max_loaded_at` in the same ISO 8601 form, which sorts by time.
+    fn fresher_sources(
+        previous_state: &Option<Rc<PreviousState>>,
+    ) -> Result<HashSet<&UniqueId>, SelectionError> {
+        let Some(previous_state) = previous_state else {
+            Err(RequiresPreviousState(
+                "No previous state found for source_status selector.".to_string(),
+            ))?
+        };
+        let (Some(previous), Some(current)) =
+            (&previous_state.sources, &previous_state.sources_current)
+        else {
+            Err(RequiresPreviousState(
+                "No previous and current sources.json found for source_status selector."
+                    .to_string(),
+            ))?
+        };
+        Ok(current
+            .iter()
+            .filter_map(|(unique_id, loaded_at)| {
+                let loaded_at = loaded_at.as_ref()?;
+                let is_fresher = match previous.get(unique_id) {
+                    None => true,
+                    Some(None) => false,
+                    Some(Some(previous_loaded_at)) => loaded_at > previous_loaded_at,
+                };
+                is_fresher.then_some(unique_id)
+            })
+            .collect())
+    }
+
+    /// The resource type selected by `exposure:`, `metric:`, `semantic_model:`,
+    /// `saved_query:` and `unit_test:`
+    pub(crate) fn named_resource_type(&self) -> Option<NodeTypeKey> {
+        match self {
+            Exposure => Some(NodeTypeKey::Exposure),
+            Metric => Some(NodeTypeKey::Metric),
+            SemanticModel => Some(NodeTypeKey::SemanticModel),
+            SavedQuery => Some(NodeTypeKey::SavedQuery),
+            UnitTest => Some(NodeTypeKey::UnitTest),
+            _ => None,
+        }
+    }
+
+    /// Splits `name` or `package.name` into its package, model and name
+    /// patterns. Unit tests may also be selected by `package.model.name`.
+    fn split_named_selector<'a>(&self, selector: &'a str) -> Result<[&'a str; 3], SelectionError> {
+        match selector.split('.').collect::<Vec<&str>>().as_slice() {
+            [name] => Ok([SELECTOR_GLOB, SELECTOR_GLOB, name]),
+            [package, name] => Ok([package, SELECTOR_GLOB, name]),
+            [package, model, name] if *self == UnitTest => Ok([package, model, name]),
+            _ => Err(InvalidSelector(format!(
+                "Invalid {} selector value '{}'",
+                self.key(),
+                selector
+            ))),
+        }
+    }
+
+    fn is_glob_match(value: &str, pattern: &str) -> bool {
+        pattern == SELECTOR_GLOB || fnmatch_normalized(value, pattern).unwrap_or(false)
+    }
+
+    /// Splits `source`, `source.table` or `package.source.table` into its
+    /// package, source and table patterns
+    fn split_source_selector(selector: &str) -> Result<[&str; 3], SelectionError> {
+        match selector.split('.').collect::<Vec<&str>>().as_slice() {
+            [source] => Ok([SELECTOR_GLOB, source, SELECTOR_GLOB]),
+            [source, table] => Ok([SELECTOR_GLOB, source, table]),
+            [package, source, table] => Ok([package, source, table]),
+            _ => Err(InvalidSelector(format!(
+                "Invalid source selector value '{}'. Sources must be of the form \
+                 source_name, source_name.table_name or package_name.source_name.table_name",
+                selector
+            ))),
         }
     }
 
-    /// Some methods (StateSelectorMethod) use prepare in order to update
-    /// cached state.
+    /// The nodes of `included_nodes` which are in the graph, so that no
+    /// method can select a node outside of them
+    pub fn included<'a>(
+        graph: &'a ParsedGraph,
+        included_nodes: &'a HashSet<UniqueId>,
+    ) -> impl Iterator<Item = (&'a UniqueId, &'a WrapperNode)> + Clone {
+        included_nodes
+            .iter()
+            .filter_map(|unique_id| graph.node_map.get_key_value(unique_id))
+            .map(|(unique_id, node)| (unique_id, node.as_ref()))
+    }
+
+    /// Some methods (StateSelectorMethod) use prepare to check they have
+    /// what they search before searching.
     pub fn prepare(
         &self,
-        graph: Rc<ParsedGraph>,
         previous_state: &Option<Rc<PreviousState>>,
-    ) -> Option<Result<PreviousState, SelectionError>> {
+    ) -> Result<(), SelectionError> {
         match self {
-            State => Some(StateSelectorMethod::prepare(graph, previous_state)),
-            _ => None,
+            State => StateSelectorMethod::prepare(previous_state),
+            _ => Ok(()),
         }
     }
 
@@ -88,14 +242,15 @@ impl MethodName {
         previous_state: &Option<Rc<PreviousState>>,
         graph: Rc<ParsedGraph>,
         included_nodes: &HashSet<UniqueId>,
+        method_arguments: &[String],
         selector: &str,
     ) -> Result<Vec<String>, SelectionError> {
         match self {
-            FQN => Ok(graph
-                .node_map
-                .iter()
+            // Sources are only selected by `source:`
+            FQN => Ok(Self::included(&graph, included_nodes)
+                .filter(|(_, node)| node.resource_type().key() != NodeTypeKey::Source)
                 .filter_map(|(id, node)| {
-                    if self.is_node_match(selector, &node.fqn()) {
+                    if self.is_node_match(selector, node) {
                         Some(id.to_string())
                     } else {
                         None
@@ -103,43 +258,59 @@ impl MethodName {
                 })
                 .collect::<Vec<String>>()),
 
-            Tag => {
-                // TODO: Confirm with core there's no need to fnmatch tags, and just a string
-                // match (lowercased) should be sufficient
-                let selector = &selector.to_lowercase();
-                Ok(graph.node_map.iter().filter_map(|(unique_id, node)| {
-                    node.has_tag(selector).then_some(unique_id.to_string())
-                }).collect())
-            }
+            Tag => Ok(Self::included(&graph, included_nodes)
+                .filter(|(_, node)| {
+                    node.tags()
+                        .iter()
+                        .any(|tag| fnmatch_normalized(tag, selector).unwrap_or(false))
+                })
+                .map(|(unique_id, _)| unique_id.to_string())
+                .collect()),
 
-            Group => {
-                Ok(graph.node_map.iter().filter_map(|(unique_id, node)| {
-                    let config = node.config();
-                    let Some(group) = config.get("group") else { return None; };
-                    (group == selector).then_some(unique_id.to_string())
-                }).collect())
-            }
+            // Selects the members of every matching group, and the group nodes themselves
+            Group => Ok(Self::included(&graph, included_nodes)
+                .filter(|(unique_id, node)| {
+                    let group = match node.resource_type() {
+                        NodeType::Group(_) => Some(node.name()),
+                        _ => graph.group_of(unique_id),
+                    };
+                    group.is_some_and(|group| fnmatch_normalized(group, selector).unwrap_or(false))
+                })
+                .map(|(unique_id, _)| unique_id.to_string())
+                .collect()),
 
+            // The source name is the second to last part of a source's FQN
             Source => {
-                unimplemented!()
+                let [package, source, table] = Self::split_source_selector(selector)?;
+                Ok(Self::included(&graph, included_nodes)
+                    .filter(|(_, node)| {
+                        let NodeType::Source(source_node) = node.resource_type() else {
+                            return false;
+                        };
+                        let source_name = source_node.fqn.iter().rev().nth(1);
+                        Self::is_glob_match(node.package_name(), package)
+                            && source_name.is_some_and(|name| Self::is_glob_match(name, source))
+                            && Self::is_glob_match(node.name(), table)
+                    })
+                    .map(|(unique_id, _)| unique_id.to_string())
+                    .collect())
             }
 
+            // Matches files, and every file inside of a matching directory
             Self::Path => {
-                let Ok(regex) = &fnmatch_regex::glob_to_regex(selector) else {
+                let Ok(regex) = &fnmatch_regex::glob_to_regex(selector.trim_end_matches('/'))
+                else {
                     return Err(SelectionError::FailedRegexMatchError(selector.to_string()));
                 };
-                Ok(graph.node_map.iter().filter_map(|(unique_id, node)| {
-                    if regex.is_match_at(node.original_file_path().as_str(), 0) {
-                        Some(unique_id.to_string())
-                    } else {
-                        None
-                    }
+                Ok(Self::included(&graph, included_nodes).filter_map(|(unique_id, node)| {
+                    let mut paths = Path::new(node.original_file_path()).ancestors();
+                    paths
+                        .any(|path| path.to_str().is_some_and(|path| regex.is_match(path)))
+                        .then_some(unique_id.to_string())
                 }).collect::<Vec<String>>())
             }
 
-            File => Ok(graph
-                .node_map
-                .iter()
+            File => Ok(Self::included(&graph, included_nodes)
                 .filter_map(|(id, node)| {
                     let file_path = &node.original_file_path();
                     let Some(file_name) = Path::new(file_path).file_name() else { return None; };
@@ -149,33 +320,19 @@ impl MethodName {
                 })
                 .collect::<Vec<String>>()),
 
-            Package => Ok(graph
-                .node_map
-                .iter()
+            Package => Ok(Self::included(&graph, included_nodes)
                 .filter_map(|(id, node)| {
                     let Ok(is_match) = fnmatch_normalized(node.package_name(), selector) else { return None; };
                     is_match.then_some(id.to_string())
                 })
                 .collect::<Vec<String>>()),
 
-            Config => {
-                unimplemented!()
-            }
-
-            TestName => {
-                unimplemented!()
-            }
-
-            TestType => {
-                unimplemented!()
-            }
-
             ResourceType => {
                 let resource_key = NodeTypeKey::from_key(selector);
                 match resource_key {
                     Err(_) => Err(NoMatchingResourceType(selector.to_string())),
                     Ok(resource_key) => {
-                        let iter = graph.node_map.iter();
+                        let iter = Self::included(&graph, included_nodes);
                         let iter =
                             iter.filter(|(_, node)| node.resource_type().key() == resource_key);
                         let iter = iter.map(|(id, _)| id.clone());
@@ -186,24 +343,132 @@ impl MethodName {
 
             State => StateSelectorMethod::search(previous_state, graph, included_nodes, selector),
 
-            Exposure => {
-                unimplemented!()
+            Access => {
+                let Some(access) = super::AccessType::from_string(selector) else {
+                    return Err(InvalidSelector(format!(
+                        "Invalid access type selector '{}': expected one of: public, protected, private",
+                        selector
+                    )));
+                };
+                Ok(Self::included(&graph, included_nodes)
+                    .filter(|(_, node)| {
+                        let node_access = node.resource_type().access();
+                        node_access.is_some_and(|node_access| access == node_access.into())
+                    })
+                    .map(|(unique_id, _)| unique_id.to_string())
+                    .collect())
             }
 
-            Metric => {
-                unimplemented!()
+            Exposure | Metric | SemanticModel | SavedQuery | UnitTest => {
+                let [package, model, name] = self.split_named_selector(selector)?;
+                Ok(Self::included(&graph, included_nodes)
+                    .filter(|(_, node)| {
+                        let node_type = node.resource_type();
+                        Some(node_type.key()) == self.named_resource_type()
+                            && Self::is_glob_match(node.package_name(), package)
+                            && Self::is_glob_match(
+                                node_type.unit_test_model().unwrap_or_default(),
+                                model,
+                            )
+                            && Self::is_glob_match(node.name(), name)
+                    })
+                    .map(|(unique_id, _)| unique_id.to_string())
+                    .collect())
             }
 
-            RunResult => {
-                unimplemented!()
+            Version => {
+                if !VERSION_SELECTORS.contains(&selector) {
+                    return Err(InvalidSelector(format!(
+                        "Invalid version type selector '{}': expected one of: {}",
+                        selector,
+                        VERSION_SELECTORS.join(", ")
+                    )));
+                }
+                Ok(Self::included(&graph, included_nodes)
+                    .filter(|(_, node)| Self::is_version_match(node.resource_type(), selector))
+                    .map(|(unique_id, _)| unique_id.to_string())
+                    .collect())
             }
 
-            SourceStatus => {
-                unimplemented!()
+            // Nested config is flattened into "meta.owner" keys, which the
+            // method arguments name
+            Config => {
+                let key = method_arguments.join(".");
+                Ok(Self::included(&graph, included_nodes)
+                    .filter(|(_, node)| node.resource_type().is_configurable())
+                    .filter(|(_, node)| {
+                        node.config()
+                            .get(&key)
+                            .is_some_and(|value| Self::is_config_match(&key, value, selector))
+                    })
+                    .map(|(unique_id, _)| unique_id.to_string())
+                    .collect())
+            }
+
+            // Generic tests match by the test they run, unit tests by their name
+            TestName => Ok(Self::included(&graph, included_nodes)
+                .filter(|(_, node)| {
+                    let node_type = node.resource_type();
+                    let name = match node_type {
+                        NodeType::UnitTest(_) => Some(node.name().as_str()),
+                        _ => node_type.test_metadata_name(),
+                    };
+                    name.is_some_and(|name| fnmatch_normalized(name, selector).unwrap_or(false))
+                })
+                .map(|(unique_id, _)| unique_id.to_string())
+                .collect()),
+
+            TestType => {
+                let test_types = Self::test_types(selector)?;
+                Ok(Self::included(&graph, included_nodes)
+                    .filter(|(_, node)| {
+                        let test_type = match node.resource_type() {
+                            NodeType::Test(test) if test.test_metadata_name.is_some() => "generic",
+                            NodeType::Test(_) => "singular",
+                            NodeType::UnitTest(_) => "unit",
+                            _ => return false,
+                        };
+                        test_types.contains(&test_type)
+                    })
+                    .map(|(unique_id, _)| unique_id.to_string())
+                    .collect())
             }
 
-            Wildcard => {
-                unimplemented!()
+            // Every node dbt can select, whatever the selector
+            Wildcard => Ok(Self::included(&graph, included_nodes)
+                .filter(|(_, node)| {
+                    !matches!(
+                        node.resource_type(),
+                        NodeType::Group(_) | NodeType::Macro(_) | NodeType::Doc(_)
+                    )
+                })
+                .map(|(unique_id, _)| unique_id.to_string())
+                .collect()),
+
+            RunResult => {
+                let run_results = previous_state
+                    .as_ref()
+                    .and_then(|previous_state| previous_state.run_results.as_ref())
+                    .ok_or(RequiresPreviousState(
+                        "No previous run results found for result selector.".to_string(),
+                    ))?;
+                Ok(Self::included(&graph, included_nodes)
+                    .filter(|(unique_id, _)| {
+                        run_results
+                            .get(*unique_id)
+                            .is_some_and(|status| status == selector)
+                    })
+                    .map(|(unique_id, _)| unique_id.to_string())
+                    .collect())
+            }
+
+            // "fresher" is the only status dbt selects by
+            SourceStatus => {
+                let fresher = Self::fresher_sources(previous_state)?;
+                Ok(Self::included(&graph, included_nodes)
+                    .filter(|(unique_id, _)| selector == "fresher" && fresher.contains(unique_id))
+                    .map(|(unique_id, _)| unique_id.to_string())
+                    .collect())
             }
         }
     }
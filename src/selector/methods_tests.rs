@@ -0,0 +1,719 @@
+#[cfg(test)]
+mod methods_tests {
+    use std::collections::HashMap;
+
+    use indexmap::IndexMap;
+
+    use crate::{
+        assert_err,
+        dbt_node_selector::{AccessType, ModelNode, SourceNode, TestNode, UnitTestNode},
+        selector::spec::SelectionCriteria,
+        util::test::{
+            get_exposure_node, get_metric_node, get_resource_type, get_saved_query_node,
+            get_semantic_model_node, get_test_node, get_unit_test_node, to_config, to_strings,
+            vec_to_set, GraphBuilder,
+        },
+    };
+
+    use super::super::*;
+
+    fn get_model(fqn: &[&str], version: Option<&str>, latest_version: Option<&str>) -> NodeType {
+        NodeType::Model(ModelNode {
+            fqn: fqn.iter().map(|s| s.to_string()).collect(),
+            depends_on: vec![],
+            raw_code: "".to_string(),
+            access: AccessType::Protected,
+            version: version.map(|s| s.to_string()),
+            latest_version: latest_version.map(|s| s.to_string()),
+        })
+    }
+
+    fn get_graph() -> Rc<ParsedGraph> {
+        let nodes = vec![
+            (
+                "model.pkg.dim_customers.v1",
+                get_model(
+                    &["pkg", "marts", "dim_customers", "v1"],
+                    Some("1"),
+                    Some("2"),
+                ),
+            ),
+            (
+                "model.pkg.dim_customers.v2",
+                get_model(
+                    &["pkg", "marts", "dim_customers", "v2"],
+                    Some("2"),
+                    Some("2"),
+                ),
+            ),
+            (
+                "model.pkg.dim_customers.v3",
+                get_model(
+                    &["pkg", "marts", "dim_customers", "v3"],
+                    Some("3"),
+                    Some("2"),
+                ),
+            ),
+            (
+                "model.pkg.orders",
+                get_model(&["pkg", "marts", "orders"], None, None),
+            ),
+            ("test_orders", get_resource_type("test_orders")),
+        ];
+        let graph = nodes
+            .into_iter()
+            .fold(GraphBuilder::new(), |builder, (unique_id, node_type)| {
+                builder.node_with(unique_id, &[], |node| {
+                    node.package_name = "pkg".to_string();
+                    node.node_type = node_type;
+                })
+            })
+            .build();
+        Rc::new(graph)
+    }
+
+    fn search(method: MethodName, selector: &str) -> Result<HashSet<String>, SelectionError> {
+        let graph = get_graph();
+        let included = graph.node_map.keys().cloned().collect();
+        let result = method.search(&None, graph, &included, &[], selector)?;
+        Ok(result.into_iter().collect())
+    }
+
+    #[test]
+    fn fqn_selects_every_version_of_a_model() {
+        let result = search(FQN, "dim_customers").unwrap();
+
+        assert_eq!(
+            result,
+            vec_to_set(vec![
+                "model.pkg.dim_customers.v1",
+                "model.pkg.dim_customers.v2",
+                "model.pkg.dim_customers.v3",
+            ])
+        );
+    }
+
+    #[test]
+    fn fqn_selects_a_single_version() {
+        let expected = vec_to_set(vec!["model.pkg.dim_customers.v2"]);
+
+        assert_eq!(search(FQN, "dim_customers.v2").unwrap(), expected);
+        assert_eq!(search(FQN, "dim_customers_v2").unwrap(), expected);
+        assert_eq!(search(FQN, "pkg.marts.dim_customers.v2").unwrap(), expected);
+        assert_eq!(search(FQN, "marts.dim_customers.v2").unwrap(), expected);
+    }
+
+    #[test]
+    fn definitions_search_with_their_method() {
+        let search_definition = |method: &str, value: &str| {
+            let definition = IndexMap::from([
+                ("method".to_string(), method.to_string()),
+                ("value".to_string(), value.to_string()),
+            ]);
+            let criteria =
+                SelectionCriteria::selection_criteria_from_indexmap(value, &definition, None)?;
+            search(criteria.method, &criteria.value)
+        };
+
+        assert_eq!(
+            search_definition("version", "latest").unwrap(),
+            vec_to_set(vec!["model.pkg.dim_customers.v2"])
+        );
+        assert_eq!(
+            search_definition("fqn", "orders").unwrap(),
+            vec_to_set(vec!["model.pkg.orders"])
+        );
+        assert_err!(
+            search_definition("versions", "latest"),
+            Err(InvalidMethodError(_))
+        );
+    }
+
+    #[test]
+    fn fqn_matches_wildcards_against_the_rest_of_the_fqn() {
+        let result = search(FQN, "pkg.marts.dim_*").unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert!(search(FQN, "pkg.staging.*").unwrap().is_empty());
+        assert_eq!(
+            search(FQN, "marts.ord?rs").unwrap(),
+            vec_to_set(vec!["model.pkg.orders"])
+        );
+    }
+
+    fn search_graph(
+        builder: GraphBuilder,
+        method: MethodName,
+        selector: &str,
+    ) -> Result<HashSet<String>, SelectionError> {
+        let graph = Rc::new(builder.build());
+        let included = graph.node_map.keys().cloned().collect();
+        let result = method.search(&None, graph, &included, &[], selector)?;
+        Ok(result.into_iter().collect())
+    }
+
+    /// Searches with the method and method arguments of `raw`, i.e.
+    /// "config.materialized:view"
+    fn search_spec(builder: GraphBuilder, raw: &str) -> Result<HashSet<String>, SelectionError> {
+        let criteria = SelectionCriteria::from_single_raw_spec(raw)?;
+        let graph = Rc::new(builder.build());
+        let included = graph.node_map.keys().cloned().collect();
+        let result = criteria.method.search(
+            &None,
+            graph,
+            &included,
+            &criteria.method_arguments,
+            &criteria.value,
+        )?;
+        Ok(result.into_iter().collect())
+    }
+
+    #[test]
+    fn tag_matches_wildcards_ignoring_case() {
+        let builder = || {
+            GraphBuilder::new()
+                .node_with("model_finance", &[], |node| {
+                    node.tags = to_strings(&["finance"]);
+                })
+                .node_with("model_fin_ops", &[], |node| {
+                    node.tags = to_strings(&["marketing", "Fin_Ops"]);
+                })
+                .node_with("model_marketing", &[], |node| {
+                    node.tags = to_strings(&["marketing"]);
+                })
+        };
+
+        assert_eq!(
+            search_graph(builder(), Tag, "fin*").unwrap(),
+            vec_to_set(vec!["model_finance", "model_fin_ops"])
+        );
+        assert_eq!(
+            search_graph(builder(), Tag, "FINANCE").unwrap(),
+            vec_to_set(vec!["model_finance"])
+        );
+        assert_eq!(search_graph(builder(), Tag, "fin").unwrap(), HashSet::new());
+    }
+
+    #[test]
+    fn config_matches_nested_keys() {
+        let builder = || {
+            GraphBuilder::new()
+                .node_with("model_view", &[], |node| {
+                    node.config = to_config(&[
+                        ("materialized", "view"),
+                        ("meta.owner", "finance"),
+                        ("enabled", "true"),
+                    ]);
+                })
+                .node_with("model_table", &[], |node| {
+                    node.config =
+                        to_config(&[("materialized", "table"), ("meta.owner", "Finance")]);
+                })
+                .node_with("test_warn", &[], |node| {
+                    node.node_type = get_test_node("test_warn");
+                    node.config = to_config(&[("severity", "WARN")]);
+                })
+                // Only nodes and sources are configurable
+                .node_with("exposure_view", &[], |node| {
+                    node.node_type = get_exposure_node("exposure_view");
+                    node.config = to_config(&[("materialized", "view")]);
+                })
+        };
+
+        assert_eq!(
+            search_spec(builder(), "config.materialized:view").unwrap(),
+            vec_to_set(vec!["model_view"])
+        );
+        assert_eq!(
+            search_spec(builder(), "config.meta.owner:finance").unwrap(),
+            vec_to_set(vec!["model_view"])
+        );
+        assert_eq!(
+            search_spec(builder(), "config.enabled:True").unwrap(),
+            vec_to_set(vec!["model_view"])
+        );
+        assert_eq!(
+            search_spec(builder(), "config.severity:warn").unwrap(),
+            vec_to_set(vec!["test_warn"])
+        );
+        assert_eq!(
+            search_spec(builder(), "config.meta:finance").unwrap(),
+            HashSet::new()
+        );
+        assert_eq!(
+            search_spec(builder(), "config:view").unwrap(),
+            HashSet::new()
+        );
+    }
+
+    #[test]
+    fn path_matches_files_and_directories() {
+        let builder = || {
+            [
+                ("model_stg_orders", "models/staging/stg_orders.sql"),
+                ("model_base_orders", "models/staging/base/base_orders.sql"),
+                ("model_old_orders", "models/staging_old/old_orders.sql"),
+                ("model_orders", "models/marts/orders.sql"),
+            ]
+            .into_iter()
+            .fold(GraphBuilder::new(), |builder, (unique_id, path)| {
+                builder.node_with(unique_id, &[], |node| {
+                    node.original_file_path = path.to_string();
+                })
+            })
+        };
+        let staging = vec_to_set(vec!["model_stg_orders", "model_base_orders"]);
+
+        assert_eq!(
+            search_graph(builder(), MethodName::Path, "models/staging").unwrap(),
+            staging
+        );
+        assert_eq!(
+            search_graph(builder(), MethodName::Path, "models/staging/").unwrap(),
+            staging
+        );
+        assert_eq!(
+            search_graph(builder(), MethodName::Path, "models/*/orders.sql").unwrap(),
+            vec_to_set(vec!["model_orders"])
+        );
+    }
+
+    /// A generic not_null and unique test, a singular test, a unit test and a model
+    fn test_builder() -> GraphBuilder {
+        let test_node = |test_metadata_name: Option<&str>| {
+            NodeType::Test(TestNode {
+                fqn: vec![],
+                raw_code: String::new(),
+                test_metadata_name: test_metadata_name.map(|s| s.to_string()),
+            })
+        };
+        GraphBuilder::new()
+            .node("model_orders", &[])
+            .node_with("test_not_null", &["model_orders"], |node| {
+                node.node_type = test_node(Some("not_null"));
+            })
+            .node_with("test_unique", &["model_orders"], |node| {
+                node.node_type = test_node(Some("unique"));
+            })
+            .node_with("test_singular", &["model_orders"], |node| {
+                node.node_type = test_node(None);
+            })
+            .node_with("unit_test_orders", &["model_orders"], |node| {
+                node.name = "test_orders".to_string();
+                node.node_type = get_unit_test_node("unit_test_orders");
+            })
+    }
+
+    #[test]
+    fn test_name_matches_generic_tests_and_unit_tests() {
+        assert_eq!(
+            search_graph(test_builder(), TestName, "not_null").unwrap(),
+            vec_to_set(vec!["test_not_null"])
+        );
+        assert_eq!(
+            search_graph(test_builder(), TestName, "*").unwrap(),
+            vec_to_set(vec!["test_not_null", "test_unique", "unit_test_orders"])
+        );
+        assert_eq!(
+            search_graph(test_builder(), TestName, "test_orders").unwrap(),
+            vec_to_set(vec!["unit_test_orders"])
+        );
+    }
+
+    #[test]
+    fn test_type_selects_by_kind_of_test() {
+        let search_type = |selector| search_graph(test_builder(), TestType, selector);
+
+        let generic = vec_to_set(vec!["test_not_null", "test_unique"]);
+        assert_eq!(search_type("generic").unwrap(), generic);
+        assert_eq!(search_type("schema").unwrap(), generic);
+        assert_eq!(
+            search_type("singular").unwrap(),
+            vec_to_set(vec!["test_singular"])
+        );
+        assert_eq!(
+            search_type("unit").unwrap(),
+            vec_to_set(vec!["unit_test_orders"])
+        );
+        // Data tests are both generic and singular tests, and like in dbt,
+        // so is any part of "data"
+        let data = vec_to_set(vec!["test_not_null", "test_unique", "test_singular"]);
+        assert_eq!(search_type("data").unwrap(), data);
+        assert_eq!(search_type("dat").unwrap(), data);
+        assert_err!(search_type("integration"), Err(InvalidSelector(_)));
+    }
+
+    #[test]
+    fn wildcard_selects_every_node_but_groups_macros_and_docs() {
+        let builder = test_builder()
+            .node_with("group.pkg.finance", &[], |node| {
+                node.node_type = get_resource_type("group");
+            })
+            .node_with("macro.pkg.cents", &[], |node| {
+                node.node_type = get_resource_type("macro");
+            })
+            .node_with("exposure_dashboard", &["model_orders"], |node| {
+                node.node_type = get_resource_type("exposure_dashboard");
+            });
+
+        assert_eq!(
+            search_graph(builder, Wildcard, "*").unwrap(),
+            vec_to_set(vec![
+                "model_orders",
+                "test_not_null",
+                "test_unique",
+                "test_singular",
+                "unit_test_orders",
+                "exposure_dashboard",
+            ])
+        );
+    }
+
+    #[test]
+    fn version_selects_by_relation_to_the_latest_version() {
+        assert_eq!(
+            search(Version, "latest").unwrap(),
+            vec_to_set(vec!["model.pkg.dim_customers.v2"])
+        );
+        assert_eq!(
+            search(Version, "prerelease").unwrap(),
+            vec_to_set(vec!["model.pkg.dim_customers.v3"])
+        );
+        assert_eq!(
+            search(Version, "old").unwrap(),
+            vec_to_set(vec!["model.pkg.dim_customers.v1"])
+        );
+        // Only models can be unversioned
+        assert_eq!(
+            search(Version, "none").unwrap(),
+            vec_to_set(vec!["model.pkg.orders"])
+        );
+    }
+
+    #[test]
+    fn version_rejects_unknown_selectors() {
+        assert_err!(search(Version, "newest"), Err(InvalidSelector(_)));
+    }
+
+    /// Searches a model and sources against the run results and source
+    /// freshness of `previous_state`
+    fn search_run(
+        method: MethodName,
+        selector: &str,
+        previous_state: Option<PreviousState>,
+    ) -> Result<HashSet<String>, SelectionError> {
+        let graph = Rc::new(
+            GraphBuilder::new()
+                .node("model_orders", &[])
+                .node("source_orders", &[])
+                .node("source_items", &[])
+                .node("source_new", &[])
+                .node("source_failing", &[])
+                .node("source_payments", &[])
+                .build(),
+        );
+        let included = graph.node_map.keys().cloned().collect();
+        let previous_state = previous_state.map(Rc::new);
+        let result = method.search(&previous_state, graph, &included, &[], selector)?;
+        Ok(result.into_iter().collect())
+    }
+
+    fn to_map<T: Clone>(items: &[(&str, T)]) -> Option<HashMap<String, T>> {
+        Some(
+            items
+                .iter()
+                .map(|(unique_id, value)| (unique_id.to_string(), value.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn result_selects_by_previous_status() {
+        let previous_state = || PreviousState {
+            run_results: to_map(&[
+                ("model_orders", "error".to_string()),
+                ("source_orders", "pass".to_string()),
+                ("model_deleted", "error".to_string()),
+            ]),
+            ..PreviousState::default()
+        };
+
+        assert_eq!(
+            search_run(RunResult, "error", Some(previous_state())).unwrap(),
+            vec_to_set(vec!["model_orders"])
+        );
+        assert_eq!(
+            search_run(RunResult, "pass", Some(previous_state())).unwrap(),
+            vec_to_set(vec!["source_orders"])
+        );
+        assert_err!(
+            search_run(RunResult, "error", Some(PreviousState::default())),
+            Err(RequiresPreviousState(_))
+        );
+    }
+
+    #[test]
+    fn source_status_selects_sources_loaded_since_the_previous_run() {
+        let (earlier, later) = ("2024-06-01T00:00:00+00:00", "2024-06-01T12:00:00+00:00");
+        let previous_state = || PreviousState {
+            sources: to_map(&[
+                ("source_orders", Some(earlier.to_string())),
+                ("source_items", Some(earlier.to_string())),
+                ("source_failing", None),
+                ("source_payments", Some(earlier.to_string())),
+            ]),
+            sources_current: to_map(&[
+                ("source_orders", Some(later.to_string())),
+                ("source_items", Some(earlier.to_string())),
+                ("source_new", Some(earlier.to_string())),
+                ("source_failing", Some(later.to_string())),
+                ("source_payments", None),
+            ]),
+            ..PreviousState::default()
+        };
+
+        // New sources are fresher, and those which errored in either run never are
+        assert_eq!(
+            search_run(SourceStatus, "fresher", Some(previous_state())).unwrap(),
+            vec_to_set(vec!["source_orders", "source_new"])
+        );
+        assert_eq!(
+            search_run(SourceStatus, "stale", Some(previous_state())).unwrap(),
+            HashSet::new()
+        );
+        assert_err!(
+            search_run(SourceStatus, "fresher", None),
+            Err(RequiresPreviousState(_))
+        );
+    }
+
+    #[test]
+    fn versions_compare_numerically_when_possible() {
+        assert_eq!(MethodName::compare_versions("10", "9"), Ordering::Greater);
+        assert_eq!(
+            MethodName::compare_versions("1.5", "1.10"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            MethodName::compare_versions("beta", "alpha"),
+            Ordering::Greater
+        );
+        assert_eq!(MethodName::compare_versions("2", "2.0"), Ordering::Equal);
+    }
+
+    fn get_unit_test(model: &str, name: &str) -> NodeType {
+        NodeType::UnitTest(UnitTestNode {
+            fqn: vec!["pkg".to_string(), model.to_string(), name.to_string()],
+            depends_on: vec![format!("model.pkg.{}", model)],
+            model: model.to_string(),
+        })
+    }
+
+    fn get_source(fqn: &[&str]) -> NodeType {
+        NodeType::Source(SourceNode {
+            fqn: fqn.iter().map(|s| s.to_string()).collect(),
+            raw_code: "".to_string(),
+        })
+    }
+
+    fn search_named(method: MethodName, selector: &str) -> Result<HashSet<String>, SelectionError> {
+        let nodes = vec![
+            (
+                "source.pkg.ecom.orders",
+                "orders",
+                "pkg",
+                get_source(&["pkg", "staging", "ecom", "orders"]),
+            ),
+            (
+                "source.pkg.ecom.customers",
+                "customers",
+                "pkg",
+                get_source(&["pkg", "staging", "ecom", "customers"]),
+            ),
+            (
+                "source.other.ecom.orders",
+                "orders",
+                "other",
+                get_source(&["other", "ecom", "orders"]),
+            ),
+            (
+                "exposure.pkg.orders",
+                "orders",
+                "pkg",
+                get_exposure_node("orders"),
+            ),
+            (
+                "metric.pkg.orders",
+                "orders",
+                "pkg",
+                get_metric_node("orders"),
+            ),
+            (
+                "semantic_model.pkg.orders",
+                "orders",
+                "pkg",
+                get_semantic_model_node("orders"),
+            ),
+            (
+                "semantic_model.other.orders",
+                "orders",
+                "other",
+                get_semantic_model_node("orders"),
+            ),
+            (
+                "semantic_model.pkg.customers",
+                "customers",
+                "pkg",
+                get_semantic_model_node("customers"),
+            ),
+            (
+                "saved_query.pkg.orders",
+                "orders",
+                "pkg",
+                get_saved_query_node("orders"),
+            ),
+            (
+                "unit_test.pkg.orders.test_total",
+                "test_total",
+                "pkg",
+                get_unit_test("orders", "test_total"),
+            ),
+            (
+                "unit_test.pkg.customers.test_total",
+                "test_total",
+                "pkg",
+                get_unit_test("customers", "test_total"),
+            ),
+            (
+                "model.pkg.orders",
+                "orders",
+                "pkg",
+                get_resource_type("model"),
+            ),
+        ];
+        let graph = nodes
+            .into_iter()
+            .fold(
+                GraphBuilder::new(),
+                |builder, (unique_id, name, package_name, node_type)| {
+                    builder.node_with(unique_id, &[], |node| {
+                        node.name = name.to_string();
+                        node.package_name = package_name.to_string();
+                        node.node_type = node_type;
+                    })
+                },
+            )
+            .build();
+        let graph = Rc::new(graph);
+        let included = graph.node_map.keys().cloned().collect();
+        let result = method.search(&None, graph, &included, &[], selector)?;
+        Ok(result.into_iter().collect())
+    }
+
+    #[test]
+    fn semantic_model_selects_by_name_and_package() {
+        assert_eq!(
+            search_named(SemanticModel, "orders").unwrap(),
+            vec_to_set(vec![
+                "semantic_model.pkg.orders",
+                "semantic_model.other.orders"
+            ])
+        );
+        assert_eq!(
+            search_named(SemanticModel, "other.orders").unwrap(),
+            vec_to_set(vec!["semantic_model.other.orders"])
+        );
+        assert_eq!(
+            search_named(SemanticModel, "pkg.*").unwrap(),
+            vec_to_set(vec![
+                "semantic_model.pkg.orders",
+                "semantic_model.pkg.customers"
+            ])
+        );
+        assert_err!(
+            search_named(SemanticModel, "pkg.orders.extra"),
+            Err(InvalidSelector(_))
+        );
+    }
+
+    #[test]
+    fn source_selects_by_source_and_table_name() {
+        assert_eq!(
+            search_named(Source, "ecom").unwrap(),
+            vec_to_set(vec![
+                "source.pkg.ecom.orders",
+                "source.pkg.ecom.customers",
+                "source.other.ecom.orders",
+            ])
+        );
+        assert_eq!(
+            search_named(Source, "ecom.orders").unwrap(),
+            vec_to_set(vec!["source.pkg.ecom.orders", "source.other.ecom.orders"])
+        );
+        assert_eq!(
+            search_named(Source, "other.ecom.*").unwrap(),
+            vec_to_set(vec!["source.other.ecom.orders"])
+        );
+        // The path of a source isn't part of its source name
+        assert_eq!(search_named(Source, "staging").unwrap(), HashSet::new());
+        assert_err!(
+            search_named(Source, "pkg.ecom.orders.extra"),
+            Err(InvalidSelector(_))
+        );
+    }
+
+    #[test]
+    fn fqn_never_selects_sources() {
+        assert_eq!(
+            search_named(FQN, "pkg.staging.ecom.*").unwrap(),
+            HashSet::new()
+        );
+        assert!(search_named(FQN, "*")
+            .unwrap()
+            .iter()
+            .all(|unique_id| !unique_id.starts_with("source.")));
+    }
+
+    #[test]
+    fn exposure_and_metric_select_by_name_and_package() {
+        assert_eq!(
+            search_named(Exposure, "orders").unwrap(),
+            vec_to_set(vec!["exposure.pkg.orders"])
+        );
+        assert_eq!(
+            search_named(Metric, "pkg.order*").unwrap(),
+            vec_to_set(vec!["metric.pkg.orders"])
+        );
+        assert_eq!(
+            search_named(Metric, "other.orders").unwrap(),
+            HashSet::new()
+        );
+    }
+
+    #[test]
+    fn saved_query_selects_only_saved_queries() {
+        assert_eq!(
+            search_named(SavedQuery, "orders").unwrap(),
+            vec_to_set(vec!["saved_query.pkg.orders"])
+        );
+    }
+
+    #[test]
+    fn unit_test_selects_by_model_under_test() {
+        assert_eq!(
+            search_named(UnitTest, "test_total").unwrap(),
+            vec_to_set(vec![
+                "unit_test.pkg.orders.test_total",
+                "unit_test.pkg.customers.test_total",
+            ])
+        );
+        assert_eq!(
+            search_named(UnitTest, "pkg.orders.test_total").unwrap(),
+            vec_to_set(vec!["unit_test.pkg.orders.test_total"])
+        );
+        assert_eq!(
+            search_named(UnitTest, "*.cust*.*").unwrap(),
+            vec_to_set(vec!["unit_test.pkg.customers.test_total"])
+        );
+    }
+}
@@ -1,18 +1,29 @@
 /// https://github.com/dbt-labs/dbt-core/blob/a203fe866ad3e969e7de9cc24ddbbef1934aa7d0/core/dbt/graph/selector_methods.py
+pub mod analysis;
+pub mod autocomplete;
+pub mod cache;
+pub mod canonical;
+pub mod diagnostic;
+pub mod explain;
 pub mod methods;
 pub mod node_selector;
 pub mod resource_type_filter;
 pub mod spec;
+pub mod spec_records;
 pub mod state_selector_method;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-enum AccessType {
+pub enum AccessType {
     Protected,
     Private,
     Public,
 }
 
 impl AccessType {
+    pub fn all() -> &'static [AccessType] {
+        &[AccessType::Protected, AccessType::Private, AccessType::Public]
+    }
+
     pub fn key(&self) -> &str {
         match self {
             AccessType::Protected => "protected",
@@ -20,6 +31,23 @@ impl AccessType {
             AccessType::Public => "public",
         }
     }
+
+    pub fn from_string(input: &str) -> Option<AccessType> {
+        AccessType::all()
+            .iter()
+            .find(|access| access.key() == input)
+            .copied()
+    }
+}
+
+impl From<&crate::dbt_node_selector::AccessType> for AccessType {
+    fn from(access: &crate::dbt_node_selector::AccessType) -> Self {
+        match access {
+            crate::dbt_node_selector::AccessType::Protected => AccessType::Protected,
+            crate::dbt_node_selector::AccessType::Private => AccessType::Private,
+            crate::dbt_node_selector::AccessType::Public => AccessType::Public,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -48,6 +76,16 @@ pub enum MethodName {
     RunResult,
     SourceStatus,
     Wildcard,
+    /// Yields models from included by their version: latest, prerelease, old or none.
+    Version,
+    /// Yields models from included with the given access: public, protected or private.
+    Access,
+    /// Yields semantic models from included by `name` or `package.name`.
+    SemanticModel,
+    /// Yields saved queries from included by `name` or `package.name`.
+    SavedQuery,
+    /// Yields unit tests from included by `name`, `package.name` or `package.model.name`.
+    UnitTest,
 }
 
 /// Decide if the value is probably a path. Windows has two path separators, so
@@ -63,6 +101,34 @@ fn _probably_path(value: &str) -> bool {
 use MethodName::*;
 
 impl MethodName {
+    /// Every method which can be written as `<method>:<value>`
+    pub fn all() -> &'static [MethodName] {
+        &[
+            FQN,
+            Tag,
+            Group,
+            Source,
+            Path,
+            File,
+            Package,
+            Config,
+            TestName,
+            TestType,
+            ResourceType,
+            State,
+            Exposure,
+            Metric,
+            RunResult,
+            SourceStatus,
+            Wildcard,
+            Version,
+            Access,
+            SemanticModel,
+            SavedQuery,
+            UnitTest,
+        ]
+    }
+
     pub fn key(&self) -> &str {
         match self {
             FQN => "fqn",
@@ -82,6 +148,11 @@ impl MethodName {
             RunResult => "result",
             SourceStatus => "source_status",
             Wildcard => "wildcard",
+            Version => "version",
+            Access => "access",
+            SemanticModel => "semantic_model",
+            SavedQuery => "saved_query",
+            UnitTest => "unit_test",
         }
     }
 
@@ -104,6 +175,11 @@ impl MethodName {
             "result" => Some(RunResult),
             "source_status" => Some(SourceStatus),
             "wildcard" => Some(Wildcard),
+            "version" => Some(Version),
+            "access" => Some(Access),
+            "semantic_model" => Some(SemanticModel),
+            "saved_query" => Some(SavedQuery),
+            "unit_test" => Some(UnitTest),
             _ => None,
         }
     }
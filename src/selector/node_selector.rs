@@ -1,32 +1,48 @@
+#[cfg(test)]
+#[path = "node_selector_tests.rs"]
+mod node_selector_tests;
+
 use wai_bindgen_rust::Handle;
 
+use crate::args::{parse_union, parse_union_with_syntax};
 use crate::graph::{
     node::{WrapperNode, WrapperNodeExt},
     parsed_graph::ParsedGraph,
+    search_index::SearchIndex,
     UniqueId,
 };
 use std::{
-    cell::RefCell,
     collections::{HashMap, HashSet},
     rc::Rc,
 };
 
-use crate::selector::spec::{IndirectSelection, SelectionCriteria, SelectionGroup, SelectionSpec};
+use crate::selector::spec::{
+    IndirectSelection, SelectionCriteria, SelectionGroup, SelectionSpec, TraversalSpec,
+};
 
 use crate::dbt_node_selector::{
-    Edge, Node, ResourceTypeFilter, SelectionError, SelectorCreateError,
+    AccessViolation, Completion, Edge, GraphDiff, GraphLayout, GraphPatch, GroupMembership,
+    ImpactReport, LayoutOptions, Node, NodeExplanation, ResourceTypeFilter, SearchMatch,
+    SelectionError, SelectionSpecRecord, SelectorCreateError, SelectorSyntax, SelectorWarning,
 };
 
 use crate::IndirectSelection::*;
 use crate::SelectionError::*;
 
-use super::{spec::SetOperation, state_selector_method::StateSelectorMethod};
+use super::{
+    cache::{resource_type_filter_key, SelectionCache},
+    spec::SetOperation,
+};
 
 pub struct PreviousState {
     pub graph: Option<Rc<ParsedGraph>>,
-    /// modified_macros is a cache of computed macros, which allows for reuse
-    /// of prior modified macros computations.
-    pub modified_macros: RefCell<Option<HashSet<UniqueId>>>,
+    /// The status of each node in the previous `run_results.json`, for `result:`
+    pub run_results: Option<HashMap<UniqueId, String>>,
+    /// The `max_loaded_at` of each source in the previous `sources.json`, for
+    /// `source_status:`. Sources whose freshness check errored have none.
+    pub sources: Option<HashMap<UniqueId, Option<String>>>,
+    /// The same for the current `sources.json`
+    pub sources_current: Option<HashMap<UniqueId, Option<String>>>,
 }
 
 impl PreviousState {
@@ -34,69 +50,38 @@ impl PreviousState {
         self.graph
             .as_ref()
             .and_then(|graph| graph.node_map.get(unique_id))
-            .cloned()
+            .map(|node| WrapperNode::clone(node))
     }
 
     pub fn default() -> Self {
         Self {
             graph: None,
-            modified_macros: None.into(),
+            run_results: None,
+            sources: None,
+            sources_current: None,
         }
     }
 
     pub fn from_graph(prev_graph: Rc<ParsedGraph>) -> Self {
         Self {
             graph: Some(prev_graph),
-            modified_macros: None.into(),
-        }
-    }
-
-    pub fn from_graph_and_macros(
-        prev_graph: Rc<ParsedGraph>,
-        modified_macros: HashSet<UniqueId>,
-    ) -> Self {
-        Self {
-            graph: Some(prev_graph),
-            modified_macros: Some(modified_macros.into_iter().collect()).into(),
-        }
-    }
-
-    pub fn get_modified_macros(
-        &self,
-        current_graph: &ParsedGraph,
-    ) -> Result<Option<HashSet<UniqueId>>, SelectionError> {
-        let previous_graph = &self.graph;
-        let modified_macros = self.modified_macros.borrow();
-        let modified_macros = modified_macros.as_ref();
-        match (previous_graph, modified_macros) {
-            (_, Some(previous_macros)) => Ok(Some(previous_macros.clone())),
-            (None, _) => Err(RequiresPreviousState(
-                "No previous state to generate modified macros.".to_string(),
-            )),
-            (Some(previous_graph), _) => {
-                let modified_macros = StateSelectorMethod::generate_modified_macros(
-                    current_graph,
-                    &(previous_graph.clone()),
-                )?;
-                Ok(Some(modified_macros))
-            }
+            ..Self::default()
         }
     }
 }
 
 pub struct NodeSelector {
     pub graph: Rc<ParsedGraph>,
-    pub previous_state: Option<Rc<PreviousState>>,
+    /// Set through `set_previous_state`, which keeps the cache consistent
+    previous_state: Option<Rc<PreviousState>>,
+    pub search_index: SearchIndex,
+    pub cache: SelectionCache,
 }
 
-type DirectNodes = HashSet<UniqueId>;
-type IndirectNodes = HashSet<UniqueId>;
+pub type DirectNodes = HashSet<UniqueId>;
+pub type IndirectNodes = HashSet<UniqueId>;
 
-trait NodeMatch {
-    fn node_is_match(&self, node: WrapperNode) -> bool;
-}
-
-trait OtherSelectNodes {
+pub trait OtherSelectNodes {
     /// Given the set of models selected by the explicit part of the
     /// selector (like "tag:foo"), apply the modifiers on the spec ("+"/"@").
     /// Return the set of additional nodes that should be collected (which may
@@ -153,45 +138,40 @@ impl NodeSelector {
             parent_map.insert(edge.unique_id.to_owned(), parents);
         }
         let graph = Rc::new(ParsedGraph::from_parents(node_map, parent_map));
+        let search_index = SearchIndex::from_graph(&graph);
         Ok(Self {
             graph,
             previous_state,
+            search_index,
+            cache: SelectionCache::default(),
         })
     }
 
+    pub fn previous_state(&self) -> &Option<Rc<PreviousState>> {
+        &self.previous_state
+    }
+
+    /// Replaces the state `state:` methods compare against, forgetting any
+    /// cached result which may depend on it
+    pub fn set_previous_state(&mut self, previous_state: Option<Rc<PreviousState>>) {
+        self.previous_state = previous_state;
+        self.cache.clear();
+    }
+
     fn select_included(
         &self,
         included_nodes: &HashSet<UniqueId>,
         spec: &SelectionCriteria,
     ) -> Result<HashSet<UniqueId>, SelectionError> {
-        let new_previous_state = spec
-            .method
-            .prepare(self.graph.clone(), &self.previous_state);
-
-        match (self.previous_state.clone(), new_previous_state) {
-            (Some(saved_previous_state), Some(Ok(new_previous_state))) => {
-                // Write to the modified_macros cache whenever we get new_previous_state
-                *saved_previous_state.modified_macros.borrow_mut() =
-                    new_previous_state.modified_macros.into_inner();
-
-                let result =
-                    spec.method
-                        .search(&None, self.graph.clone(), included_nodes, &spec.value)?;
-                Ok(HashSet::from_iter(result.iter().map(|s| s.to_owned())))
-            }
-            (_, None) => {
-                let result =
-                    spec.method
-                        .search(&None, self.graph.clone(), included_nodes, &spec.value)?;
-                Ok(HashSet::from_iter(result.iter().map(|s| s.to_owned())))
-            }
-            (_, Some(Err(e))) => return Err(e),
-            (_, _) => {
-                return Err(RequiresPreviousState(
-                    "Unknown issue with previous state".to_string(),
-                ))
-            }
-        }
+        spec.method.prepare(&self.previous_state)?;
+        let result = spec.method.search(
+            &self.previous_state,
+            self.graph.clone(),
+            included_nodes,
+            &spec.method_arguments,
+            &spec.value,
+        )?;
+        Ok(HashSet::from_iter(result))
     }
 
     fn successors(&self, node_id: &UniqueId) -> Option<impl Iterator<Item = &UniqueId>> {
@@ -220,28 +200,60 @@ impl NodeSelector {
     fn get_nodes_from_criteria(
         &self,
         spec: &SelectionCriteria,
+        included_nodes: &HashSet<UniqueId>,
     ) -> Result<(DirectNodes, IndirectNodes), SelectionError> {
-        let nodes: HashSet<UniqueId> = self
-            .graph
-            .node_map
-            .keys()
-            .map(|id| id.to_string())
-            .collect();
+        let collected = self.collect_nodes_from_criteria(spec, included_nodes)?;
+        self.get_nodes_from_collected(spec, &collected)
+    }
+
+    /// Every node in the graph, the universe of an unrestricted selection
+    pub fn all_nodes(&self) -> HashSet<UniqueId> {
+        self.graph.node_map.keys().cloned().collect()
+    }
+
+    /// Collects the nodes of `included_nodes` directly matched by the
+    /// criteria's method
+    pub fn collect_nodes_from_criteria(
+        &self,
+        spec: &SelectionCriteria,
+        included_nodes: &HashSet<UniqueId>,
+    ) -> Result<HashSet<UniqueId>, SelectionError> {
         // TODO: SelectorReportInvalidSelector in py has better error
-        let collected: HashSet<UniqueId> = self.select_included(&nodes, &spec)?;
+        self.select_included(included_nodes, spec)
+    }
+
+    /// Target IDs resolved outside of this library must exist in the graph,
+    /// otherwise we would silently traverse from nothing.
+    pub fn get_traversal_targets(
+        &self,
+        traversal: &TraversalSpec,
+    ) -> Result<HashSet<UniqueId>, SelectionError> {
+        match traversal
+            .target_ids
+            .iter()
+            .find(|id| !self.graph.node_map.contains_key(*id))
+        {
+            Some(missing) => Err(NodeNotInGraph(missing.to_string())),
+            None => Ok(traversal.target_ids.clone()),
+        }
+    }
 
+    /// Applies the graph operators and indirect selection of the criteria to
+    /// the nodes it collected.
+    pub fn get_nodes_from_collected(
+        &self,
+        spec: &SelectionCriteria,
+        collected: &HashSet<UniqueId>,
+    ) -> Result<(DirectNodes, IndirectNodes), SelectionError> {
+        let neighbors = self.collect_specified_neighbors(spec, collected)?;
+        let selected: HashSet<UniqueId> = collected
+            .union(&neighbors)
+            .map(|id| id.to_string())
+            .collect();
         match &spec.indirect_selection {
-            Empty => Ok((collected, HashSet::new())),
-            indirect_selector => {
-                let neighbors = self.collect_specified_neighbors(&spec, &collected)?;
-                let selected: HashSet<UniqueId> = collected
-                    .union(&neighbors)
-                    .map(|id| id.to_string())
-                    .collect();
-                let (direct_nodes, indirect_nodes) =
-                    self.expand_selection(&selected, indirect_selector)?;
-                Ok((direct_nodes, indirect_nodes))
-            }
+            // Do not expand to adjacent tests
+            Empty => Ok((selected, HashSet::new())),
+            indirect_selector => self.expand_selection(&selected, indirect_selector),
         }
     }
 
@@ -257,33 +269,28 @@ impl NodeSelector {
             return Ok(direct_nodes.clone());
         }
         let mut selected = direct_nodes.clone();
-        match indirect_selection {
-            Cautious => {
-                for unique_id in indirect_nodes {
-                    let Some(node) = self.graph.node_map.get(unique_id) else {
-                        continue;
-                    };
-                    if selected.is_superset(&node.depends_on()) {
-                        selected.insert(unique_id.to_string());
-                    }
-                }
-                Ok(selected)
-            }
-            Buildable => {
-                let selected_and_parents: HashSet<String> =
-                    self.graph.and_select_parents(&selected, &None)?;
-                for unique_id in indirect_nodes {
-                    let Some(node) = self.graph.node_map.get(unique_id) else {
-                        continue;
-                    };
-                    if selected_and_parents.is_superset(&node.depends_on()) {
-                        selected.insert(unique_id.to_string());
-                    }
-                }
-                Ok(selected)
+        let parents_must_be_in = match indirect_selection {
+            Cautious => direct_nodes.clone(),
+            Buildable => self.graph.and_select_parents(direct_nodes, &None)?,
+            _ => return Ok(selected),
+        };
+        for unique_id in indirect_nodes {
+            if self.graph.node_map.contains_key(unique_id)
+                && self.depends_on_subset(unique_id, &parents_must_be_in)
+            {
+                selected.insert(unique_id.to_string());
             }
-            _ => Ok(selected),
         }
+        Ok(selected)
+    }
+
+    /// Whether every node `unique_id` depends on is in `nodes`. Dependencies
+    /// are read from the graph's edges, which are what the graph was built from.
+    fn depends_on_subset(&self, unique_id: &UniqueId, nodes: &HashSet<UniqueId>) -> bool {
+        self.graph
+            .parents_map
+            .get(unique_id)
+            .is_none_or(|parents| parents.iter().all(|parent| nodes.contains(parent)))
     }
 
     pub fn get_selected_type(
@@ -291,8 +298,36 @@ impl NodeSelector {
         selection_group: &SelectionGroup,
         resource_type_filter: &ResourceTypeFilter,
     ) -> Result<HashSet<UniqueId>, SelectionError> {
+        let key = (
+            selection_group.cache_key(),
+            resource_type_filter_key(resource_type_filter),
+        );
+        if let Some(selected) = self.cache.get_selection(&key) {
+            return Ok(selected);
+        }
         let (selected_nodes, _indirect_only) = self.select_nodes(selection_group)?;
 
+        let selected = self.filter_selection(&selected_nodes, resource_type_filter)?;
+        self.cache.insert_selection(key, selected.clone());
+        Ok(selected)
+    }
+
+    /// Like `get_selected_type`, but only nodes of `included_nodes` can be
+    /// selected. Graph operators still traverse the whole graph, so `a+`
+    /// reaches included descendants of `a` through excluded nodes.
+    pub fn get_selected_included(
+        &self,
+        selection_group: &SelectionGroup,
+        included_nodes: &HashSet<UniqueId>,
+        resource_type_filter: &ResourceTypeFilter,
+    ) -> Result<HashSet<UniqueId>, SelectionError> {
+        let (selected_nodes, _indirect_only) =
+            self.select_nodes_included(selection_group, included_nodes)?;
+        let selected_nodes = selected_nodes
+            .intersection(included_nodes)
+            .cloned()
+            .collect();
+
         self.filter_selection(&selected_nodes, resource_type_filter)
     }
 
@@ -351,11 +386,26 @@ impl NodeSelector {
     /// This is the main point of entry for turning a spec into a set of nodes:
     /// - Recurse through spec, select by criteria, combine by set operation
     /// - Return final (unfiltered) selection set
-    fn select_nodes(
+    pub fn select_nodes(
+        &self,
+        selection_group: &SelectionGroup,
+    ) -> Result<(DirectNodes, IndirectNodes), SelectionError> {
+        self.select_nodes_included(selection_group, &self.all_nodes())
+    }
+
+    /// Like `select_nodes`, but methods only match nodes of `included_nodes`
+    pub fn select_nodes_included(
         &self,
         selection_group: &SelectionGroup,
+        included_nodes: &HashSet<UniqueId>,
     ) -> Result<(DirectNodes, IndirectNodes), SelectionError> {
-        let (direct_nodes, indirect_nodes) = self.select_nodes_recursively(selection_group)?;
+        // Results can only be shared when any node may be selected
+        let memoise = included_nodes.len() == self.graph.node_map.len()
+            && included_nodes
+                .iter()
+                .all(|id| self.graph.node_map.contains_key(id));
+        let (direct_nodes, indirect_nodes) =
+            self.select_nodes_recursively(selection_group, included_nodes, memoise)?;
         let indirect_only =
             HashSet::difference(&indirect_nodes, &direct_nodes).map(|s| s.to_string());
         Ok((direct_nodes.to_owned(), indirect_only.collect()))
@@ -363,44 +413,75 @@ impl NodeSelector {
 
     /// If the spec is a composite spec (a union, difference, or intersection),
     /// recurse into its selections and combine them. If the spec is a concrete
-    /// selection criteria, resolve that using the given graph.
+    /// selection criteria, resolve that using the given graph. With `memoise`,
+    /// the results of the group and its subgroups are cached.
     fn select_nodes_recursively(
         &self,
         selection_group: &SelectionGroup,
+        included_nodes: &HashSet<UniqueId>,
+        memoise: bool,
     ) -> Result<(DirectNodes, IndirectNodes), SelectionError> {
-        match &selection_group.spec {
-            SelectionSpec::SelectionCriteria(spec) => self.get_nodes_from_criteria(&spec),
+        let key = memoise.then(|| selection_group.cache_key());
+        if let Some(nodes) = key.as_ref().and_then(|key| self.cache.get_group(key)) {
+            return Ok(nodes);
+        }
+        let nodes = match &selection_group.spec {
+            SelectionSpec::SelectionCriteria(spec) => {
+                self.get_nodes_from_criteria(&spec, included_nodes)
+            }
+            SelectionSpec::Traversal(traversal) => {
+                let target_ids = self.get_traversal_targets(traversal)?;
+                self.get_nodes_from_collected(&traversal.criteria, &target_ids)
+            }
             SelectionSpec::SetOperation(operation) => {
                 let bundles = selection_group
                     .components
                     .iter()
-                    .map(|component| self.select_nodes_recursively(component));
-
-                let mut direct_sets: Vec<HashSet<UniqueId>> = vec![];
-                let mut indirect_sets: Vec<HashSet<UniqueId>> = vec![];
-
-                for result in bundles {
-                    let (direct, indirect) = result?;
-                    indirect_sets.push(direct.union(&indirect).map(|s| s.to_owned()).collect());
-                    direct_sets.push(direct);
-                }
-
-                let initial_direct = operation.combine_selections(&direct_sets);
-                let indirect_nodes = operation.combine_selections(&indirect_sets);
-
-                let direct_nodes: HashSet<UniqueId> = self.incorporate_indirect_nodes(
-                    &initial_direct,
-                    &indirect_nodes,
-                    &selection_group.indirect_selection,
-                )?;
-
-                match selection_group.expect_exists && direct_nodes.len() == 0 {
-                    true => Err(SelectionError::NoNodesForSelectionCriteria(
-                        selection_group.raw.clone(),
-                    )),
-                    false => Ok((direct_nodes, indirect_nodes)),
-                }
+                    .map(|component| {
+                        self.select_nodes_recursively(component, included_nodes, memoise)
+                    })
+                    .collect::<Result<Vec<_>, SelectionError>>()?;
+
+                self.combine_components(selection_group, operation, &bundles)
             }
+        }?;
+        if let Some(key) = key {
+            self.cache.insert_group(key, nodes.clone());
+        }
+        Ok(nodes)
+    }
+
+    /// Combines the direct and indirect selections of each component of a
+    /// set operation, incorporating any indirect nodes whose parents are now
+    /// all selected.
+    pub fn combine_components(
+        &self,
+        selection_group: &SelectionGroup,
+        operation: &SetOperation,
+        bundles: &[(DirectNodes, IndirectNodes)],
+    ) -> Result<(DirectNodes, IndirectNodes), SelectionError> {
+        let mut direct_sets: Vec<HashSet<UniqueId>> = vec![];
+        let mut indirect_sets: Vec<HashSet<UniqueId>> = vec![];
+
+        for (direct, indirect) in bundles {
+            indirect_sets.push(direct.union(indirect).map(|s| s.to_owned()).collect());
+            direct_sets.push(direct.clone());
+        }
+
+        let initial_direct = operation.combine_selections(&direct_sets);
+        let indirect_nodes = operation.combine_selections(&indirect_sets);
+
+        let direct_nodes: HashSet<UniqueId> = self.incorporate_indirect_nodes(
+            &initial_direct,
+            &indirect_nodes,
+            &selection_group.indirect_selection,
+        )?;
+
+        match selection_group.expect_exists && direct_nodes.is_empty() {
+            true => Err(SelectionError::NoNodesForSelectionCriteria(
+                selection_group.raw.clone(),
+            )),
+            false => Ok((direct_nodes, indirect_nodes)),
         }
     }
 
@@ -424,50 +505,38 @@ impl NodeSelector {
     ///
     /// Users can opt out of inclusive EAGER mode by passing --indirect-selection cautious
     /// CLI argument or by specifying `indirect_selection: true` in a yaml selector
-    fn expand_selection(
+    pub fn expand_selection(
         &self,
         selected: &HashSet<UniqueId>,
         indirect_selection: &IndirectSelection,
     ) -> Result<(DirectNodes, IndirectNodes), SelectionError> {
         let mut direct_nodes = selected.clone();
         let mut indirect_nodes: HashSet<UniqueId> = HashSet::new();
-        let selected_and_parents: HashSet<UniqueId> = HashSet::new();
-
-        if *indirect_selection == Buildable {
-            let selected_and_parents = self
+        let selected_and_parents: HashSet<UniqueId> = match indirect_selection {
+            Buildable => self
                 .graph
-                .select_parents(selected, &None)?
-                .union(&self.graph.sources)
-                .map(|s| s.into())
-                .collect();
-            let selected_and_parents: HashSet<UniqueId> = selected
-                .union(&selected_and_parents)
-                .map(|s| s.into())
-                .collect();
-        }
+                .and_select_parents(selected, &None)?
+                .into_iter()
+                .chain(self.graph.sources.iter().cloned())
+                .collect(),
+            _ => HashSet::new(),
+        };
 
         for unique_id in self.select_successors(selected) {
-            match self
-                .graph
-                .node_map
-                .get(&unique_id)
-                .and_then(|node| IndirectSelection::can_select_indirectly(node).then_some(node))
-            {
-                None => {}
-                Some(node) => {
-                    match indirect_selection {
-                        Eager /* TODO: | OR depends_on_nodes is subset of selected */ => {
-                            direct_nodes.insert(unique_id);
-                        },
-                        Buildable /* TODO: | OR depends_on_nodes is subset of selected_and_parents */ => {
-                            direct_nodes.insert(unique_id);
-                        },
-                        Cautious => {
-                            indirect_nodes.insert(unique_id);
-                        },
-                        Empty => {},
-                    }
-                }
+            let Some(node) = self.graph.node_map.get(&unique_id) else {
+                continue;
+            };
+            if !IndirectSelection::can_select_indirectly(node) {
+                continue;
+            }
+            let is_direct = *indirect_selection == Eager
+                || self.depends_on_subset(&unique_id, selected)
+                || (*indirect_selection == Buildable
+                    && self.depends_on_subset(&unique_id, &selected_and_parents));
+            if is_direct {
+                direct_nodes.insert(unique_id);
+            } else if *indirect_selection != Empty {
+                indirect_nodes.insert(unique_id);
             }
         }
 
@@ -489,6 +558,30 @@ impl NodeSelector {
         NodeSelector::from(nodes, edges, Some(Rc::new(previous_state))).and_then(|s| Ok(s.into()))
     }
 
+    /// A selector for the graph with `patch` applied, which keeps the current
+    /// graph as its previous state. Both graphs and search indexes share
+    /// whatever the patch leaves alone.
+    pub fn patched(&self, patch: GraphPatch) -> Result<Self, SelectorCreateError> {
+        let mut graph = (*self.graph).clone();
+        graph.apply_patch(&patch)?;
+        let changed = patch
+            .upsert_nodes
+            .iter()
+            .map(|node| &node.unique_id)
+            .chain(patch.delete_nodes.iter());
+        let search_index = self.search_index.patched(&graph, changed);
+        Ok(Self {
+            graph: Rc::new(graph),
+            previous_state: Some(Rc::new(PreviousState::from_graph(self.graph.clone()))),
+            search_index,
+            cache: SelectionCache::default(),
+        })
+    }
+
+    pub fn _patch(&self, patch: GraphPatch) -> Result<Handle<Self>, SelectorCreateError> {
+        Ok(self.patched(patch)?.into())
+    }
+
     pub fn _select(&self, selector: String) -> Result<Vec<UniqueId>, SelectionError> {
         let selection_criteria = SelectionCriteria::from_single_raw_spec(selector)?;
         let selection_group = SelectionGroup::from_criteria(selection_criteria);
@@ -498,6 +591,19 @@ impl NodeSelector {
         Ok(selected_set.into_iter().collect())
     }
 
+    pub fn _select_with_syntax(
+        &self,
+        selector: String,
+        syntax: SelectorSyntax,
+    ) -> Result<Vec<UniqueId>, SelectionError> {
+        let selection_group =
+            parse_union_with_syntax(&vec![selector], false, IndirectSelection::default(), syntax)?;
+
+        let selected_set: HashSet<String> = self.get_selected(&selection_group)?;
+
+        Ok(selected_set.into_iter().collect())
+    }
+
     pub fn _select_type(
         &self,
         selector: String,
@@ -518,9 +624,119 @@ impl NodeSelector {
         selector: String,
         resource_type_filter: ResourceTypeFilter,
     ) -> Result<Vec<UniqueId>, SelectionError> {
-        todo!()
-        // let selected_set: HashSet<String> = self.select_type(selector, resource_type_filter)?;
+        let included_nodes: HashSet<UniqueId> = included_nodes.into_iter().collect();
+        if let Some(missing) = included_nodes
+            .iter()
+            .find(|id| !self.graph.node_map.contains_key(*id))
+        {
+            return Err(NodeNotInGraph(missing.to_string()));
+        }
+        let selection_criteria = SelectionCriteria::from_single_raw_spec(selector)?;
+        let selection_group = SelectionGroup::from_criteria(selection_criteria);
+
+        let selected_set: HashSet<String> =
+            self.get_selected_included(&selection_group, &included_nodes, &resource_type_filter)?;
+
+        Ok(selected_set.into_iter().collect())
+    }
+
+    pub fn _traverse(
+        &self,
+        specs: Vec<SelectionSpecRecord>,
+        target_ids: Vec<Vec<UniqueId>>,
+    ) -> Result<Vec<UniqueId>, SelectionError> {
+        let selection_group = SelectionGroup::from_records(&specs, &target_ids)?;
+
+        let selected_set: HashSet<String> = self.get_selected(&selection_group)?;
+
+        Ok(selected_set.into_iter().collect())
+    }
+
+    pub fn _select_explained(
+        &self,
+        selector: String,
+    ) -> Result<Vec<NodeExplanation>, SelectionError> {
+        let selection_group = parse_union(&vec![selector], false, IndirectSelection::default())?;
+        self.explain_selected(&selection_group)
+    }
+
+    pub fn _layout(
+        &self,
+        node_ids: Vec<UniqueId>,
+        options: LayoutOptions,
+    ) -> Result<GraphLayout, SelectionError> {
+        self.graph.layout(&node_ids.into_iter().collect(), &options)
+    }
+
+    pub fn _complete(&self, selector: String, cursor: u32) -> Vec<Completion> {
+        self.graph.complete(&selector, cursor as usize)
+    }
+
+    pub fn _search(
+        &self,
+        query: String,
+        resource_type_filter: ResourceTypeFilter,
+        limit: u32,
+    ) -> Vec<SearchMatch> {
+        self.search_index
+            .search(&self.graph, &query, &resource_type_filter, limit as usize)
+    }
+
+    pub fn _validate_access(&self) -> Vec<AccessViolation> {
+        self.graph.access_violations()
+    }
+
+    pub fn _group_members(&self, group: String) -> Result<GroupMembership, SelectionError> {
+        self.graph.group_members(&group)
+    }
+
+    pub fn _shortest_path(
+        &self,
+        upstream: UniqueId,
+        downstream: UniqueId,
+    ) -> Result<Vec<UniqueId>, SelectionError> {
+        self.graph.shortest_path(&upstream, &downstream)
+    }
+
+    pub fn _all_paths(
+        &self,
+        upstream: UniqueId,
+        downstream: UniqueId,
+        limit: u32,
+    ) -> Result<Vec<Vec<UniqueId>>, SelectionError> {
+        self.graph.all_paths(&upstream, &downstream, limit as usize)
+    }
+
+    pub fn _between(
+        &self,
+        upstream: UniqueId,
+        downstream: UniqueId,
+    ) -> Result<Vec<UniqueId>, SelectionError> {
+        self.graph.between(&upstream, &downstream)
+    }
+
+    pub fn _impact(&self, changed: Vec<UniqueId>) -> Result<ImpactReport, SelectionError> {
+        self.graph.impact(&changed.into_iter().collect())
+    }
 
-        // Ok(selected_set.into_iter().collect())
+    pub fn _impact_of_selection(&self, selector: String) -> Result<ImpactReport, SelectionError> {
+        let selection_group = parse_union(&vec![selector], false, IndirectSelection::default())?;
+        self.graph.impact(&self.get_selected(&selection_group)?)
+    }
+
+    pub fn _analyze(&self, selector: String) -> Result<Vec<SelectorWarning>, SelectionError> {
+        let selection_group = parse_union(&vec![selector], false, IndirectSelection::default())?;
+        Ok(selection_group.analyze(self.previous_state.is_some()))
+    }
+
+    pub fn _diff(&self) -> Result<GraphDiff, SelectionError> {
+        let previous_graph = self
+            .previous_state
+            .as_ref()
+            .and_then(|previous_state| previous_state.graph.as_ref())
+            .ok_or(RequiresPreviousState(
+                "No previous state to diff the graph against".to_string(),
+            ))?;
+        Ok(self.graph.diff(previous_graph))
     }
 }
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod node_selector_tests {
+    use crate::{
+        dbt_node_selector::{Edge, Node},
+        util::test::{get_resource_type, get_unit_test_node, vec_to_set},
+    };
+
+    use super::super::*;
+
+    /// source_a -> a -> b, and source_a -> c. `test_ab` tests both `a` and
+    /// `b`, `test_bc` tests both `b` and `c`, and `unit_test_b` tests `b`.
+    fn get_node_selector() -> NodeSelector {
+        let edges = vec![
+            ("source_a", vec![]),
+            ("a", vec!["source_a"]),
+            ("b", vec!["a"]),
+            ("c", vec!["source_a"]),
+            ("test_ab", vec!["a", "b"]),
+            ("test_bc", vec!["b", "c"]),
+            ("unit_test_b", vec!["b"]),
+        ];
+        let nodes = edges
+            .iter()
+            .map(|(unique_id, parents)| Node {
+                unique_id: unique_id.to_string(),
+                depends_on: parents.iter().map(|s| s.to_string()).collect(),
+                name: unique_id.to_string(),
+                package_name: "pkg".to_string(),
+                path: format!("{}.sql", unique_id),
+                original_file_path: format!("models/{}.sql", unique_id),
+                node_type: match unique_id.starts_with("unit_test") {
+                    true => get_unit_test_node(*unique_id),
+                    false => get_resource_type(*unique_id),
+                },
+                config: vec![],
+                tags: vec![],
+            })
+            .collect();
+        let edges = edges
+            .into_iter()
+            .map(|(unique_id, parents)| Edge {
+                unique_id: unique_id.to_string(),
+                parents: parents.into_iter().map(|s| s.to_string()).collect(),
+            })
+            .collect();
+        NodeSelector::from(nodes, edges, None).unwrap()
+    }
+
+    fn select(selector: &str, indirect_selection: IndirectSelection) -> HashSet<UniqueId> {
+        let selection_group =
+            parse_union(&vec![selector.to_string()], false, indirect_selection).unwrap();
+        get_node_selector().get_selected(&selection_group).unwrap()
+    }
+
+    #[test]
+    fn eager_selects_tests_with_any_parent_selected() {
+        assert_eq!(select("a", Eager), vec_to_set(vec!["a", "test_ab"]));
+        assert_eq!(
+            select("b", Eager),
+            vec_to_set(vec!["b", "test_ab", "test_bc", "unit_test_b"])
+        );
+    }
+
+    #[test]
+    fn cautious_selects_tests_with_every_parent_selected() {
+        assert_eq!(select("a", Cautious), vec_to_set(vec!["a"]));
+        assert_eq!(select("b", Cautious), vec_to_set(vec!["b", "unit_test_b"]));
+        // Each criteria only selects one parent of `test_ab`, but together they
+        // select both
+        assert_eq!(
+            select("a b", Cautious),
+            vec_to_set(vec!["a", "b", "test_ab", "unit_test_b"])
+        );
+    }
+
+    #[test]
+    fn buildable_selects_tests_whose_parents_are_built_first() {
+        // `a` is an ancestor of `b`, so it is built before `b` anyway
+        assert_eq!(
+            select("b", Buildable),
+            vec_to_set(vec!["b", "test_ab", "unit_test_b"])
+        );
+        assert_eq!(select("a", Buildable), vec_to_set(vec!["a"]));
+        assert_eq!(
+            select("b c", Buildable),
+            vec_to_set(vec!["b", "c", "test_ab", "test_bc", "unit_test_b"])
+        );
+    }
+
+    #[test]
+    fn empty_selects_neighbors_but_no_tests() {
+        assert_eq!(select("b", Empty), vec_to_set(vec!["b"]));
+        assert_eq!(select("+b", Empty), vec_to_set(vec!["source_a", "a", "b"]));
+    }
+
+    #[test]
+    fn unit_tests_can_be_selected_indirectly() {
+        let node_selector = get_node_selector();
+        let unit_test = node_selector.graph.node_map.get("unit_test_b").unwrap();
+        let model = node_selector.graph.node_map.get("b").unwrap();
+
+        assert!(IndirectSelection::can_select_indirectly(unit_test));
+        assert!(!IndirectSelection::can_select_indirectly(model));
+    }
+
+    #[test]
+    fn between_selects_nodes_on_a_path() {
+        let select = |selector: &str| {
+            get_node_selector()
+                ._select_with_syntax(selector.to_string(), SelectorSyntax::Extended)
+                .map(|selected| selected.into_iter().collect::<HashSet<_>>())
+        };
+
+        assert_eq!(
+            select("a..b").unwrap(),
+            vec_to_set(vec!["a", "b", "test_ab", "test_bc", "unit_test_b"])
+        );
+        assert_eq!(select("a..a").unwrap(), vec_to_set(vec!["a", "test_ab"]));
+        // Like `c+,+b`, a test of both sides is still selected indirectly
+        // even when there is no path between them
+        assert_eq!(select("c..b").unwrap(), vec_to_set(vec!["test_bc"]));
+        assert_eq!(select("b..c").unwrap(), vec_to_set(vec!["test_bc"]));
+        assert_eq!(select("b..source_a").unwrap(), HashSet::new());
+        assert!(get_node_selector()
+            ._select_with_syntax("a..b".to_string(), SelectorSyntax::Dbt)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -10,6 +10,7 @@ use std::{collections::VecDeque, fmt::Display, num::ParseIntError, str::FromStr}
 use regex::{Captures, Match, Regex};
 
 use super::MethodName;
+use crate::dbt_node_selector::{SelectorDiagnostic, SelectorSyntax};
 
 lazy_static! {
     static ref RAW_SELECTOR_PATTERN: Regex = {
@@ -25,6 +26,9 @@ lazy_static! {
     };
 }
 
+/// Separates the two sides of the extended syntax's between operator
+pub const BETWEEN_DELIMITER: &str = "..";
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum IndirectSelection {
     Eager,
@@ -67,10 +71,13 @@ impl IndirectSelection {
 
     /// If a node is not selected itself, but its parent(s) are, it may qualify
     /// for indirect selection.
-    /// Today, only Test nodes can be indirectly selected. In the future,
-    /// other node types or invocation flags might qualify.
+    /// Today, only Test and UnitTest nodes can be indirectly selected. In the
+    /// future, other node types or invocation flags might qualify.
     pub fn can_select_indirectly(node: &WrapperNode) -> bool {
-        node.resource_type().key().eq(&NodeTypeKey::Test)
+        matches!(
+            node.resource_type().key(),
+            NodeTypeKey::Test | NodeTypeKey::UnitTest
+        )
     }
 }
 
@@ -136,13 +143,16 @@ impl ParsedMethod {
 
         match (method_match, parsed_method) {
             (None, _) => Ok(Self::default_method(value)),
+            (Some(method_match), Err(InvalidMethodError(method_name))) => Err(InvalidSyntax(
+                SelectorDiagnostic::invalid_method(&method_name, method_match.start()),
+            )),
             (_, Err(e)) => Err(e),
             (_, Ok(parsed_method)) => Ok(parsed_method),
         }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SelectionCriteria {
     pub raw: String,
     pub method: MethodName,
@@ -157,7 +167,8 @@ pub struct SelectionCriteria {
     pub indirect_selection: IndirectSelection,
 }
 
-use crate::args::InputArgs;
+#[cfg(feature = "cli")]
+use crate::args::{parse_difference, InputArgs, SelectorConfig};
 use crate::dbt_node_selector::UniqueId;
 use crate::graph::node::{NodeTypeKey, WrapperNode, WrapperNodeExt};
 use crate::SelectionError;
@@ -217,9 +228,15 @@ impl Display for SelectionError {
             RequiresPreviousState(warning) => {
                 write!(f, "{}", warning)
             }
+            InvalidSyntax(diagnostic) => {
+                write!(f, "{}", diagnostic.message)
+            }
             InvalidSelector(warning) => {
                 write!(f, "{}", warning)
             }
+            UnsupportedMethod(method) => {
+                write!(f, "The '{}' selector method is not supported yet", method)
+            }
         }
     }
 }
@@ -232,18 +249,21 @@ impl SelectionCriteria {
         }
     }
 
-    fn get_num_from_match(regex_match: Option<Match>) -> Result<Option<usize>, ParseIntError> {
+    fn get_num_from_match(regex_match: Option<Match>) -> Result<Option<usize>, SelectionError> {
         match regex_match {
-            Some(r) => {
-                let r = r.as_str();
-                match r {
-                    "" => Ok(None),
-                    _ => {
-                        let num = r.parse::<usize>()?;
-                        Ok(Some(num))
-                    }
-                }
-            }
+            Some(r) => match r.as_str() {
+                "" => Ok(None),
+                digits => digits.parse::<usize>().map(Some).map_err(|_| {
+                    InvalidSyntax(
+                        SelectorDiagnostic::new(
+                            format!("Depth '{}' is too large", digits),
+                            r.start(),
+                            r.end(),
+                        )
+                        .with_expected(vec!["<integer>".to_string()]),
+                    )
+                }),
+            },
             None => Ok(None),
         }
     }
@@ -263,9 +283,9 @@ impl SelectionCriteria {
         let children_depth = Self::get_num_from_match(captures.name("children_depth"));
 
         match (children && childrens_parents, parents_depth, children_depth) {
-            (true, _, _) => Err(IncompatiblePrefixAndSuffixError(raw.to_string())),
-            (_, Err(err), _) => Err(ParentsDepthParseIntError(raw.to_string())),
-            (_, _, Err(err)) => Err(ChildrensDepthParseIntError(raw.to_string())),
+            (true, _, _) => Err(Self::incompatible_prefix_and_suffix(raw, captures)),
+            (_, Err(err), _) => Err(err),
+            (_, _, Err(err)) => Err(err),
             (false, Ok(parents_depth), Ok(children_depth)) => Ok(Self {
                 raw: raw.to_owned(),
                 method: parsed_method.method_name,
@@ -281,6 +301,32 @@ impl SelectionCriteria {
         }
     }
 
+    fn incompatible_prefix_and_suffix(raw: &str, captures: &Captures) -> SelectionError {
+        let children = captures.name("children").unwrap();
+        InvalidSyntax(
+            SelectorDiagnostic::new(
+                "'@' prefix and '+' suffix are incompatible",
+                children.start(),
+                children.end(),
+            )
+            .with_suggestions(vec![
+                raw[1..].to_string(),
+                raw[..children.start()].to_string(),
+            ]),
+        )
+    }
+
+    fn unmatched_spec(raw: &str) -> SelectionError {
+        // The pattern matches anything on a single line
+        match raw.find('\n') {
+            Some(start) => InvalidSyntax(
+                SelectorDiagnostic::new("Selectors cannot contain line breaks", start, start + 1)
+                    .with_suggestions(vec![raw.replace('\n', " ")]),
+            ),
+            None => FailedRegexMatchError(raw.to_string()),
+        }
+    }
+
     pub fn from_single_raw_spec(raw: impl Into<String>) -> Result<Self, SelectionError> {
         Self::from_single_spec(raw, &IndirectSelection::default())
     }
@@ -294,7 +340,7 @@ impl SelectionCriteria {
 
         match result {
             Some(captures) => Self::from_captures(&raw, &captures, indirect_selection),
-            None => Err(FailedRegexMatchError(raw.to_string())),
+            None => Err(Self::unmatched_spec(&raw)),
         }
     }
 
@@ -354,10 +400,9 @@ impl SelectionCriteria {
         match value {
             None => Err(MissingValueError(raw.to_string())),
             Some(value) => {
-                // WARN! This is a dictionary in the python impl, we expect a string instead.
-                let method_args = index_map.get("method_args");
+                let method = index_map.get("method");
                 let method =
-                    ParsedMethod::from_value_and_method(value.to_string(), method_args.cloned())?;
+                    ParsedMethod::from_value_and_method(value.to_string(), method.cloned())?;
 
                 let default_indirect_selection = default_indirect_selection.unwrap_or_default();
                 let indirect_selection = index_map.get("indirect_selection");
@@ -391,20 +436,29 @@ impl SelectionCriteria {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum SetOperation {
     Intersection,
     Difference,
     Union,
 }
 
-#[derive(Clone, Debug)]
+/// A selection criteria whose method has already been resolved to a set of
+/// target nodes, i.e. by the Discovery API. Only the graph traversal is left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraversalSpec {
+    pub criteria: SelectionCriteria,
+    pub target_ids: HashSet<UniqueId>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum SelectionSpec {
     SelectionCriteria(SelectionCriteria),
+    Traversal(TraversalSpec),
     SetOperation(SetOperation),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SelectionGroup {
     pub components: Vec<SelectionGroup>,
     pub indirect_selection: IndirectSelection,
@@ -424,27 +478,120 @@ impl SelectionGroup {
         }
     }
 
-    pub fn get_selection_group(self, args: &InputArgs) -> SelectionGroup {
-        // TODO: We don't allow a config or default_selector name yet
-
-        let indirect_selection = IndirectSelection::default();
+    pub fn from_traversal(criteria: SelectionCriteria, target_ids: HashSet<UniqueId>) -> Self {
+        Self {
+            components: vec![],
+            indirect_selection: criteria.indirect_selection,
+            expect_exists: false,
+            raw: criteria.raw.clone(),
+            spec: SelectionSpec::Traversal(TraversalSpec {
+                criteria,
+                target_ids,
+            }),
+        }
+    }
 
-        match (&args.selector, &args.select, &args.exclude) {
-            (Some(selector), _, _) => {
-                // Use pre-defined selector (--selector)
-                todo!()
-            }
-            (_, None, None) => {
-                // Use pre-defined selector (--selector) with default: true
-                unimplemented!()
+    /// Parses a single spec, i.e. one side of an intersection. With the
+    /// extended syntax, `a..b` is the same as `a+,+b`: the descendants of `a`
+    /// which are also ancestors of `b`.
+    pub fn from_spec(
+        raw: &str,
+        indirect_selection: &IndirectSelection,
+        syntax: SelectorSyntax,
+    ) -> Result<Self, SelectionError> {
+        let between = match syntax {
+            SelectorSyntax::Dbt => None,
+            SelectorSyntax::Extended => Self::find_between(raw)
+                .map(|start| (&raw[..start], &raw[start + BETWEEN_DELIMITER.len()..])),
+        };
+        let Some((upstream, downstream)) = between else {
+            let criteria = SelectionCriteria::from_single_spec(raw, indirect_selection)?;
+            return Ok(Self::from_criteria(criteria));
+        };
+
+        let downstream_offset = upstream.len() + BETWEEN_DELIMITER.len();
+        let mut sides = vec![];
+        for (side, offset) in [(upstream, 0), (downstream, downstream_offset)] {
+            let criteria = SelectionCriteria::from_single_spec(side, indirect_selection)
+                .map_err(|err| err.offset(offset))?;
+            if criteria.value.is_empty() {
+                return Err(InvalidSyntax(
+                    SelectorDiagnostic::new(
+                        format!("'{}' needs a selector on both sides", BETWEEN_DELIMITER),
+                        upstream.len(),
+                        downstream_offset,
+                    )
+                    .with_expected(vec!["<value>".to_string()]),
+                ));
             }
-            (_, selection_arg, exclusion_arg) => {
-                // Use --select and --exclude args
-                todo!()
+            if criteria.childrens_parents || criteria.parents || criteria.children {
+                return Err(InvalidSyntax(SelectorDiagnostic::new(
+                    format!(
+                        "Graph operators cannot be combined with '{}'",
+                        BETWEEN_DELIMITER
+                    ),
+                    offset,
+                    offset + side.len(),
+                )));
             }
+            sides.push(criteria);
         }
+        let mut downstream = sides.pop().unwrap();
+        let mut upstream = sides.pop().unwrap();
+        upstream.children = true;
+        downstream.parents = true;
+
+        Ok(Self::intersection(
+            raw.to_string(),
+            vec![
+                Self::from_criteria(upstream),
+                Self::from_criteria(downstream),
+            ],
+            *indirect_selection,
+            false,
+        ))
+    }
 
-        // let default_selector_name = self.conf
+    /// The start of the between operator in `raw`. A `..` path segment, as in
+    /// `path:../models`, is part of the method value instead.
+    fn find_between(raw: &str) -> Option<usize> {
+        raw.match_indices(BETWEEN_DELIMITER)
+            .map(|(start, _)| start)
+            .find(|&start| {
+                let before = raw[..start].chars().next_back();
+                let after = raw[start + BETWEEN_DELIMITER.len()..].chars().next();
+                !matches!(before, Some('/' | '\\' | ':')) && !matches!(after, Some('/' | '\\'))
+            })
+    }
+
+    /// The selection of the CLI arguments: the `--selector` named selector, the
+    /// default selector when there's no `--select` or `--exclude`, or else
+    /// `--select` minus `--exclude`
+    #[cfg(feature = "cli")]
+    pub fn get_selection_group(
+        args: &InputArgs,
+        selectors: &IndexMap<String, SelectorConfig>,
+    ) -> Result<SelectionGroup, SelectionError> {
+        let default_selector = selectors.values().find(|selector| selector.default);
+        match (
+            &args.selector,
+            &args.select,
+            &args.exclude,
+            default_selector,
+        ) {
+            (Some(name), _, _, _) => match selectors.get(name) {
+                Some(selector) => Ok(selector.definition.clone()),
+                None => Err(InvalidSelector(format!(
+                    "Could not find selector named '{}', expected one of: [{}]",
+                    name,
+                    selectors.keys().cloned().collect::<Vec<_>>().join(", ")
+                ))),
+            },
+            (None, None, None, Some(default_selector)) => Ok(default_selector.definition.clone()),
+            (None, select, exclude, _) => {
+                parse_difference(select, exclude, args.indirect_selection, args.syntax)
+            }
+        }
     }
 
     pub fn intersection(
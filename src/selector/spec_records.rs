@@ -0,0 +1,182 @@
+#[cfg(test)]
+#[path = "spec_records_tests.rs"]
+mod spec_records_tests;
+
+/// Conversions between the spec tree and the flat records exposed over WAI,
+/// following the `getDagTraversalSpec` design in RecommendedArchitecture.md.
+use std::collections::HashSet;
+
+use crate::dbt_node_selector::{
+    IndirectSelectionMode, SelectionCriteriaRecord, SelectionError, SelectionSpecKind,
+    SelectionSpecRecord, SetOperationKind, UniqueId,
+};
+use crate::SelectionError::*;
+
+use super::{
+    spec::{IndirectSelection, SelectionCriteria, SelectionGroup, SelectionSpec, SetOperation},
+    MethodName,
+};
+
+impl From<IndirectSelectionMode> for IndirectSelection {
+    fn from(mode: IndirectSelectionMode) -> Self {
+        match mode {
+            IndirectSelectionMode::Eager => Self::Eager,
+            IndirectSelectionMode::Cautious => Self::Cautious,
+            IndirectSelectionMode::Buildable => Self::Buildable,
+            IndirectSelectionMode::Empty => Self::Empty,
+        }
+    }
+}
+
+impl From<SetOperationKind> for SetOperation {
+    fn from(kind: SetOperationKind) -> Self {
+        match kind {
+            SetOperationKind::Intersection => Self::Intersection,
+            SetOperationKind::Difference => Self::Difference,
+            SetOperationKind::Union => Self::Union,
+        }
+    }
+}
+
+impl From<&SelectionCriteria> for SelectionCriteriaRecord {
+    fn from(criteria: &SelectionCriteria) -> Self {
+        Self {
+            raw: criteria.raw.clone(),
+            method: criteria.method.key().to_string(),
+            method_arguments: criteria.method_arguments.clone(),
+            value: criteria.value.clone(),
+            childrens_parents: criteria.childrens_parents,
+            parents: criteria.parents,
+            parents_depth: criteria.parents_depth.map(|depth| depth as u32),
+            children: criteria.children,
+            children_depth: criteria.children_depth.map(|depth| depth as u32),
+            indirect_selection: (&criteria.indirect_selection).into(),
+        }
+    }
+}
+
+impl SelectionCriteria {
+    pub fn from_record(record: &SelectionCriteriaRecord) -> Result<Self, SelectionError> {
+        let method = MethodName::from_string(&record.method)
+            .ok_or_else(|| InvalidMethodError(record.method.clone()))?;
+        Ok(Self {
+            raw: record.raw.clone(),
+            method,
+            method_arguments: record.method_arguments.clone(),
+            value: record.value.clone(),
+            childrens_parents: record.childrens_parents,
+            parents: record.parents,
+            parents_depth: record.parents_depth.map(|depth| depth as usize),
+            children: record.children,
+            children_depth: record.children_depth.map(|depth| depth as usize),
+            indirect_selection: record.indirect_selection.into(),
+        })
+    }
+}
+
+impl SelectionGroup {
+    fn push_records(&self, records: &mut Vec<SelectionSpecRecord>) -> usize {
+        let index = records.len();
+        let kind = match &self.spec {
+            SelectionSpec::SelectionCriteria(criteria) => {
+                SelectionSpecKind::Criteria(criteria.into())
+            }
+            SelectionSpec::Traversal(traversal) => {
+                SelectionSpecKind::Criteria((&traversal.criteria).into())
+            }
+            SelectionSpec::SetOperation(operation) => {
+                SelectionSpecKind::SetOperation(operation.into())
+            }
+        };
+        records.push(SelectionSpecRecord {
+            kind,
+            components: vec![],
+            raw: self.raw.clone(),
+            indirect_selection: (&self.indirect_selection).into(),
+            expect_exists: self.expect_exists,
+        });
+
+        let components: Vec<u32> = self
+            .components
+            .iter()
+            .map(|component| component.push_records(records) as u32)
+            .collect();
+        records[index].components = components;
+        index
+    }
+
+    /// Flattens the spec tree in pre-order, so the root is the first record.
+    pub fn to_records(&self) -> Vec<SelectionSpecRecord> {
+        let mut records = vec![];
+        self.push_records(&mut records);
+        records
+    }
+
+    fn from_record_at(
+        records: &[SelectionSpecRecord],
+        target_ids: &[Vec<UniqueId>],
+        index: usize,
+    ) -> Result<Self, SelectionError> {
+        let record = records
+            .get(index)
+            .ok_or_else(|| InvalidSelector(format!("No selection spec at index {}", index)))?;
+
+        let spec = match &record.kind {
+            SelectionSpecKind::Criteria(criteria) => {
+                let targets: HashSet<UniqueId> = target_ids[index].iter().cloned().collect();
+                let group = SelectionGroup::from_traversal(
+                    SelectionCriteria::from_record(criteria)?,
+                    targets,
+                );
+                return Ok(Self {
+                    raw: record.raw.clone(),
+                    indirect_selection: record.indirect_selection.into(),
+                    expect_exists: record.expect_exists,
+                    ..group
+                });
+            }
+            SelectionSpecKind::SetOperation(kind) => SelectionSpec::SetOperation((*kind).into()),
+        };
+
+        let components = record
+            .components
+            .iter()
+            .map(|component| {
+                let component = *component as usize;
+                // Pre-order guarantees components come after their parent, which
+                // also rules out cycles in malformed input.
+                match component > index {
+                    true => Self::from_record_at(records, target_ids, component),
+                    false => Err(InvalidSelector(format!(
+                        "Selection spec {} can not be a component of selection spec {}",
+                        component, index
+                    ))),
+                }
+            })
+            .collect::<Result<Vec<_>, SelectionError>>()?;
+
+        Ok(Self {
+            components,
+            indirect_selection: record.indirect_selection.into(),
+            expect_exists: record.expect_exists,
+            spec,
+            raw: record.raw.clone(),
+        })
+    }
+
+    /// Rebuilds a spec tree from `to_records`, where each criteria is replaced
+    /// by a traversal from the target IDs at the same index.
+    pub fn from_records(
+        records: &[SelectionSpecRecord],
+        target_ids: &[Vec<UniqueId>],
+    ) -> Result<Self, SelectionError> {
+        if target_ids.len() != records.len() {
+            return Err(InvalidSelector(format!(
+                "Expected target IDs for each of the {} selection specs, got {}",
+                records.len(),
+                target_ids.len()
+            )));
+        }
+        Self::from_record_at(records, target_ids, 0)
+    }
+}
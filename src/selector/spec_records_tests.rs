@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod spec_records_tests {
+    use crate::{
+        args::parse_union,
+        assert_err, assert_ok,
+        dbt_node_selector::{IndirectSelectionMode, SelectionSpecKind, SetOperationKind},
+    };
+
+    use super::super::*;
+
+    fn parse(selector: &str) -> SelectionGroup {
+        assert_ok!(parse_union(
+            &vec![selector.to_string()],
+            false,
+            IndirectSelection::default()
+        ))
+    }
+
+    #[test]
+    fn to_records_flattens_in_pre_order() {
+        let records = parse("2+my_model+3 tag:nightly,config.materialized:table").to_records();
+
+        assert_eq!(records.len(), 6);
+        assert!(matches!(
+            records[0].kind,
+            SelectionSpecKind::SetOperation(SetOperationKind::Union)
+        ));
+        assert_eq!(records[0].components, vec![1, 3]);
+        assert!(matches!(
+            records[1].kind,
+            SelectionSpecKind::SetOperation(SetOperationKind::Intersection)
+        ));
+        assert_eq!(records[1].components, vec![2]);
+        assert_eq!(records[3].components, vec![4, 5]);
+        assert!(records[2].components.is_empty());
+        assert!(records[4].components.is_empty());
+        assert!(records[5].components.is_empty());
+    }
+
+    #[test]
+    fn to_records_keeps_criteria() {
+        let records = parse("2+my_model+3 config.materialized:table").to_records();
+
+        let SelectionSpecKind::Criteria(my_model) = &records[2].kind else {
+            panic!("Expected criteria")
+        };
+        assert_eq!(my_model.raw, "2+my_model+3");
+        assert_eq!(my_model.method, "fqn");
+        assert_eq!(my_model.value, "my_model");
+        assert!(my_model.parents);
+        assert_eq!(my_model.parents_depth, Some(2));
+        assert!(my_model.children);
+        assert_eq!(my_model.children_depth, Some(3));
+        assert!(!my_model.childrens_parents);
+        assert_eq!(my_model.indirect_selection, IndirectSelectionMode::Eager);
+
+        let SelectionSpecKind::Criteria(config) = &records[4].kind else {
+            panic!("Expected criteria")
+        };
+        assert_eq!(config.method, "config");
+        assert_eq!(config.method_arguments, vec!["materialized".to_string()]);
+        assert_eq!(config.value, "table");
+        assert_eq!(config.parents_depth, None);
+    }
+
+    #[test]
+    fn from_records_round_trip() {
+        let records = parse("@a b+,tag:c").to_records();
+        let target_ids = vec![vec![]; records.len()];
+
+        let selection_group = assert_ok!(SelectionGroup::from_records(&records, &target_ids));
+
+        assert_eq!(
+            format!("{:?}", selection_group.to_records()),
+            format!("{:?}", records)
+        );
+    }
+
+    #[test]
+    fn from_records_uses_target_ids() {
+        let records = parse("a+").to_records();
+        let target_ids = vec![vec![], vec![], vec!["model.pkg.a".to_string()]];
+
+        let selection_group = assert_ok!(SelectionGroup::from_records(&records, &target_ids));
+
+        let criteria = &selection_group.components[0].components[0];
+        let SelectionSpec::Traversal(traversal) = &criteria.spec else {
+            panic!("Expected traversal")
+        };
+        assert!(traversal.target_ids.contains("model.pkg.a"));
+        assert!(traversal.criteria.children);
+    }
+
+    #[test]
+    fn from_records_rejects_backwards_components() {
+        let mut records = parse("a b").to_records();
+        records[1].components = vec![0];
+        let target_ids = vec![vec![]; records.len()];
+
+        let result = SelectionGroup::from_records(&records, &target_ids);
+
+        assert_err!(result, Err(SelectionError::InvalidSelector(_)));
+    }
+
+    #[test]
+    fn from_records_rejects_unknown_methods() {
+        let mut records = parse("a").to_records();
+        if let SelectionSpecKind::Criteria(criteria) = &mut records[2].kind {
+            criteria.method = "not_a_method".to_string();
+        }
+        let target_ids = vec![vec![]; records.len()];
+
+        let result = SelectionGroup::from_records(&records, &target_ids);
+
+        assert_err!(result, Err(SelectionError::InvalidMethodError(_)));
+    }
+
+    #[test]
+    fn from_records_rejects_missing_target_ids() {
+        let records = parse("a+").to_records();
+        let target_ids = vec![vec![], vec![]];
+
+        let result = SelectionGroup::from_records(&records, &target_ids);
+
+        assert_err!(result, Err(SelectionError::InvalidSelector(_)));
+    }
+
+    #[test]
+    fn from_records_empty() {
+        let result = SelectionGroup::from_records(&[], &[]);
+
+        assert_err!(result, Err(SelectionError::InvalidSelector(_)));
+    }
+}
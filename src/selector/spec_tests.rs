@@ -4,6 +4,8 @@
 mod select_nodes_tests {
     use std::path::Path;
 
+    use indexmap::IndexMap;
+
     use crate::util::test::vec_to_set;
 
     use super::super::*;
@@ -38,7 +40,7 @@ mod select_nodes_tests {
         let expected_method_arguments: Vec<String> = vec![];
 
         assert_eq!(result.raw, raw);
-        assert_eq!(result.method.key(), Path.key());
+        assert_eq!(result.method.key(), MethodName::Path.key());
         assert_eq!(result.method_arguments, expected_method_arguments);
         assert_eq!(result.value, raw);
         assert_eq!(result.childrens_parents, false);
@@ -59,7 +61,7 @@ mod select_nodes_tests {
         let expected_method_arguments: Vec<String> = vec![];
 
         assert_eq!(result.raw, raw);
-        assert_eq!(result.method.key(), Path.key());
+        assert_eq!(result.method.key(), MethodName::Path.key());
         assert_eq!(result.method_arguments, expected_method_arguments);
         assert_eq!(result.value, expected_value);
         assert_eq!(result.childrens_parents, true);
@@ -159,6 +161,26 @@ mod select_nodes_tests {
         assert!(invalid5.is_err());
     }
 
+    #[test]
+    fn definition_reads_the_method() {
+        let definition = IndexMap::from([
+            ("method".to_string(), "config.materialized".to_string()),
+            ("value".to_string(), "view".to_string()),
+        ]);
+        let result =
+            SelectionCriteria::selection_criteria_from_indexmap("raw", &definition, None).unwrap();
+
+        assert_eq!(result.method.key(), Config.key());
+        assert_eq!(result.method_arguments, vec!["materialized".to_string()]);
+        assert_eq!(result.value, "view");
+
+        // Without a method, the value is inferred like a raw selector's is
+        let definition = IndexMap::from([("value".to_string(), "models/a.sql".to_string())]);
+        let result =
+            SelectionCriteria::selection_criteria_from_indexmap("raw", &definition, None).unwrap();
+        assert_eq!(result.method.key(), MethodName::Path.key());
+    }
+
     #[test]
     fn intersection_simple() {
         let components = vec![
@@ -384,4 +406,87 @@ mod select_nodes_tests {
         let expected = vec_to_set(vec!["a", "b"]);
         assert_eq!(expected, combined)
     }
+
+    #[test]
+    fn between_is_an_intersection_of_children_and_parents() {
+        let group = SelectionGroup::from_spec(
+            "source:a..exposure:b",
+            &IndirectSelection::Cautious,
+            SelectorSyntax::Extended,
+        )
+        .unwrap();
+
+        assert_eq!(group.raw, "source:a..exposure:b");
+        assert!(matches!(
+            group.spec,
+            SelectionSpec::SetOperation(SetOperation::Intersection)
+        ));
+        let criteria: Vec<&SelectionCriteria> = group
+            .components
+            .iter()
+            .map(|component| match &component.spec {
+                SelectionSpec::SelectionCriteria(criteria) => criteria,
+                spec => panic!("Expected a selection criteria, got {:?}", spec),
+            })
+            .collect();
+        let [upstream, downstream] = criteria[..] else {
+            panic!("Expected two components")
+        };
+        assert_eq!(upstream.method.key(), Source.key());
+        assert_eq!(upstream.value, "a");
+        assert!(upstream.children && !upstream.parents);
+        assert_eq!(downstream.method.key(), Exposure.key());
+        assert_eq!(downstream.value, "b");
+        assert!(downstream.parents && !downstream.children);
+        assert_eq!(upstream.indirect_selection, IndirectSelection::Cautious);
+    }
+
+    #[test]
+    fn between_needs_the_extended_syntax() {
+        let group =
+            SelectionGroup::from_spec("a..b", &IndirectSelection::Eager, SelectorSyntax::Dbt)
+                .unwrap();
+        let SelectionSpec::SelectionCriteria(criteria) = group.spec else {
+            panic!("Expected a selection criteria")
+        };
+        assert_eq!(criteria.value, "a..b");
+    }
+
+    #[test]
+    fn between_leaves_path_segments_in_the_value() {
+        for raw in [
+            "path:../x",
+            "path:models/../staging",
+            "path:..",
+            "path:..\\x",
+        ] {
+            let group =
+                SelectionGroup::from_spec(raw, &IndirectSelection::Eager, SelectorSyntax::Extended)
+                    .unwrap();
+            let SelectionSpec::SelectionCriteria(criteria) = group.spec else {
+                panic!("Expected a selection criteria for '{}'", raw)
+            };
+            assert_eq!(criteria.method.key(), Path.key());
+            assert_eq!(criteria.value, raw["path:".len()..]);
+        }
+    }
+
+    #[test]
+    fn between_rejects_graph_operators_and_empty_sides() {
+        let error = |raw: &str| match SelectionGroup::from_spec(
+            raw,
+            &IndirectSelection::Eager,
+            SelectorSyntax::Extended,
+        ) {
+            Err(SelectionError::InvalidSyntax(diagnostic)) => (diagnostic.start, diagnostic.end),
+            result => panic!("Expected a syntax error for '{}', got {:?}", raw, result),
+        };
+
+        assert_eq!(error("a+..b"), (0, 2));
+        assert_eq!(error("a..2+b"), (3, 6));
+        assert_eq!(error("@a..b"), (0, 2));
+        assert_eq!(error("a.."), (1, 3));
+        assert_eq!(error("..b"), (0, 2));
+        assert_eq!(error("bad:a..b"), (0, 3));
+    }
 }
@@ -1,14 +1,30 @@
 /// Test utilities
 
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
-use crate::{dbt_node_selector::*, graph::node::NodeTypeKey};
+use crate::{
+    dbt_node_selector::*,
+    graph::{
+        node::NodeTypeKey,
+        node::WrapperNode,
+        parsed_graph::{NodeSet, ParsedGraph},
+    },
+    selector::node_selector::{NodeSelector, PreviousState},
+};
 
 /// Often times, we have a Vec and we really don't care about the order
 pub fn vec_to_set(vec: Vec<impl Into<String>>) -> HashSet<String> {
     vec.into_iter().map(|s| s.into()).collect()
 }
 
+/// `vec_to_set`, for comparing with the sets of a graph
+pub fn vec_to_node_set(vec: Vec<impl Into<String>>) -> NodeSet {
+    vec.into_iter().map(|s| s.into()).collect()
+}
+
 #[macro_export]
 macro_rules! assert_ok {
     ($expression:expr) => {
@@ -47,6 +63,8 @@ pub fn get_model_node(unique_id: impl Into<String>) -> NodeType {
         depends_on: vec!["DEP_A".to_string()],
         raw_code: "RAW_MODEL".to_string(),
         access: AccessType::Private,
+        version: None,
+        latest_version: None,
     })
 }
 
@@ -62,6 +80,7 @@ pub fn get_test_node(unique_id: impl Into<String>) -> NodeType {
     NodeType::Test(TestNode {
         fqn: vec!["TEST".to_owned(), "FQN".to_owned(), unique_id.into()],
         raw_code: "RAW_TEST".to_owned(),
+        test_metadata_name: Some("not_null".to_owned()),
     })
 }
 
@@ -139,6 +158,32 @@ pub fn get_metric_node(unique_id: impl Into<String>) -> NodeType {
 pub fn get_group_node(unique_id: impl Into<String>) -> NodeType {
     NodeType::Group(GroupNode {
         fqn: vec!["GROUP".to_owned(), "FQN".to_owned(), unique_id.into()],
+        owner: GroupOwner {
+            name: Some("OWNER".to_owned()),
+            email: None,
+        },
+    })
+}
+
+pub fn get_semantic_model_node(unique_id: impl Into<String>) -> NodeType {
+    NodeType::SemanticModel(SemanticModelNode {
+        fqn: vec!["SEMANTIC_MODEL".to_owned(), "FQN".to_owned(), unique_id.into()],
+        depends_on: vec!["DEP_A".to_owned()],
+    })
+}
+
+pub fn get_saved_query_node(unique_id: impl Into<String>) -> NodeType {
+    NodeType::SavedQuery(SavedQueryNode {
+        fqn: vec!["SAVED_QUERY".to_owned(), "FQN".to_owned(), unique_id.into()],
+        depends_on: vec!["DEP_A".to_owned()],
+    })
+}
+
+pub fn get_unit_test_node(unique_id: impl Into<String>) -> NodeType {
+    NodeType::UnitTest(UnitTestNode {
+        fqn: vec!["UNIT_TEST".to_owned(), "FQN".to_owned(), unique_id.into()],
+        depends_on: vec!["DEP_A".to_owned()],
+        model: "DEP_A".to_owned(),
     })
 }
 
@@ -163,5 +208,166 @@ pub fn get_resource_type(unique_id: impl Into<String>) -> NodeType {
         NodeTypeKey::Exposure => get_exposure_node(unique_id),
         NodeTypeKey::Metric => get_metric_node(unique_id),
         NodeTypeKey::Group => get_group_node(unique_id),
+        NodeTypeKey::SemanticModel => get_semantic_model_node(unique_id),
+        NodeTypeKey::SavedQuery => get_saved_query_node(unique_id),
+        NodeTypeKey::UnitTest => get_unit_test_node(unique_id),
+    }
+}
+
+/// A small deterministic PRNG (SplitMix64), so randomized tests can be
+/// reproduced from the seed in their failure message
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Picks each item with a `1 / one_in` chance
+    pub fn subset<'a>(
+        &mut self,
+        items: impl IntoIterator<Item = &'a UniqueId>,
+        one_in: usize,
+    ) -> HashSet<UniqueId> {
+        items
+            .into_iter()
+            .filter(|_| self.below(one_in) == 0)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Resource types of random graph nodes, picked by index like
+/// `graph_compression_testing/generate_json.py` does
+const RANDOM_RESOURCE_TYPES: &[&str] = &[
+    "model", "source", "seed", "snapshot", "test", "exposure", "metric",
+];
+
+/// A node with placeholder fields and a resource type picked by
+/// `get_resource_type`, for tests to change the fields they care about
+pub fn get_node(unique_id: impl Into<String>) -> Node {
+    let unique_id = unique_id.into();
+    Node {
+        name: unique_id.clone(),
+        node_type: get_resource_type(unique_id.clone()),
+        unique_id,
+        package_name: "PKG".to_string(),
+        path: "PATH".to_string(),
+        original_file_path: "OPATH".to_string(),
+        depends_on: vec![],
+        config: vec![],
+        tags: vec![],
+    }
+}
+
+pub fn to_strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+pub fn to_config(items: &[(&str, &str)]) -> Vec<(String, String)> {
+    items
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Builds test graphs node by node, i.e.
+/// `GraphBuilder::new().node("a", &[]).node("b", &["a"]).build()`
+#[derive(Default)]
+pub struct GraphBuilder {
+    nodes: Vec<Node>,
+    parents_map: HashMap<UniqueId, HashSet<UniqueId>>,
+}
+
+impl GraphBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node from `get_node` with the given parents
+    pub fn node(self, unique_id: &str, parents: &[&str]) -> Self {
+        self.node_with(unique_id, parents, |_| {})
+    }
+
+    /// Adds a node from `get_node`, changed by `edit`, with the given parents
+    pub fn node_with(
+        self,
+        unique_id: &str,
+        parents: &[&str],
+        edit: impl FnOnce(&mut Node),
+    ) -> Self {
+        let mut node = get_node(unique_id);
+        edit(&mut node);
+        self.add(node, parents)
+    }
+
+    /// Adds a node as it is, with the given parents
+    pub fn add(mut self, node: Node, parents: &[&str]) -> Self {
+        self.parents_map
+            .insert(node.unique_id.clone(), vec_to_set(parents.to_vec()));
+        self.nodes.push(node);
+        self
+    }
+
+    pub fn build(self) -> ParsedGraph {
+        let node_map = self
+            .nodes
+            .iter()
+            .map(|node| (node.unique_id.clone(), WrapperNode::from(node).unwrap()))
+            .collect();
+        ParsedGraph::from_parents(node_map, self.parents_map)
+    }
+
+    /// Builds a `NodeSelector` over the graph, with `previous` as its previous
+    /// state
+    pub fn build_selector(self, previous: Option<ParsedGraph>) -> NodeSelector {
+        let edges = self
+            .parents_map
+            .into_iter()
+            .map(|(unique_id, parents)| Edge {
+                unique_id,
+                parents: parents.into_iter().collect(),
+            })
+            .collect();
+        let previous_state =
+            previous.map(|graph| Rc::new(PreviousState::from_graph(Rc::new(graph))));
+        NodeSelector::from(self.nodes, edges, previous_state).unwrap()
+    }
+}
+
+/// Builds a random DAG of `num_nodes` nodes. Node `i` only gets parents with a
+/// lower index, at most `max_parents` of them, so the graph has no cycles.
+pub fn random_dag(rng: &mut Rng, num_nodes: usize, max_parents: usize) -> ParsedGraph {
+    let unique_ids: Vec<UniqueId> = (0..num_nodes)
+        .map(|index| {
+            let resource_type = RANDOM_RESOURCE_TYPES[index % RANDOM_RESOURCE_TYPES.len()];
+            format!("{}_{}", resource_type, index)
+        })
+        .collect();
+
+    let mut builder = GraphBuilder::new();
+    for (index, unique_id) in unique_ids.iter().enumerate() {
+        let num_parents = match index {
+            0 => 0,
+            _ => rng.below(max_parents + 1),
+        };
+        let parents: HashSet<&str> = (0..num_parents)
+            .map(|_| unique_ids[rng.below(index)].as_str())
+            .collect();
+        builder = builder.node(unique_id, &parents.into_iter().collect::<Vec<_>>());
     }
+    builder.build()
 }
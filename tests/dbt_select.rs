@@ -0,0 +1,72 @@
+/// Runs the `dbt-select` binary against the jaffle_shop fixture
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+const MANIFEST: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/jaffle_shop/manifest.json"
+);
+
+fn dbt_select(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_dbt-select"))
+        .args(["--manifest", MANIFEST])
+        .args(args)
+        .output()
+        .expect("Could not run dbt-select")
+}
+
+#[test]
+fn prints_the_selection() {
+    let output = dbt_select(&[
+        "--select",
+        "source:ecom.raw_orders+1",
+        "--resource-type",
+        "model",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "model.jaffle_shop.stg_orders\n"
+    );
+}
+
+#[test]
+fn fails_on_invalid_selectors() {
+    let output = dbt_select(&["--select", "nonexistent_method:a"]);
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error: "), "{}", stderr);
+}
+
+#[test]
+fn reads_selectors_in_the_repl() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_dbt-select"))
+        .args(["--manifest", MANIFEST, "--repl", "--resource-type", "seed"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Could not run dbt-select");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(
+            b"resource_type:seed\n:path model.jaffle_shop.orders model.jaffle_shop.customers\n",
+        )
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], "> seed.jaffle_shop.country_codes");
+    assert_eq!(
+        lines[2],
+        "> model.jaffle_shop.orders -> model.jaffle_shop.customers"
+    );
+}
@@ -0,0 +1,3 @@
+select order_items.*, orders.ordered_at
+from {{ ref('stg_order_items') }} as order_items
+join {{ ref('stg_orders') }} as orders on orders.order_id = order_items.order_id
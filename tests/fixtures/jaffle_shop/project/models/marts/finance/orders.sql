@@ -0,0 +1,10 @@
+select
+    orders.order_id,
+    orders.customer_id,
+    orders.ordered_at,
+    count(order_items.order_item_id) as item_count,
+    sum(payments.amount) as order_total
+from {{ ref('stg_orders') }} as orders
+left join {{ ref('stg_order_items') }} as order_items on order_items.order_id = orders.order_id
+left join {{ ref('stg_payments') }} as payments on payments.order_id = orders.order_id
+group by 1, 2, 3
@@ -0,0 +1,9 @@
+select
+    customers.*,
+    country_codes.country_name,
+    count(orders.order_id) as order_count
+from {{ ref('stg_customers') }} as customers
+left join {{ ref('orders') }} as orders on orders.customer_id = customers.customer_id
+left join {{ ref('country_codes') }} as country_codes
+    on country_codes.country_code = customers.country_code
+group by all
@@ -0,0 +1 @@
+select * from {{ source('ecom', 'raw_customers') }}
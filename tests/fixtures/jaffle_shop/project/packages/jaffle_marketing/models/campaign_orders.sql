@@ -0,0 +1 @@
+select * from {{ ref('jaffle_shop', 'customers') }}
@@ -0,0 +1,14 @@
+{% snapshot customers_snapshot %}
+
+{{
+    config(
+        target_schema='snapshots',
+        unique_key='customer_id',
+        strategy='timestamp',
+        updated_at='updated_at',
+    )
+}}
+
+select * from {{ ref('stg_customers') }}
+
+{% endsnapshot %}
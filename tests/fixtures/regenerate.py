@@ -0,0 +1,156 @@
+#!/usr/bin/env python3
+"""Regenerates a parity fixture from its dbt project with the installed dbt.
+
+    pip install dbt-core~=1.8.0 dbt-duckdb~=1.8.0
+    python tests/fixtures/regenerate.py tests/fixtures/jaffle_shop
+
+Rewrites the fixture's `manifest.json` and `state/manifest.json` from
+`dbt parse`, and every `expected` set in `selectors.json` from `dbt ls`,
+recording the dbt version which listed them. `state/run_results.json`,
+`state/sources.json` and `sources.json` are inputs: they stand for a previous
+run with a failing `orders` and for the current source freshness.
+"""
+
+import json
+import shutil
+import subprocess
+import sys
+import tempfile
+from pathlib import Path
+
+# Node collections `Manifest::load` reads
+COLLECTIONS = [
+    "nodes",
+    "sources",
+    "exposures",
+    "metrics",
+    "groups",
+    "semantic_models",
+    "saved_queries",
+    "unit_tests",
+]
+
+# Node fields the selector reads, the rest is dropped
+FIELDS = {
+    "access",
+    "config",
+    "depends_on",
+    "fqn",
+    "group",
+    "latest_version",
+    "model",
+    "name",
+    "original_file_path",
+    "owner",
+    "package_name",
+    "path",
+    "raw_code",
+    "resource_type",
+    "source_name",
+    "tags",
+    "test_metadata",
+    "unique_id",
+    "version",
+}
+
+# The edit between the previous run and the current one, which
+# `state:modified` selects
+PREVIOUS_STATE = {
+    "models/staging/stg_payments.sql": "select * from {{ source('ecom', 'raw_payments') }}\n"
+    "where amount is not null\n",
+}
+
+
+def dbt(project, *args):
+    """Runs dbt in `project` and returns its stdout"""
+    command = ["dbt", "--quiet", *args, "--project-dir", project, "--profiles-dir", project]
+    return subprocess.run(command, check=True, capture_output=True, text=True).stdout
+
+
+def parse(project):
+    """The trimmed-down manifest `dbt parse` writes for `project`"""
+    dbt(project, "deps")
+    dbt(project, "parse")
+    manifest = json.loads((Path(project) / "target" / "manifest.json").read_text())
+    trimmed = {
+        "metadata": {
+            key: manifest["metadata"][key]
+            for key in ["dbt_schema_version", "dbt_version", "project_name"]
+        },
+        "parent_map": manifest["parent_map"],
+        "child_map": manifest["child_map"],
+        "group_map": manifest["group_map"],
+        "selectors": manifest["selectors"],
+        "macros": {},
+        "docs": {},
+        "disabled": {},
+    }
+    for collection in COLLECTIONS:
+        trimmed[collection] = {
+            unique_id: trim(node) for unique_id, node in manifest[collection].items()
+        }
+    return trimmed
+
+
+def trim(node):
+    node = {key: value for key, value in node.items() if key in FIELDS}
+    if "depends_on" in node:
+        node["depends_on"] = {"nodes": node["depends_on"].get("nodes", [])}
+    return node
+
+
+def write_json(path, value):
+    path.write_text(json.dumps(value, indent=2, sort_keys=True) + "\n")
+
+
+def main(fixture):
+    fixture = Path(fixture).resolve()
+    version = subprocess.run(
+        ["dbt", "--version"], check=True, capture_output=True, text=True
+    ).stdout
+    dbt_version = next(
+        line.split(":")[1].strip().lstrip("- ")
+        for line in version.splitlines()
+        if line.strip().startswith("- installed:")
+    )
+
+    with tempfile.TemporaryDirectory() as tmp:
+        previous = Path(tmp) / "previous"
+        shutil.copytree(fixture / "project", previous)
+        for path, contents in PREVIOUS_STATE.items():
+            (previous / path).write_text(contents)
+        write_json(fixture / "state" / "manifest.json", parse(str(previous)))
+
+        project = Path(tmp) / "project"
+        shutil.copytree(fixture / "project", project)
+        write_json(fixture / "manifest.json", parse(str(project)))
+
+        state = Path(tmp) / "state"
+        shutil.copytree(fixture / "state", state)
+        shutil.copy(fixture / "sources.json", project / "target" / "sources.json")
+
+        selectors = json.loads((fixture / "selectors.json").read_text())
+        for case in selectors["cases"]:
+            listed = dbt(
+                str(project),
+                "ls",
+                "--select",
+                case["select"],
+                "--indirect-selection",
+                case.get("indirect_selection", "eager"),
+                "--state",
+                str(state),
+                "--output",
+                "json",
+                "--output-keys",
+                "unique_id",
+            )
+            case["expected"] = sorted(
+                json.loads(line)["unique_id"] for line in listed.splitlines() if line
+            )
+        selectors["dbt_version"] = dbt_version
+        (fixture / "selectors.json").write_text(json.dumps(selectors, indent=2) + "\n")
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
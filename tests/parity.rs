@@ -0,0 +1,231 @@
+/// Conformance suite: selects from a fixture manifest and compares the result
+/// with what `dbt ls --select <selector>` lists for the same project.
+///
+/// Each fixture directory under `tests/fixtures` holds a `manifest.json`, the
+/// `state` artifacts of a previous run and a `selectors.json` with the
+/// `dbt_version` whose `dbt ls` listed the `cases`:
+///
+/// - `select`: the `--select` value
+/// - `indirect_selection`: the `--indirect-selection` value, `eager` if unset
+/// - `expected`: the unique IDs `dbt ls` lists
+///
+/// `tests/fixtures/regenerate.py` rebuilds both from the fixture's dbt project.
+use std::{
+    collections::{BTreeSet, HashSet},
+    rc::Rc,
+};
+
+use dbt_node_selector::{
+    manifest::{Manifest, RunArtifacts},
+    parse_union, IndirectSelection, NodeSelector, PreviousState, SelectionError, UniqueId,
+};
+use serde_json::Value;
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/jaffle_shop");
+
+/// Resource types `dbt ls` lists without `--resource-type`. Analyses are left
+/// out, and groups aren't part of dbt's graph at all.
+const DBT_LS_RESOURCE_TYPES: &[&str] = &[
+    "model",
+    "snapshot",
+    "seed",
+    "test",
+    "source",
+    "exposure",
+    "metric",
+    "saved_query",
+    "semantic_model",
+    "unit_test",
+];
+
+/// Every selector method of dbt-core
+const DBT_METHODS: &[&str] = &[
+    "fqn",
+    "tag",
+    "group",
+    "access",
+    "source",
+    "path",
+    "file",
+    "package",
+    "config",
+    "test_name",
+    "test_type",
+    "resource_type",
+    "state",
+    "exposure",
+    "metric",
+    "result",
+    "source_status",
+    "wildcard",
+    "version",
+    "semantic_model",
+    "saved_query",
+    "unit_test",
+];
+
+struct Case {
+    select: String,
+    indirect_selection: String,
+    expected: BTreeSet<UniqueId>,
+}
+
+impl Case {
+    fn from_json(case: &Value) -> Self {
+        Case {
+            select: case["select"].as_str().expect("Missing select").to_string(),
+            indirect_selection: case["indirect_selection"]
+                .as_str()
+                .unwrap_or("eager")
+                .to_string(),
+            expected: case["expected"]
+                .as_array()
+                .expect("Missing expected")
+                .iter()
+                .map(|id| id.as_str().expect("Expected unique IDs").to_string())
+                .collect(),
+        }
+    }
+
+    /// The methods named by the selector's criteria
+    fn methods(&self) -> impl Iterator<Item = &str> {
+        self.select.split([' ', ',']).filter_map(|criteria| {
+            let (method, _) = criteria.trim_start_matches(['@', '+']).split_once(':')?;
+            method.split('.').next()
+        })
+    }
+
+    /// The unique IDs `dbt ls` would list for the selection
+    fn select(&self, node_selector: &NodeSelector) -> Result<BTreeSet<UniqueId>, SelectionError> {
+        let indirect_selection = IndirectSelection::from_string(&self.indirect_selection)?;
+        let selection_group = parse_union(&vec![self.select.clone()], false, indirect_selection)?;
+        let selected: HashSet<UniqueId> = node_selector.get_selected(&selection_group)?;
+        Ok(selected
+            .into_iter()
+            .filter(|unique_id| {
+                // Unique IDs are always prefixed by their resource type
+                let resource_type = unique_id.split('.').next().unwrap_or_default();
+                DBT_LS_RESOURCE_TYPES.contains(&resource_type)
+            })
+            .collect())
+    }
+}
+
+fn load_cases() -> Vec<Case> {
+    let path = format!("{}/selectors.json", FIXTURE);
+    let contents = std::fs::read_to_string(&path).expect("Could not read selectors.json");
+    let selectors: Value = serde_json::from_str(&contents).expect("Could not parse selectors.json");
+    selectors["cases"]
+        .as_array()
+        .expect("selectors.json must have a list of cases")
+        .iter()
+        .map(Case::from_json)
+        .collect()
+}
+
+fn load_manifest(path: &str) -> Manifest {
+    Manifest::load(format!("{}/{}", FIXTURE, path)).expect("Invalid manifest")
+}
+
+fn load_artifacts(path: &str) -> RunArtifacts {
+    RunArtifacts::load_beside(format!("{}/{}", FIXTURE, path)).expect("Invalid run artifacts")
+}
+
+/// The selector for the fixture's manifest, with the artifacts in `state` as
+/// its previous state, as `dbt ls --state state` would see them
+fn load_node_selector() -> NodeSelector {
+    let previous = load_manifest("state/manifest.json");
+    let manifest = load_manifest("manifest.json");
+    let previous =
+        NodeSelector::from(previous.nodes, previous.edges, None).expect("Invalid manifest");
+    let mut node_selector =
+        NodeSelector::from(manifest.nodes, manifest.edges, None).expect("Invalid manifest");
+    let artifacts = load_artifacts("state/manifest.json");
+    let previous_state = PreviousState {
+        graph: Some(previous.graph),
+        run_results: artifacts.run_results,
+        sources: artifacts.sources,
+        sources_current: load_artifacts("manifest.json").sources,
+    };
+    node_selector.set_previous_state(Some(Rc::new(previous_state)));
+    node_selector
+}
+
+#[test]
+fn manifest_loads_every_node() {
+    let manifest = load_manifest("manifest.json");
+    let resource_types: HashSet<&str> = manifest
+        .nodes
+        .iter()
+        .filter_map(|node| node.unique_id.split('.').next())
+        .collect();
+
+    assert_eq!(manifest.nodes.len(), 32);
+    assert_eq!(
+        resource_types,
+        HashSet::from([
+            "source",
+            "seed",
+            "model",
+            "snapshot",
+            "analysis",
+            "test",
+            "unit_test",
+            "semantic_model",
+            "metric",
+            "saved_query",
+            "exposure",
+            "group",
+        ])
+    );
+    assert_eq!(load_node_selector().graph.node_map.len(), 32);
+}
+
+#[test]
+fn selectors_match_dbt_ls() {
+    let node_selector = load_node_selector();
+    let cases = load_cases();
+
+    let failures: Vec<String> = cases
+        .iter()
+        .filter_map(|case| {
+            let expected = &case.expected;
+            let label = format!("{} ({})", case.select, case.indirect_selection);
+            match case.select(&node_selector) {
+                Err(err) => Some(format!("{}: {:?}", label, err)),
+                Ok(selected) if selected == *expected => None,
+                Ok(selected) => Some(format!(
+                    "{}:\n    missing: {:?}\n    unexpected: {:?}",
+                    label,
+                    expected.difference(&selected).collect::<Vec<_>>(),
+                    selected.difference(expected).collect::<Vec<_>>(),
+                )),
+            }
+        })
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} cases differ from dbt ls:\n{}",
+        failures.len(),
+        cases.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn every_method_is_covered() {
+    let cases = load_cases();
+
+    let uncovered: Vec<&str> = DBT_METHODS
+        .iter()
+        .copied()
+        .filter(|method| {
+            !cases
+                .iter()
+                .any(|case| case.methods().any(|used| used == *method))
+        })
+        .collect();
+
+    assert!(uncovered.is_empty(), "No cases for {:?}", uncovered);
+}
@@ -0,0 +1,86 @@
+/// Selects through the re-exported Rust API only, as a host linking the crate
+/// would
+use std::collections::HashSet;
+
+use dbt_node_selector::{
+    parse_union, AccessType, Edge, IndirectSelection, ModelNode, Node, NodeSelector, NodeType,
+    TestNode, UniqueId,
+};
+
+fn node(unique_id: &str, node_type: NodeType, depends_on: &[&str]) -> Node {
+    Node {
+        unique_id: unique_id.to_string(),
+        depends_on: depends_on.iter().map(|id| id.to_string()).collect(),
+        name: unique_id.rsplit('.').next().unwrap_or_default().to_string(),
+        package_name: "pkg".to_string(),
+        path: format!("{}.sql", unique_id),
+        original_file_path: format!("models/{}.sql", unique_id),
+        node_type,
+        config: vec![],
+        tags: vec![],
+    }
+}
+
+fn model(name: &str) -> NodeType {
+    NodeType::Model(ModelNode {
+        fqn: vec!["pkg".to_string(), name.to_string()],
+        depends_on: vec![],
+        raw_code: "".to_string(),
+        access: AccessType::Protected,
+        version: None,
+        latest_version: None,
+    })
+}
+
+fn get_node_selector() -> NodeSelector {
+    let nodes = vec![
+        node("model.pkg.stg_orders", model("stg_orders"), &[]),
+        node(
+            "model.pkg.orders",
+            model("orders"),
+            &["model.pkg.stg_orders"],
+        ),
+        node(
+            "test.pkg.not_null_orders_id",
+            NodeType::Test(TestNode {
+                fqn: vec!["pkg".to_string(), "not_null_orders_id".to_string()],
+                raw_code: "".to_string(),
+                test_metadata_name: Some("not_null".to_string()),
+            }),
+            &["model.pkg.orders"],
+        ),
+    ];
+    let edges = nodes
+        .iter()
+        .map(|node| Edge {
+            unique_id: node.unique_id.clone(),
+            parents: node.depends_on.clone(),
+        })
+        .collect();
+    NodeSelector::from(nodes, edges, None).expect("Invalid graph")
+}
+
+fn select(selector: &str, indirect_selection: IndirectSelection) -> HashSet<UniqueId> {
+    let selection_group = parse_union(&vec![selector.to_string()], false, indirect_selection)
+        .expect("Invalid selector");
+    get_node_selector()
+        .get_selected(&selection_group)
+        .expect("Selection failed")
+}
+
+#[test]
+fn selects_through_the_rust_api() {
+    let expected = HashSet::from([
+        "model.pkg.stg_orders".to_string(),
+        "model.pkg.orders".to_string(),
+    ]);
+
+    assert_eq!(select("+orders", IndirectSelection::Empty), expected);
+}
+
+#[test]
+fn indirect_selection_adds_tests() {
+    let selected = select("orders", IndirectSelection::Eager);
+
+    assert!(selected.contains("test.pkg.not_null_orders_id"));
+}